@@ -70,7 +70,9 @@ fn main() {
                 .b(),
             ),
             PositionBox::new(
-                Elevate::new(Blob::new(200., 200., 0xFF88FF).b(), 10).b(),
+                Elevate::new(Blob::new(200., 200., 0xFF88FF).b(), 10)
+                    .shadow()
+                    .b(),
                 140.,
                 40.,
             )
@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+pub struct FrameClock {
+    start: Instant,
+    last_frame: Instant,
+    elapsed: Duration,
+    delta: Duration,
+    frame: u64,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameClock {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            last_frame: now,
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
+            frame: 0,
+        }
+    }
+
+    // Starts a new frame at the current time
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        self.delta = now - self.last_frame;
+        self.elapsed = now - self.start;
+        self.last_frame = now;
+        self.frame += 1;
+    }
+
+    // Starts a new frame `delta` after the previous one, without looking at the wall clock
+    pub fn advance(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.elapsed += delta;
+        self.last_frame += delta;
+        self.frame += 1;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+}
@@ -0,0 +1,253 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use super::scheduler;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnimationStatus {
+    Dismissed,
+    Forward,
+    Reverse,
+    Completed,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum RepeatMode {
+    Once,
+    Loop,
+    PingPong,
+}
+
+pub(super) struct ControllerState {
+    value: f32,
+    duration: Duration,
+    reverse_duration: Option<Duration>,
+    status: AnimationStatus,
+    repeat: RepeatMode,
+    animating: bool,
+}
+
+impl ControllerState {
+    pub(super) fn is_animating(&self) -> bool {
+        self.animating
+    }
+
+    // Advances the animation by `delta`, returns whether it is still running
+    pub(super) fn tick(&mut self, delta: Duration) -> bool {
+        if !self.animating {
+            return false;
+        }
+        let duration = match self.status {
+            AnimationStatus::Reverse => self.reverse_duration.unwrap_or(self.duration),
+            _ => self.duration,
+        };
+        let step = if duration.is_zero() {
+            1.0
+        } else {
+            delta.as_secs_f32() / duration.as_secs_f32()
+        };
+        match self.status {
+            AnimationStatus::Forward => self.value += step,
+            AnimationStatus::Reverse => self.value -= step,
+            _ => {}
+        }
+        // tolerate the rounding error accumulated over many small steps
+        if self.value >= 1.0 - f32::EPSILON * 8.0 {
+            match self.repeat {
+                RepeatMode::Once => {
+                    self.value = 1.0;
+                    self.status = AnimationStatus::Completed;
+                    self.animating = false;
+                }
+                RepeatMode::Loop => self.value = self.value.fract(),
+                RepeatMode::PingPong => {
+                    self.value = (2.0 - self.value).max(0.0);
+                    self.status = AnimationStatus::Reverse;
+                }
+            }
+        } else if self.value <= f32::EPSILON * 8.0 {
+            match self.repeat {
+                RepeatMode::Once | RepeatMode::Loop => {
+                    self.value = 0.0;
+                    self.status = AnimationStatus::Dismissed;
+                    self.animating = false;
+                }
+                RepeatMode::PingPong => {
+                    self.value = (-self.value).min(1.0);
+                    self.status = AnimationStatus::Forward;
+                }
+            }
+        }
+        self.animating
+    }
+}
+
+// Handle to a shared animation state: clones drive the same animation.
+#[derive(Clone)]
+pub struct AnimationController {
+    state: Rc<RefCell<ControllerState>>,
+}
+
+impl AnimationController {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ControllerState {
+                value: 0.0,
+                duration,
+                reverse_duration: None,
+                status: AnimationStatus::Dismissed,
+                repeat: RepeatMode::Once,
+                animating: false,
+            })),
+        }
+    }
+
+    pub fn reverse_duration(self, reverse_duration: Duration) -> Self {
+        self.state.borrow_mut().reverse_duration = Some(reverse_duration);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.state.borrow().value
+    }
+
+    pub fn set_value(&self, value: f32) {
+        let mut state = self.state.borrow_mut();
+        state.value = value.clamp(0.0, 1.0);
+        state.animating = false;
+        state.status = if state.value == 1.0 {
+            AnimationStatus::Completed
+        } else {
+            AnimationStatus::Dismissed
+        };
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.state.borrow().duration
+    }
+
+    pub fn set_duration(&self, duration: Duration) {
+        self.state.borrow_mut().duration = duration;
+    }
+
+    pub fn status(&self) -> AnimationStatus {
+        self.state.borrow().status
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.state.borrow().animating
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.status() == AnimationStatus::Completed
+    }
+
+    pub fn is_dismissed(&self) -> bool {
+        self.status() == AnimationStatus::Dismissed
+    }
+
+    fn start(&self, status: AnimationStatus, repeat: RepeatMode) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.status = status;
+            state.repeat = repeat;
+            state.animating = true;
+        }
+        scheduler::schedule(&self.state);
+    }
+
+    pub fn forward(&self) {
+        self.start(AnimationStatus::Forward, RepeatMode::Once);
+    }
+
+    pub fn forward_from(&self, from: f32) {
+        self.state.borrow_mut().value = from.clamp(0.0, 1.0);
+        self.forward();
+    }
+
+    pub fn reverse(&self) {
+        self.start(AnimationStatus::Reverse, RepeatMode::Once);
+    }
+
+    pub fn reverse_from(&self, from: f32) {
+        self.state.borrow_mut().value = from.clamp(0.0, 1.0);
+        self.reverse();
+    }
+
+    // Runs forward forever, either jumping back to 0 or bouncing back and forth
+    pub fn repeat(&self, reverse: bool) {
+        let mode = if reverse {
+            RepeatMode::PingPong
+        } else {
+            RepeatMode::Loop
+        };
+        self.start(AnimationStatus::Forward, mode);
+    }
+
+    pub fn stop(&self) {
+        self.state.borrow_mut().animating = false;
+    }
+
+    pub fn reset(&self) {
+        self.set_value(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{scheduler, FrameClock};
+
+    fn run(clock: &mut FrameClock, millis: u64) {
+        clock.advance(Duration::from_millis(millis));
+        scheduler::tick(clock);
+    }
+
+    #[test]
+    fn forward_reaches_completed() {
+        let mut clock = FrameClock::new();
+        let controller = AnimationController::new(Duration::from_millis(100));
+        controller.forward();
+        assert!(scheduler::has_scheduled_frame());
+        run(&mut clock, 50);
+        assert!((controller.value() - 0.5).abs() < 1e-4);
+        assert_eq!(controller.status(), AnimationStatus::Forward);
+        run(&mut clock, 60);
+        assert_eq!(controller.value(), 1.0);
+        assert!(controller.is_completed());
+        assert!(!controller.is_animating());
+    }
+
+    #[test]
+    fn reverse_uses_the_reverse_duration() {
+        let mut clock = FrameClock::new();
+        let controller = AnimationController::new(Duration::from_millis(100))
+            .reverse_duration(Duration::from_millis(200));
+        controller.reverse_from(1.0);
+        run(&mut clock, 100);
+        assert!((controller.value() - 0.5).abs() < 1e-4);
+        run(&mut clock, 100);
+        assert!(controller.is_dismissed());
+    }
+
+    #[test]
+    fn ping_pong_turns_around() {
+        let mut clock = FrameClock::new();
+        let controller = AnimationController::new(Duration::from_millis(100));
+        controller.repeat(true);
+        run(&mut clock, 125);
+        assert!((controller.value() - 0.75).abs() < 1e-4);
+        assert_eq!(controller.status(), AnimationStatus::Reverse);
+        controller.stop();
+        run(&mut clock, 50);
+        assert!((controller.value() - 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn zero_duration_completes_in_one_frame() {
+        let mut clock = FrameClock::new();
+        let controller = AnimationController::new(Duration::ZERO);
+        controller.forward();
+        run(&mut clock, 16);
+        assert!(controller.is_completed());
+    }
+}
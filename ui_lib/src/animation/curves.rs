@@ -0,0 +1,146 @@
+use std::f32::consts::PI;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Curve {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    FastOutSlowIn,
+    Decelerate,
+    BounceOut,
+    ElasticOut,
+    // Cubic bezier going from (0, 0) to (1, 1) with control points (a, b) and (c, d)
+    Cubic(f32, f32, f32, f32),
+    Interval(f32, f32),
+}
+
+impl Curve {
+    pub fn transform(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        if t == 0.0 || t == 1.0 {
+            return t;
+        }
+        match *self {
+            Self::Linear => t,
+            Self::EaseIn => cubic(0.42, 0.0, 1.0, 1.0, t),
+            Self::EaseOut => cubic(0.0, 0.0, 0.58, 1.0, t),
+            Self::EaseInOut => cubic(0.42, 0.0, 0.58, 1.0, t),
+            Self::FastOutSlowIn => cubic(0.4, 0.0, 0.2, 1.0, t),
+            Self::Decelerate => {
+                let t = 1.0 - t;
+                1.0 - t * t
+            }
+            Self::BounceOut => bounce(t),
+            Self::ElasticOut => {
+                let period = 0.4;
+                let s = period / 4.0;
+                2f32.powf(-10.0 * t) * ((t - s) * (PI * 2.0) / period).sin() + 1.0
+            }
+            Self::Cubic(a, b, c, d) => cubic(a, b, c, d, t),
+            // an empty interval jumps from 0 to 1 at its end
+            Self::Interval(begin, end) if end <= begin => {
+                if t < end {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            Self::Interval(begin, end) => ((t - begin) / (end - begin)).clamp(0.0, 1.0),
+        }
+    }
+}
+
+fn evaluate_cubic(a: f32, b: f32, m: f32) -> f32 {
+    3.0 * a * (1.0 - m) * (1.0 - m) * m + 3.0 * b * (1.0 - m) * m * m + m * m * m
+}
+
+fn cubic(a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+    // bisection on the x coordinate, then evaluate y
+    let mut start = 0f32;
+    let mut end = 1f32;
+    let mut midpoint = 0.5;
+    for _ in 0..32 {
+        midpoint = (start + end) / 2.0;
+        let estimate = evaluate_cubic(a, c, midpoint);
+        if (t - estimate).abs() < 0.001 {
+            break;
+        }
+        if estimate < t {
+            start = midpoint;
+        } else {
+            end = midpoint;
+        }
+    }
+    evaluate_cubic(b, d, midpoint)
+}
+
+fn bounce(t: f32) -> f32 {
+    if t < 1.0 / 2.75 {
+        7.5625 * t * t
+    } else if t < 2.0 / 2.75 {
+        let t = t - 1.5 / 2.75;
+        7.5625 * t * t + 0.75
+    } else if t < 2.5 / 2.75 {
+        let t = t - 2.25 / 2.75;
+        7.5625 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / 2.75;
+        7.5625 * t * t + 0.984375
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVES: [Curve; 10] = [
+        Curve::Linear,
+        Curve::EaseIn,
+        Curve::EaseOut,
+        Curve::EaseInOut,
+        Curve::FastOutSlowIn,
+        Curve::Decelerate,
+        Curve::BounceOut,
+        Curve::ElasticOut,
+        Curve::Cubic(0.25, 0.1, 0.25, 1.0),
+        Curve::Interval(0.25, 0.75),
+    ];
+
+    #[test]
+    fn curves_start_at_0_and_end_at_1() {
+        for curve in CURVES {
+            assert_eq!(curve.transform(0.0), 0.0, "{curve:?}");
+            assert_eq!(curve.transform(1.0), 1.0, "{curve:?}");
+            assert_eq!(curve.transform(-1.0), 0.0, "{curve:?}");
+            assert_eq!(curve.transform(2.0), 1.0, "{curve:?}");
+        }
+    }
+
+    #[test]
+    fn ease_in_out_is_symmetric() {
+        let curve = Curve::EaseInOut;
+        assert!((curve.transform(0.5) - 0.5).abs() < 0.01);
+        assert!(curve.transform(0.25) < 0.25);
+        assert!(curve.transform(0.75) > 0.75);
+    }
+
+    #[test]
+    fn interval_maps_its_range() {
+        let curve = Curve::Interval(0.25, 0.75);
+        assert_eq!(curve.transform(0.2), 0.0);
+        assert_eq!(curve.transform(0.5), 0.5);
+        assert_eq!(curve.transform(0.8), 1.0);
+    }
+
+    #[test]
+    fn empty_interval_is_a_step_at_its_end() {
+        let curve = Curve::Interval(0.5, 0.5);
+        assert_eq!(curve.transform(0.4), 0.0);
+        assert_eq!(curve.transform(0.5), 1.0);
+        assert_eq!(curve.transform(0.6), 1.0);
+        let backwards = Curve::Interval(0.6, 0.4);
+        assert_eq!(backwards.transform(0.3), 0.0);
+        assert_eq!(backwards.transform(0.5), 1.0);
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use crate::types::ValueNotifier;
+
+use super::{AnimationController, Curve, Lerp, Tween};
+
+// Follows a target value: whenever the target changes, animates from the value currently
+// displayed to the new target instead of jumping to it.
+pub struct ImplicitAnimation<T: Lerp + PartialEq> {
+    target: ValueNotifier<T>,
+    version: u64,
+    tween: Tween<T>,
+    curve: Curve,
+    controller: AnimationController,
+    // controller value the last time `value` was read
+    read_at: Option<f32>,
+}
+
+impl<T: Lerp + PartialEq> ImplicitAnimation<T> {
+    pub fn new(target: ValueNotifier<T>, duration: Duration, curve: Curve) -> Self {
+        let value = target.get();
+        let controller = AnimationController::new(duration);
+        controller.set_value(1.0);
+        Self {
+            version: target.version(),
+            target,
+            tween: Tween::new(value.clone(), value),
+            curve,
+            controller,
+            read_at: None,
+        }
+    }
+
+    pub fn value(&mut self) -> T {
+        if self.target.version() != self.version {
+            self.version = self.target.version();
+            let end = self.target.get();
+            if end != self.tween.end {
+                let current = self.tween.evaluate(&self.controller);
+                self.tween = Tween::new(current, end).curve(self.curve);
+                self.controller.forward_from(0.0);
+            }
+        }
+        self.read_at = Some(self.controller.value());
+        self.tween.evaluate(&self.controller)
+    }
+
+    // Whether `value` would give something new since it was last read
+    pub fn has_changed(&self) -> bool {
+        self.target.version() != self.version || self.read_at != Some(self.controller.value())
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.controller.is_animating()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{scheduler, FrameClock};
+
+    #[test]
+    fn starts_at_the_target_without_animating() {
+        let mut animation = ImplicitAnimation::new(
+            ValueNotifier::new(5.0f32),
+            Duration::from_millis(100),
+            Curve::Linear,
+        );
+        assert_eq!(animation.value(), 5.0);
+        assert!(!animation.is_animating());
+        assert!(!animation.has_changed());
+    }
+
+    #[test]
+    fn animates_from_the_displayed_value_to_a_new_target() {
+        let mut clock = FrameClock::new();
+        let target = ValueNotifier::new(0.0f32);
+        let mut animation =
+            ImplicitAnimation::new(target.clone(), Duration::from_millis(100), Curve::Linear);
+        animation.value();
+        target.set(10.0);
+        assert!(animation.has_changed());
+        assert_eq!(animation.value(), 0.0);
+        assert!(animation.is_animating());
+
+        clock.advance(Duration::from_millis(50));
+        scheduler::tick(&clock);
+        assert!(animation.has_changed());
+        assert!((animation.value() - 5.0).abs() < 1e-3);
+
+        // retargeting halfway starts again from the value on screen
+        target.set(0.0);
+        assert!((animation.value() - 5.0).abs() < 1e-3);
+        clock.advance(Duration::from_millis(100));
+        scheduler::tick(&clock);
+        assert_eq!(animation.value(), 0.0);
+        assert!(!animation.is_animating());
+    }
+
+    #[test]
+    fn setting_the_same_target_does_not_restart() {
+        let target = ValueNotifier::new(3.0f32);
+        let mut animation =
+            ImplicitAnimation::new(target.clone(), Duration::from_millis(100), Curve::Linear);
+        animation.value();
+        target.set(3.0);
+        assert_eq!(animation.value(), 3.0);
+        assert!(!animation.is_animating());
+    }
+}
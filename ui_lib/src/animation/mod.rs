@@ -0,0 +1,12 @@
+mod clock;
+mod controller;
+mod curves;
+mod implicit;
+pub mod scheduler;
+mod tween;
+
+pub use clock::FrameClock;
+pub use controller::{AnimationController, AnimationStatus};
+pub use curves::Curve;
+pub use implicit::ImplicitAnimation;
+pub use tween::{Lerp, Tween};
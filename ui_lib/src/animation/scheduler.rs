@@ -0,0 +1,45 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use super::{controller::ControllerState, FrameClock};
+
+// Controllers register here when they start and are dropped from the list once they
+// stop, so the frame loop only has to keep running while something is animating.
+thread_local! {
+    static ACTIVE: RefCell<Vec<Weak<RefCell<ControllerState>>>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(super) fn schedule(state: &Rc<RefCell<ControllerState>>) {
+    ACTIVE.with(|active| {
+        let mut active = active.borrow_mut();
+        if !active
+            .iter()
+            .any(|s| std::ptr::eq(s.as_ptr(), Rc::as_ptr(state)))
+        {
+            active.push(Rc::downgrade(state));
+        }
+    });
+}
+
+pub fn has_scheduled_frame() -> bool {
+    ACTIVE.with(|active| !active.borrow().is_empty())
+}
+
+// Advances every running controller to the clock's current frame.
+// Returns whether any animation value changed.
+pub fn tick(clock: &FrameClock) -> bool {
+    ACTIVE.with(|active| {
+        let mut changed = false;
+        active.borrow_mut().retain(|s| match s.upgrade() {
+            Some(s) => {
+                let mut s = s.borrow_mut();
+                changed |= s.is_animating();
+                s.tick(clock.delta())
+            }
+            None => false,
+        });
+        changed
+    })
+}
@@ -0,0 +1,117 @@
+use crate::types::{Alignment, BoxConstraints, Color, Insets, Position, Size};
+
+use super::{AnimationController, Curve};
+
+pub trait Lerp: Clone {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        a + (b - a) * t
+    }
+}
+
+impl Lerp for Position {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        Position::lerp(a, b, t)
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        Size::lerp(a, b, t)
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        Color::lerp(a, b, t)
+    }
+}
+
+impl Lerp for BoxConstraints {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        BoxConstraints::lerp(a, b, t)
+    }
+}
+
+impl Lerp for Alignment {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        Alignment::lerp(a, b, t)
+    }
+}
+
+impl Lerp for Insets {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        Insets::lerp(a, b, t)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Tween<T: Lerp> {
+    pub begin: T,
+    pub end: T,
+    curve: Curve,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(begin: T, end: T) -> Self {
+        Self {
+            begin,
+            end,
+            curve: Curve::Linear,
+        }
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn transform(&self, t: f32) -> T {
+        let t = self.curve.transform(t);
+        if t == 0.0 {
+            return self.begin.clone();
+        }
+        if t == 1.0 {
+            return self.end.clone();
+        }
+        T::lerp(&self.begin, &self.end, t)
+    }
+
+    pub fn evaluate(&self, controller: &AnimationController) -> T {
+        self.transform(controller.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tween_ends_exactly_on_its_bounds() {
+        let tween = Tween::new(10.0f32, 20.0).curve(Curve::EaseIn);
+        assert_eq!(tween.transform(0.0), 10.0);
+        assert_eq!(tween.transform(1.0), 20.0);
+        let linear = Tween::new(10.0f32, 20.0);
+        assert_eq!(linear.transform(0.25), 12.5);
+    }
+
+    #[test]
+    fn constraints_jump_to_and_from_infinite_bounds() {
+        let a = BoxConstraints::tight_for(100.0, 100.0);
+        let b = BoxConstraints::default();
+        let half = BoxConstraints::lerp(&a, &b, 0.25);
+        assert_eq!(half.max_width, 100.0);
+        assert!(!half.min_width.is_nan());
+        assert_eq!(BoxConstraints::lerp(&a, &b, 0.75).max_width, f32::INFINITY);
+        assert_eq!(BoxConstraints::lerp(&b, &a, 0.75).max_width, 100.0);
+    }
+
+    #[test]
+    fn colors_lerp_per_channel() {
+        let c = Color::lerp(&Color::argb(0, 0, 100, 255), &Color::WHITE, 0.5);
+        assert_eq!(c, Color::argb(128, 128, 178, 255));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{cell::RefCell, rc::Rc};
+
+// Where copied text goes. minifb has no access to the system clipboard, so the library
+// ships an in-memory one and lets applications plug their own.
+pub trait Clipboard {
+    fn get_text(&self) -> Option<String>;
+    fn set_text(&mut self, text: String);
+}
+
+#[derive(Default)]
+pub struct InMemoryClipboard {
+    text: Option<String>,
+}
+
+impl InMemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Clipboard for InMemoryClipboard {
+    fn get_text(&self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
+
+pub type SharedClipboard = Rc<RefCell<dyn Clipboard>>;
+
+thread_local! {
+    static DEFAULT_CLIPBOARD: SharedClipboard = Rc::new(RefCell::new(InMemoryClipboard::new()));
+}
+
+// The clipboard used by text fields that weren't given one
+pub fn default_clipboard() -> SharedClipboard {
+    DEFAULT_CLIPBOARD.with(|c| c.clone())
+}
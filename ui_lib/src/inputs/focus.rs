@@ -0,0 +1,307 @@
+use std::{cell::Cell, rc::Rc};
+
+use crate::types::{mark_pending_changes, Rect};
+
+// Scope id of the focusables that aren't inside any `FocusScope`
+pub const ROOT_SCOPE: usize = 0;
+
+thread_local! {
+    static NEXT_ID: Cell<usize> = const { Cell::new(ROOT_SCOPE + 1) };
+    static PRIMARY_FOCUS: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+pub fn primary_focus() -> Option<usize> {
+    PRIMARY_FOCUS.with(|f| f.get())
+}
+
+pub fn has_primary_focus() -> bool {
+    primary_focus().is_some()
+}
+
+fn set_primary_focus(id: Option<usize>) {
+    if PRIMARY_FOCUS.with(|f| f.replace(id)) != id {
+        // the focus ring moved, the frame has to be redrawn
+        mark_pending_changes();
+    }
+}
+
+struct FocusNodeState {
+    id: usize,
+    is_scope: bool,
+    can_request_focus: Cell<bool>,
+    skip_traversal: Cell<bool>,
+    // for scopes, the descendant that had the focus last
+    last_focused: Cell<Option<usize>>,
+}
+
+// Handle on a focusable element, owned by the widget that wants the keyboard and kept by
+// user code to move the focus around.
+#[derive(Clone)]
+pub struct FocusNode {
+    state: Rc<FocusNodeState>,
+}
+
+impl Default for FocusNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FocusNode {
+    fn with_kind(is_scope: bool) -> Self {
+        let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+        Self {
+            state: Rc::new(FocusNodeState {
+                id,
+                is_scope,
+                can_request_focus: Cell::new(true),
+                skip_traversal: Cell::new(false),
+                last_focused: Cell::new(None),
+            }),
+        }
+    }
+
+    pub fn new() -> Self {
+        Self::with_kind(false)
+    }
+
+    // Groups the nodes below it: Tab only cycles between the nodes of the focused scope
+    pub fn scope() -> Self {
+        Self::with_kind(true)
+    }
+
+    pub fn id(&self) -> usize {
+        self.state.id
+    }
+
+    pub fn is_scope(&self) -> bool {
+        self.state.is_scope
+    }
+
+    pub fn can_request_focus(&self) -> bool {
+        self.state.can_request_focus.get()
+    }
+
+    pub fn set_can_request_focus(&self, value: bool) {
+        self.state.can_request_focus.set(value);
+        if !value && self.has_focus() {
+            self.unfocus();
+        }
+    }
+
+    pub fn skip_traversal(&self) -> bool {
+        self.state.skip_traversal.get()
+    }
+
+    pub fn set_skip_traversal(&self, value: bool) {
+        self.state.skip_traversal.set(value);
+    }
+
+    // A scope passes the focus on to its last focused descendant, or to its first one once
+    // the next frame has been laid out
+    pub fn request_focus(&self) {
+        if !self.can_request_focus() {
+            return;
+        }
+        match self.state.last_focused.get() {
+            Some(id) if self.is_scope() => set_primary_focus(Some(id)),
+            _ => set_primary_focus(Some(self.id())),
+        }
+    }
+
+    pub fn unfocus(&self) {
+        if self.has_focus() {
+            set_primary_focus(None);
+        }
+    }
+
+    pub fn has_focus(&self) -> bool {
+        primary_focus() == Some(self.id())
+    }
+}
+
+// A focusable element as it was painted in the last frame
+#[derive(Clone)]
+pub struct FocusEntry {
+    pub node: FocusNode,
+    pub scope: usize,
+    pub rect: Rect,
+}
+
+// Sorts top to bottom, then left to right within a line: a line is the vertical band
+// covered by the elements that overlap it, so a tall element can share a line with
+// several short ones
+fn sort_reading_order(nodes: &mut Vec<&FocusEntry>) {
+    nodes.sort_by(|a, b| a.rect.y.total_cmp(&b.rect.y));
+    let mut line = 0;
+    let mut band_bottom = f32::NEG_INFINITY;
+    let mut lines = Vec::with_capacity(nodes.len());
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 && node.rect.y >= band_bottom {
+            line += 1;
+            band_bottom = f32::NEG_INFINITY;
+        }
+        band_bottom = band_bottom.max(node.rect.bottom());
+        lines.push(line);
+    }
+    let mut keyed: Vec<_> = lines.into_iter().zip(nodes.drain(..)).collect();
+    keyed.sort_by(|(la, a), (lb, b)| la.cmp(lb).then(a.rect.x.total_cmp(&b.rect.x)));
+    nodes.extend(keyed.into_iter().map(|(_, node)| node));
+}
+
+fn traversable(entries: &[FocusEntry], scope: usize) -> Vec<&FocusEntry> {
+    let mut nodes: Vec<&FocusEntry> = entries
+        .iter()
+        .filter(|e| e.scope == scope && e.node.can_request_focus() && !e.node.skip_traversal())
+        .collect();
+    sort_reading_order(&mut nodes);
+    nodes
+}
+
+// Gives the focus to `entry`, entering it if it is a scope
+fn focus_entry(entries: &[FocusEntry], entry: &FocusEntry) {
+    if !entry.node.is_scope() {
+        entry.node.request_focus();
+        return;
+    }
+    let last = entry.node.state.last_focused.get();
+    let nodes = traversable(entries, entry.node.id());
+    match nodes
+        .iter()
+        .find(|e| Some(e.node.id()) == last)
+        .or(nodes.first())
+    {
+        Some(e) => focus_entry(entries, e),
+        None => entry.node.request_focus(),
+    }
+}
+
+// Moves the focus to the next (or previous) element of the focused scope in reading order
+pub fn traverse(entries: &[FocusEntry], forward: bool) {
+    let current = entries
+        .iter()
+        .find(|e| Some(e.node.id()) == primary_focus());
+    let scope = current.map_or(ROOT_SCOPE, |e| e.scope);
+    let nodes = traversable(entries, scope);
+    if nodes.is_empty() {
+        return;
+    }
+    let index = current.and_then(|c| nodes.iter().position(|e| e.node.id() == c.node.id()));
+    let next = match (index, forward) {
+        (Some(i), true) => (i + 1) % nodes.len(),
+        (Some(i), false) => (i + nodes.len() - 1) % nodes.len(),
+        (None, true) => 0,
+        (None, false) => nodes.len() - 1,
+    };
+    focus_entry(entries, nodes[next]);
+}
+
+// Called after every frame: resolves focus given to a scope and remembers, in each scope,
+// which of its descendants is focused.
+pub fn settle(entries: &[FocusEntry]) {
+    let Some(current) = entries
+        .iter()
+        .find(|e| Some(e.node.id()) == primary_focus())
+    else {
+        return;
+    };
+    if current.node.is_scope() {
+        focus_entry(entries, &current.clone());
+        return;
+    }
+    let mut scope = current.scope;
+    let mut id = current.node.id();
+    while let Some(s) = entries.iter().find(|e| e.node.id() == scope) {
+        s.node.state.last_focused.set(Some(id));
+        id = scope;
+        scope = s.scope;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(x: f32, y: f32, w: f32, h: f32) -> FocusEntry {
+        FocusEntry {
+            node: FocusNode::new(),
+            scope: ROOT_SCOPE,
+            rect: Rect::new(x, y, w, h),
+        }
+    }
+
+    fn order(entries: &[FocusEntry]) -> Vec<usize> {
+        traversable(entries, ROOT_SCOPE)
+            .iter()
+            .map(|e| {
+                entries
+                    .iter()
+                    .position(|o| o.node.id() == e.node.id())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reading_order_groups_overlapping_rects_into_lines() {
+        // a tall field on the left shares its line with two short buttons stacked on the
+        // right, which don't overlap each other
+        let entries = [
+            entry(200.0, 30.0, 50.0, 20.0),
+            entry(0.0, 0.0, 100.0, 60.0),
+            entry(200.0, 0.0, 50.0, 20.0),
+            entry(100.0, 10.0, 50.0, 40.0),
+            entry(0.0, 80.0, 50.0, 20.0),
+            entry(f32::NAN, 120.0, 50.0, 20.0),
+        ];
+        assert_eq!(order(&entries), vec![1, 3, 2, 0, 4, 5]);
+    }
+
+    #[test]
+    fn traverse_wraps_around_and_skips_unfocusable_nodes() {
+        let entries = [
+            entry(0.0, 0.0, 10.0, 10.0),
+            entry(20.0, 0.0, 10.0, 10.0),
+            entry(40.0, 0.0, 10.0, 10.0),
+        ];
+        entries[1].node.set_skip_traversal(true);
+        traverse(&entries, true);
+        assert!(entries[0].node.has_focus());
+        traverse(&entries, true);
+        assert!(entries[2].node.has_focus());
+        traverse(&entries, true);
+        assert!(entries[0].node.has_focus());
+        traverse(&entries, false);
+        assert!(entries[2].node.has_focus());
+        entries[2].node.unfocus();
+        assert!(!has_primary_focus());
+    }
+
+    #[test]
+    fn scopes_remember_their_last_focused_descendant() {
+        let scope = FocusNode::scope();
+        let outside = entry(0.0, 0.0, 10.0, 10.0);
+        let scoped = |x| FocusEntry {
+            scope: scope.id(),
+            ..entry(x, 20.0, 10.0, 10.0)
+        };
+        let entries = [
+            outside,
+            FocusEntry {
+                node: scope.clone(),
+                scope: ROOT_SCOPE,
+                rect: Rect::new(0.0, 20.0, 100.0, 10.0),
+            },
+            scoped(0.0),
+            scoped(50.0),
+        ];
+        entries[3].node.request_focus();
+        settle(&entries);
+        entries[0].node.request_focus();
+        scope.request_focus();
+        assert!(entries[3].node.has_focus());
+        // Tab stays inside the focused scope
+        traverse(&entries, true);
+        assert!(entries[2].node.has_focus());
+    }
+}
@@ -0,0 +1,387 @@
+// Keys are named after their position on a US keyboard
+macro_rules! physical_keys {
+    ($($key:ident),* $(,)?) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum PhysicalKey {
+            $($key,)*
+        }
+
+        impl From<minifb::Key> for PhysicalKey {
+            fn from(value: minifb::Key) -> Self {
+                match value {
+                    $(minifb::Key::$key => PhysicalKey::$key,)*
+                    _ => PhysicalKey::Unknown,
+                }
+            }
+        }
+    };
+}
+
+physical_keys! {
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, A, B, C, D, E, F, G, H, I, J, K, L,
+    M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, Down, Left, Right, Up, Apostrophe, Backquote, Backslash, Comma, Equal,
+    LeftBracket, Minus, Period, RightBracket, Semicolon, Slash, Backspace, Delete, End, Enter,
+    Escape, Home, Insert, Menu, PageDown, PageUp, Pause, Space, Tab, NumLock, CapsLock, ScrollLock,
+    LeftShift, RightShift, LeftCtrl, RightCtrl, NumPad0, NumPad1, NumPad2, NumPad3, NumPad4,
+    NumPad5, NumPad6, NumPad7, NumPad8, NumPad9, NumPadDot, NumPadSlash, NumPadAsterisk,
+    NumPadMinus, NumPadPlus, NumPadEnter, LeftAlt, RightAlt, LeftSuper, RightSuper, Unknown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NamedKey {
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Escape,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Shift,
+    Control,
+    Alt,
+    Meta,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    Pause,
+    ContextMenu,
+    F(u8),
+    Unidentified,
+}
+
+// What the key means, as opposed to where it is
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LogicalKey {
+    Character(char),
+    Named(NamedKey),
+}
+
+impl LogicalKey {
+    // minifb doesn't tell us about the keyboard layout, so a US layout is assumed
+    pub fn resolve(key: PhysicalKey, shift: bool) -> Self {
+        use PhysicalKey as P;
+        let pick =
+            |lower: char, upper: char| LogicalKey::Character(if shift { upper } else { lower });
+        match key {
+            P::Key0 => pick('0', ')'),
+            P::Key1 => pick('1', '!'),
+            P::Key2 => pick('2', '@'),
+            P::Key3 => pick('3', '#'),
+            P::Key4 => pick('4', '$'),
+            P::Key5 => pick('5', '%'),
+            P::Key6 => pick('6', '^'),
+            P::Key7 => pick('7', '&'),
+            P::Key8 => pick('8', '*'),
+            P::Key9 => pick('9', '('),
+            P::A
+            | P::B
+            | P::C
+            | P::D
+            | P::E
+            | P::F
+            | P::G
+            | P::H
+            | P::I
+            | P::J
+            | P::K
+            | P::L
+            | P::M
+            | P::N
+            | P::O
+            | P::P
+            | P::Q
+            | P::R
+            | P::S
+            | P::T
+            | P::U
+            | P::V
+            | P::W
+            | P::X
+            | P::Y
+            | P::Z => {
+                let c = (b'a' + (key as u8 - P::A as u8)) as char;
+                pick(c, c.to_ascii_uppercase())
+            }
+            P::Apostrophe => pick('\'', '"'),
+            P::Backquote => pick('`', '~'),
+            P::Backslash => pick('\\', '|'),
+            P::Comma => pick(',', '<'),
+            P::Equal => pick('=', '+'),
+            P::LeftBracket => pick('[', '{'),
+            P::Minus => pick('-', '_'),
+            P::Period => pick('.', '>'),
+            P::RightBracket => pick(']', '}'),
+            P::Semicolon => pick(';', ':'),
+            P::Slash => pick('/', '?'),
+            P::Space => LogicalKey::Character(' '),
+            P::NumPad0 => LogicalKey::Character('0'),
+            P::NumPad1 => LogicalKey::Character('1'),
+            P::NumPad2 => LogicalKey::Character('2'),
+            P::NumPad3 => LogicalKey::Character('3'),
+            P::NumPad4 => LogicalKey::Character('4'),
+            P::NumPad5 => LogicalKey::Character('5'),
+            P::NumPad6 => LogicalKey::Character('6'),
+            P::NumPad7 => LogicalKey::Character('7'),
+            P::NumPad8 => LogicalKey::Character('8'),
+            P::NumPad9 => LogicalKey::Character('9'),
+            P::NumPadDot => LogicalKey::Character('.'),
+            P::NumPadSlash => LogicalKey::Character('/'),
+            P::NumPadAsterisk => LogicalKey::Character('*'),
+            P::NumPadMinus => LogicalKey::Character('-'),
+            P::NumPadPlus => LogicalKey::Character('+'),
+            P::F1 => LogicalKey::Named(NamedKey::F(1)),
+            P::F2 => LogicalKey::Named(NamedKey::F(2)),
+            P::F3 => LogicalKey::Named(NamedKey::F(3)),
+            P::F4 => LogicalKey::Named(NamedKey::F(4)),
+            P::F5 => LogicalKey::Named(NamedKey::F(5)),
+            P::F6 => LogicalKey::Named(NamedKey::F(6)),
+            P::F7 => LogicalKey::Named(NamedKey::F(7)),
+            P::F8 => LogicalKey::Named(NamedKey::F(8)),
+            P::F9 => LogicalKey::Named(NamedKey::F(9)),
+            P::F10 => LogicalKey::Named(NamedKey::F(10)),
+            P::F11 => LogicalKey::Named(NamedKey::F(11)),
+            P::F12 => LogicalKey::Named(NamedKey::F(12)),
+            P::F13 => LogicalKey::Named(NamedKey::F(13)),
+            P::F14 => LogicalKey::Named(NamedKey::F(14)),
+            P::F15 => LogicalKey::Named(NamedKey::F(15)),
+            P::Down => LogicalKey::Named(NamedKey::ArrowDown),
+            P::Left => LogicalKey::Named(NamedKey::ArrowLeft),
+            P::Right => LogicalKey::Named(NamedKey::ArrowRight),
+            P::Up => LogicalKey::Named(NamedKey::ArrowUp),
+            P::Backspace => LogicalKey::Named(NamedKey::Backspace),
+            P::Delete => LogicalKey::Named(NamedKey::Delete),
+            P::End => LogicalKey::Named(NamedKey::End),
+            P::Enter | P::NumPadEnter => LogicalKey::Named(NamedKey::Enter),
+            P::Escape => LogicalKey::Named(NamedKey::Escape),
+            P::Home => LogicalKey::Named(NamedKey::Home),
+            P::Insert => LogicalKey::Named(NamedKey::Insert),
+            P::Menu => LogicalKey::Named(NamedKey::ContextMenu),
+            P::PageDown => LogicalKey::Named(NamedKey::PageDown),
+            P::PageUp => LogicalKey::Named(NamedKey::PageUp),
+            P::Pause => LogicalKey::Named(NamedKey::Pause),
+            P::Tab => LogicalKey::Named(NamedKey::Tab),
+            P::NumLock => LogicalKey::Named(NamedKey::NumLock),
+            P::CapsLock => LogicalKey::Named(NamedKey::CapsLock),
+            P::ScrollLock => LogicalKey::Named(NamedKey::ScrollLock),
+            P::LeftShift | P::RightShift => LogicalKey::Named(NamedKey::Shift),
+            P::LeftCtrl | P::RightCtrl => LogicalKey::Named(NamedKey::Control),
+            P::LeftAlt | P::RightAlt => LogicalKey::Named(NamedKey::Alt),
+            P::LeftSuper | P::RightSuper => LogicalKey::Named(NamedKey::Meta),
+            P::Unknown => LogicalKey::Named(NamedKey::Unidentified),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub fn from_held_keys(keys: &[PhysicalKey]) -> Self {
+        use PhysicalKey as P;
+        Self {
+            shift: keys
+                .iter()
+                .any(|k| matches!(k, P::LeftShift | P::RightShift)),
+            ctrl: keys.iter().any(|k| matches!(k, P::LeftCtrl | P::RightCtrl)),
+            alt: keys.iter().any(|k| matches!(k, P::LeftAlt | P::RightAlt)),
+            meta: keys
+                .iter()
+                .any(|k| matches!(k, P::LeftSuper | P::RightSuper)),
+        }
+    }
+
+    pub fn none(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt || self.meta)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyEventKind {
+    Down,
+    Up,
+    Repeat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyEvent {
+    pub kind: KeyEventKind,
+    pub physical_key: PhysicalKey,
+    pub logical_key: LogicalKey,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub fn new(kind: KeyEventKind, physical_key: PhysicalKey, modifiers: Modifiers) -> Self {
+        Self {
+            kind,
+            physical_key,
+            logical_key: LogicalKey::resolve(physical_key, modifiers.shift),
+            modifiers,
+        }
+    }
+
+    // The text this event would type, if any
+    pub fn character(&self) -> Option<char> {
+        if self.kind == KeyEventKind::Up
+            || self.modifiers.ctrl
+            || self.modifiers.alt
+            || self.modifiers.meta
+        {
+            return None;
+        }
+        match self.logical_key {
+            LogicalKey::Character(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.kind != KeyEventKind::Up
+    }
+}
+
+// A key with the exact set of modifiers that must be held, like Ctrl+S
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct KeyChord {
+    pub key: PhysicalKey,
+    pub modifiers: Modifiers,
+}
+
+impl KeyChord {
+    pub fn new(key: PhysicalKey) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    pub fn meta(mut self) -> Self {
+        self.modifiers.meta = true;
+        self
+    }
+
+    pub fn accepts(&self, event: &KeyEvent) -> bool {
+        event.is_down() && event.physical_key == self.key && event.modifiers == self.modifiers
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Intent(pub &'static str);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyEventResult {
+    Handled,
+    Ignored,
+    // A shortcut matched: bubbles up until an `Actions` ancestor handles it
+    Intent(Intent),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shift() -> Modifiers {
+        Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        }
+    }
+
+    #[test]
+    fn resolves_characters_on_a_us_layout() {
+        assert_eq!(
+            LogicalKey::resolve(PhysicalKey::C, false),
+            LogicalKey::Character('c')
+        );
+        assert_eq!(
+            LogicalKey::resolve(PhysicalKey::Z, true),
+            LogicalKey::Character('Z')
+        );
+        assert_eq!(
+            LogicalKey::resolve(PhysicalKey::Key2, true),
+            LogicalKey::Character('@')
+        );
+        assert_eq!(
+            LogicalKey::resolve(PhysicalKey::F7, false),
+            LogicalKey::Named(NamedKey::F(7))
+        );
+    }
+
+    #[test]
+    fn modifiers_come_from_either_side() {
+        let modifiers =
+            Modifiers::from_held_keys(&[PhysicalKey::RightShift, PhysicalKey::LeftCtrl]);
+        assert!(modifiers.shift && modifiers.ctrl && !modifiers.alt && !modifiers.meta);
+        assert!(Modifiers::from_held_keys(&[PhysicalKey::A]).none());
+    }
+
+    #[test]
+    fn only_plain_key_presses_type_text() {
+        let typed = KeyEvent::new(KeyEventKind::Down, PhysicalKey::A, shift());
+        assert_eq!(typed.character(), Some('A'));
+        let released = KeyEvent::new(KeyEventKind::Up, PhysicalKey::A, Modifiers::default());
+        assert_eq!(released.character(), None);
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Modifiers::default()
+        };
+        assert_eq!(
+            KeyEvent::new(KeyEventKind::Down, PhysicalKey::A, ctrl).character(),
+            None
+        );
+    }
+
+    #[test]
+    fn chords_need_the_exact_modifiers() {
+        let save = KeyChord::new(PhysicalKey::S).ctrl();
+        let ctrl = Modifiers {
+            ctrl: true,
+            ..Modifiers::default()
+        };
+        let ctrl_shift = Modifiers {
+            shift: true,
+            ..ctrl
+        };
+        assert!(save.accepts(&KeyEvent::new(KeyEventKind::Down, PhysicalKey::S, ctrl)));
+        assert!(save.accepts(&KeyEvent::new(KeyEventKind::Repeat, PhysicalKey::S, ctrl)));
+        assert!(!save.accepts(&KeyEvent::new(KeyEventKind::Up, PhysicalKey::S, ctrl)));
+        assert!(!save.accepts(&KeyEvent::new(
+            KeyEventKind::Down,
+            PhysicalKey::S,
+            ctrl_shift
+        )));
+        assert!(!save.accepts(&KeyEvent::new(
+            KeyEventKind::Down,
+            PhysicalKey::S,
+            Modifiers::default()
+        )));
+    }
+}
@@ -1,3 +1,16 @@
+pub mod clipboard;
+pub mod focus;
+mod keyboard;
+mod text_editing;
+
+pub use clipboard::{Clipboard, InMemoryClipboard};
+pub use focus::{FocusEntry, FocusNode};
+pub use keyboard::{
+    Intent, KeyChord, KeyEvent, KeyEventKind, KeyEventResult, LogicalKey, Modifiers, NamedKey,
+    PhysicalKey,
+};
+pub use text_editing::{TextEditingState, TextEditor};
+
 use crate::types::{Position, Size};
 
 #[derive(Clone, Copy)]
@@ -28,7 +41,7 @@ impl ButtonState {
 #[derive(Clone)]
 pub struct MouseStatus {
     pub pos: Option<(usize, usize, usize)>,
-    pub motion: Option<(usize, usize)>,
+    pub motion: Option<(isize, isize)>,
     pub lclick: ButtonState,
     pub rclick: ButtonState,
     pub mclick: ButtonState,
@@ -63,7 +76,7 @@ impl MouseStatus {
     ) -> Self {
         let motion = if let Some((x, y, _)) = pos {
             if let Some((ox, oy, _)) = self.pos {
-                Some((x - ox, y - oy))
+                Some((x as isize - ox as isize, y as isize - oy as isize))
             } else {
                 None
             }
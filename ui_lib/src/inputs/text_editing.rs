@@ -0,0 +1,419 @@
+// Text, caret and selection of an editable text, with undo history.
+// Positions are char indices, the caret sits before the char at its index.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct TextEditingState {
+    pub text: Vec<char>,
+    pub caret: usize,
+    // other end of the selection, the selection is empty when it is None
+    pub anchor: Option<usize>,
+}
+
+#[derive(Default)]
+pub struct TextEditor {
+    state: TextEditingState,
+    undo_stack: Vec<TextEditingState>,
+    redo_stack: Vec<TextEditingState>,
+    multiline: bool,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl TextEditor {
+    pub fn new(text: &str, multiline: bool) -> Self {
+        let text: Vec<char> = text.chars().collect();
+        Self {
+            state: TextEditingState {
+                caret: text.len(),
+                text,
+                anchor: None,
+            },
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            multiline,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.state.text.iter().collect()
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.state.text
+    }
+
+    pub fn caret(&self) -> usize {
+        self.state.caret
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    // Replaces the whole text without recording an undo step, the caret goes to the end
+    pub fn set_text(&mut self, text: &str) {
+        self.state.text = text.chars().collect();
+        self.state.caret = self.state.text.len();
+        self.state.anchor = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.state.anchor?;
+        if anchor == self.state.caret {
+            return None;
+        }
+        Some((anchor.min(self.state.caret), anchor.max(self.state.caret)))
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.state.text[start..end].iter().collect())
+    }
+
+    pub fn select_all(&mut self) {
+        self.state.anchor = Some(0);
+        self.state.caret = self.state.text.len();
+    }
+
+    // Moves the caret to `pos`, extending the selection instead of clearing it if `extend`
+    pub fn set_caret(&mut self, pos: usize, extend: bool) {
+        let pos = pos.min(self.state.text.len());
+        if extend {
+            if self.state.anchor.is_none() {
+                self.state.anchor = Some(self.state.caret);
+            }
+        } else {
+            self.state.anchor = None;
+        }
+        self.state.caret = pos;
+    }
+
+    fn record(&mut self) {
+        self.undo_stack.push(self.state.clone());
+        self.redo_stack.clear();
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some((start, end)) => {
+                self.state.text.drain(start..end);
+                self.state.caret = start;
+                self.state.anchor = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn insert(&mut self, text: &str) {
+        let text: Vec<char> = text
+            .chars()
+            .filter(|c| self.multiline || *c != '\n')
+            .collect();
+        if text.is_empty() && self.selection().is_none() {
+            return;
+        }
+        self.record();
+        self.delete_selection();
+        let caret = self.state.caret;
+        self.state.text.splice(caret..caret, text.iter().copied());
+        self.state.caret += text.len();
+    }
+
+    pub fn delete_backward(&mut self, word: bool) {
+        if self.selection().is_some() {
+            self.record();
+            self.delete_selection();
+            return;
+        }
+        let caret = self.state.caret;
+        let start = if word {
+            self.word_start(caret)
+        } else {
+            caret.saturating_sub(1)
+        };
+        if start == caret {
+            return;
+        }
+        self.record();
+        self.state.text.drain(start..caret);
+        self.state.caret = start;
+    }
+
+    pub fn delete_forward(&mut self, word: bool) {
+        if self.selection().is_some() {
+            self.record();
+            self.delete_selection();
+            return;
+        }
+        let caret = self.state.caret;
+        let end = if word {
+            self.word_end(caret)
+        } else {
+            (caret + 1).min(self.state.text.len())
+        };
+        if end == caret {
+            return;
+        }
+        self.record();
+        self.state.text.drain(caret..end);
+    }
+
+    pub fn cut(&mut self) -> Option<String> {
+        let text = self.selected_text()?;
+        self.record();
+        self.delete_selection();
+        Some(text)
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(state) => {
+                self.redo_stack
+                    .push(std::mem::replace(&mut self.state, state));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(state) => {
+                self.undo_stack
+                    .push(std::mem::replace(&mut self.state, state));
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Start of the word before `pos`, skipping the spaces in between
+    pub fn word_start(&self, pos: usize) -> usize {
+        let text = &self.state.text;
+        let mut i = pos;
+        while i > 0 && !is_word_char(text[i - 1]) {
+            i -= 1;
+        }
+        while i > 0 && is_word_char(text[i - 1]) {
+            i -= 1;
+        }
+        i
+    }
+
+    // End of the word after `pos`, skipping the spaces in between
+    pub fn word_end(&self, pos: usize) -> usize {
+        let text = &self.state.text;
+        let mut i = pos;
+        while i < text.len() && !is_word_char(text[i]) {
+            i += 1;
+        }
+        while i < text.len() && is_word_char(text[i]) {
+            i += 1;
+        }
+        i
+    }
+
+    pub fn line_start(&self, pos: usize) -> usize {
+        let text = &self.state.text;
+        let mut i = pos.min(text.len());
+        while i > 0 && text[i - 1] != '\n' {
+            i -= 1;
+        }
+        i
+    }
+
+    pub fn line_end(&self, pos: usize) -> usize {
+        let text = &self.state.text;
+        let mut i = pos;
+        while i < text.len() && text[i] != '\n' {
+            i += 1;
+        }
+        i
+    }
+
+    // (line, column) of a position
+    pub fn line_col(&self, pos: usize) -> (usize, usize) {
+        let pos = pos.min(self.state.text.len());
+        let line = self.state.text[..pos]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count();
+        (line, pos - self.line_start(pos))
+    }
+
+    // Position at (line, column), clamped to the existing lines and their lengths
+    pub fn position_at(&self, line: usize, col: usize) -> usize {
+        let mut start = 0;
+        for _ in 0..line {
+            let end = self.line_end(start);
+            if end == self.state.text.len() {
+                break;
+            }
+            start = end + 1;
+        }
+        (start + col).min(self.line_end(start))
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.state.text.iter().filter(|c| **c == '\n').count() + 1
+    }
+
+    pub fn move_left(&mut self, word: bool, extend: bool) {
+        let pos = match self.selection() {
+            Some((start, _)) if !extend => start,
+            _ if word => self.word_start(self.state.caret),
+            _ => self.state.caret.saturating_sub(1),
+        };
+        self.set_caret(pos, extend);
+    }
+
+    pub fn move_right(&mut self, word: bool, extend: bool) {
+        let pos = match self.selection() {
+            Some((_, end)) if !extend => end,
+            _ if word => self.word_end(self.state.caret),
+            _ => self.state.caret + 1,
+        };
+        self.set_caret(pos, extend);
+    }
+
+    pub fn move_up(&mut self, extend: bool) {
+        let (line, col) = self.line_col(self.state.caret);
+        let pos = if line == 0 {
+            0
+        } else {
+            self.position_at(line - 1, col)
+        };
+        self.set_caret(pos, extend);
+    }
+
+    pub fn move_down(&mut self, extend: bool) {
+        let (line, col) = self.line_col(self.state.caret);
+        let pos = if line + 1 >= self.line_count() {
+            self.state.text.len()
+        } else {
+            self.position_at(line + 1, col)
+        };
+        self.set_caret(pos, extend);
+    }
+
+    pub fn move_home(&mut self, document: bool, extend: bool) {
+        let pos = if document {
+            0
+        } else {
+            self.line_start(self.state.caret)
+        };
+        self.set_caret(pos, extend);
+    }
+
+    pub fn move_end(&mut self, document: bool, extend: bool) {
+        let pos = if document {
+            self.state.text.len()
+        } else {
+            self.line_end(self.state.caret)
+        };
+        self.set_caret(pos, extend);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_replaces_the_selection() {
+        let mut editor = TextEditor::new("hello world", false);
+        editor.set_caret(6, false);
+        editor.set_caret(11, true);
+        assert_eq!(editor.selected_text().as_deref(), Some("world"));
+        editor.insert("there");
+        assert_eq!(editor.text(), "hello there");
+        assert_eq!(editor.caret(), 11);
+        assert_eq!(editor.selection(), None);
+    }
+
+    #[test]
+    fn single_line_editors_drop_newlines() {
+        let mut editor = TextEditor::new("", false);
+        editor.insert("a\nb");
+        assert_eq!(editor.text(), "ab");
+        let mut editor = TextEditor::new("", true);
+        editor.insert("a\nb");
+        assert_eq!(editor.line_count(), 2);
+    }
+
+    #[test]
+    fn word_deletion_skips_the_spaces_in_between() {
+        let mut editor = TextEditor::new("one two  three", false);
+        editor.delete_backward(true);
+        assert_eq!(editor.text(), "one two  ");
+        editor.delete_backward(true);
+        assert_eq!(editor.text(), "one ");
+        editor.set_caret(0, false);
+        editor.delete_forward(true);
+        assert_eq!(editor.text(), " ");
+    }
+
+    #[test]
+    fn undo_and_redo_restore_text_and_caret() {
+        let mut editor = TextEditor::new("ab", false);
+        editor.insert("c");
+        editor.delete_backward(false);
+        editor.delete_backward(false);
+        assert_eq!(editor.text(), "a");
+        assert!(editor.undo());
+        assert!(editor.undo());
+        assert_eq!(editor.text(), "abc");
+        assert_eq!(editor.caret(), 3);
+        assert!(editor.redo());
+        assert_eq!(editor.text(), "ab");
+        // a new edit drops the redo history
+        editor.insert("x");
+        assert!(!editor.redo());
+        assert!(editor.undo() && editor.undo() && editor.undo() && !editor.undo());
+        assert_eq!(editor.text(), "ab");
+    }
+
+    #[test]
+    fn cut_takes_the_selected_text() {
+        let mut editor = TextEditor::new("copy me", false);
+        assert_eq!(editor.cut(), None);
+        editor.select_all();
+        assert_eq!(editor.cut().as_deref(), Some("copy me"));
+        assert_eq!(editor.text(), "");
+    }
+
+    #[test]
+    fn vertical_moves_keep_the_column_when_they_can() {
+        let mut editor = TextEditor::new("long line\nab\nanother", true);
+        editor.set_caret(7, false);
+        editor.move_down(false);
+        assert_eq!(editor.line_col(editor.caret()), (1, 2));
+        editor.move_down(false);
+        assert_eq!(editor.line_col(editor.caret()), (2, 2));
+        editor.move_down(true);
+        assert_eq!(editor.caret(), editor.chars().len());
+        assert_eq!(editor.selected_text().as_deref(), Some("other"));
+        editor.move_up(false);
+        editor.move_up(false);
+        editor.move_up(false);
+        assert_eq!(editor.caret(), 0);
+    }
+
+    #[test]
+    fn collapsing_a_selection_moves_to_its_edge() {
+        let mut editor = TextEditor::new("abcdef", false);
+        editor.set_caret(1, false);
+        editor.set_caret(4, true);
+        editor.move_left(false, false);
+        assert_eq!(editor.caret(), 1);
+        editor.set_caret(4, true);
+        editor.move_right(false, false);
+        assert_eq!(editor.caret(), 4);
+        editor.move_home(false, true);
+        assert_eq!(editor.selection(), Some((0, 4)));
+    }
+}
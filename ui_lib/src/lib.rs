@@ -1,3 +1,4 @@
+pub mod animation;
 pub mod inputs;
 pub mod rendering;
 pub mod types;
@@ -1,8 +1,37 @@
+use crate::types::{Color, Matrix, Position, Rect};
+
+use super::{
+    font, BoxShadow, Contour, FillRule, FilterQuality, Image, Paint, Path, ShadowMask, Stroke,
+};
+
+// Lines each row of pixels is sampled on when filling a path
+const PATH_SAMPLES: usize = 16;
+
 pub struct AppBuffer {
     pub width: usize,
     pub height: usize,
     pub color: Vec<u32>,
     pub depth: Vec<usize>,
+    // drawing outside of it is ignored
+    clip: Option<Rect>,
+    // window position of the top left pixel, for buffers covering a part of it
+    origin: (isize, isize),
+    // coverage of each pixel, for offscreen buffers drawn over something else later
+    alpha: Option<Vec<u8>>,
+}
+
+// A band of full rows of an `AppBuffer`, the unit drawn by one thread. Every pixel is
+// written by the same code whether the buffer is drawn at once or band by band.
+pub struct Tile<'a> {
+    width: usize,
+    // first row of the band in the buffer
+    top: usize,
+    rows: usize,
+    color: &'a mut [u32],
+    depth: &'a mut [usize],
+    alpha: Option<&'a mut [u8]>,
+    clip: Option<Rect>,
+    origin: (isize, isize),
 }
 
 impl AppBuffer {
@@ -12,15 +41,92 @@ impl AppBuffer {
             height: h,
             color: vec![0; w * h],
             depth: vec![0; w * h],
+            clip: None,
+            origin: (0, 0),
+            alpha: None,
+        }
+    }
+
+    // A transparent buffer for the part of the window at (x, y), to composite later
+    pub fn offscreen(x: isize, y: isize, w: usize, h: usize) -> Self {
+        Self {
+            origin: (x, y),
+            alpha: Some(vec![0; w * h]),
+            ..Self::new(w, h)
         }
     }
 
+    pub fn origin(&self) -> (isize, isize) {
+        self.origin
+    }
+
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+    }
+
+    // The pixels with their coverage, opaque unless the buffer is offscreen
+    pub fn to_image(&self) -> Image {
+        let pixels = self
+            .color
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let a = self.alpha.as_ref().map_or(0xFF, |a| a[i]);
+                Color::from(*c).with_alpha(a)
+            })
+            .collect();
+        Image::new(self.width, self.height, pixels)
+    }
+
     // Will clear the buffer too
     pub fn resize(&mut self, w: usize, h: usize) {
         self.width = w;
         self.height = h;
         self.color.resize(w * h, 0);
         self.depth.resize(w * h, 0);
+        if let Some(ref mut coverage) = self.alpha {
+            coverage.resize(w * h, 0);
+        }
+    }
+
+    // The whole buffer as a single tile
+    pub fn tile(&mut self) -> Tile<'_> {
+        Tile {
+            width: self.width,
+            top: 0,
+            rows: self.height,
+            color: &mut self.color,
+            depth: &mut self.depth,
+            alpha: self.alpha.as_deref_mut(),
+            clip: self.clip,
+            origin: self.origin,
+        }
+    }
+
+    // The buffer cut in bands of `rows` rows, leaving out the ones outside of the clip
+    pub fn tiles(&mut self, rows: usize) -> Vec<Tile<'_>> {
+        let (width, clip, origin) = (self.width, self.clip, self.origin);
+        if width == 0 {
+            return Vec::new();
+        }
+        let rows = rows.max(1);
+        let mut alpha = self.alpha.as_mut().map(|a| a.chunks_mut(width * rows));
+        self.color
+            .chunks_mut(width * rows)
+            .zip(self.depth.chunks_mut(width * rows))
+            .enumerate()
+            .map(|(i, (color, depth))| Tile {
+                width,
+                top: i * rows,
+                rows: color.len() / width,
+                color,
+                depth,
+                alpha: alpha.as_mut().and_then(|a| a.next()),
+                clip,
+                origin,
+            })
+            .filter(|t| !t.is_clipped_out())
+            .collect()
     }
 
     // Function to draw a colored rectangle in the buffer
@@ -33,15 +139,152 @@ impl AppBuffer {
         color: u32,
         depth: usize,
     ) {
-        let (x, y, w, h) = clip_rectangle(x, y, width, height, self.width, self.height);
-        for i in x..x + w {
-            for j in y..y + h {
-                let pos = self.width * j + i;
-                if self.depth[pos] > depth {
-                    continue;
-                }
-                self.color[pos] = color;
-                self.depth[pos] = depth;
+        self.tile()
+            .draw_rectangle(x, y, width, height, color, depth);
+    }
+
+    // Same as draw_rectangle, but mixes the color with what is already in the buffer
+    // according to its alpha
+    pub fn blend_rectangle(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        color: Color,
+        depth: usize,
+    ) {
+        self.tile()
+            .blend_rectangle(x, y, width, height, color, depth);
+    }
+
+    // `radius` is the same for every corner, the corners are anti-aliased
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_rounded_rect(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        radius: f32,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.tile()
+            .draw_rounded_rect(x, y, width, height, radius, &paint.into(), 1.0, depth);
+    }
+
+    // Stretches `image` over the rectangle
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_image(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        image: &Image,
+        filter: FilterQuality,
+        opacity: f32,
+        depth: usize,
+    ) {
+        self.tile()
+            .draw_image(x, y, width, height, image, filter, opacity, depth);
+    }
+
+    pub fn fill_path(
+        &mut self,
+        path: &Path,
+        rule: FillRule,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.tile()
+            .fill_contours(&path.flatten(), (0, 0), rule, &paint.into(), 1.0, depth);
+    }
+
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        stroke: &Stroke,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        let outline = stroke.outline(path);
+        self.tile().fill_contours(
+            &outline,
+            (0, 0),
+            FillRule::NonZero,
+            &paint.into(),
+            1.0,
+            depth,
+        );
+    }
+
+    pub fn draw_shadow(
+        &mut self,
+        rect: Rect,
+        border_radius: f32,
+        shadow: &BoxShadow,
+        depth: usize,
+    ) {
+        let mask = shadow.mask(rect, border_radius);
+        self.tile().draw_mask(&mask, (0, 0), shadow.color, depth);
+    }
+
+    // Draws an offscreen buffer where it belongs, moved by `offset`, keeping the depth it was
+    // drawn with
+    pub fn composite(&mut self, src: &AppBuffer, offset: (isize, isize), opacity: f32) {
+        self.tile().composite(src, offset, opacity);
+    }
+
+    pub fn composite_transformed(
+        &mut self,
+        src: &AppBuffer,
+        matrix: &Matrix,
+        filter: FilterQuality,
+        opacity: f32,
+    ) {
+        self.tile()
+            .composite_transformed(src, matrix, filter, opacity);
+    }
+
+    // Draws the border of `rect`, `thickness` pixels wide, inside of it
+    pub fn draw_outline(&mut self, rect: Rect, thickness: f32, color: Color, depth: usize) {
+        for (x, y, w, h) in outline_parts(rect, thickness) {
+            self.blend_rectangle(x, y, w, h, color, depth);
+        }
+    }
+
+    // Draws `text` with the built-in font, (x, y) being the top left corner of the first line
+    pub fn draw_text(
+        &mut self,
+        x: isize,
+        y: isize,
+        text: &str,
+        scale: usize,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.tile()
+            .draw_text(x, y, text, scale, &paint.into(), 1.0, depth);
+    }
+
+    // Clears `rect` only, ignoring the clip
+    pub fn clear_rect(&mut self, rect: Rect, color: u32, depth: usize) {
+        let (x, y, w, h) = clip_rectangle(
+            rect.x as isize - self.origin.0,
+            rect.y as isize - self.origin.1,
+            rect.w as usize,
+            rect.h as usize,
+            self.width,
+            self.height,
+        );
+        for j in y..y + h {
+            let row = j * self.width;
+            self.color[row + x..row + x + w].fill(color);
+            self.depth[row + x..row + x + w].fill(depth);
+            if let Some(ref mut coverage) = self.alpha {
+                coverage[row + x..row + x + w].fill(0);
             }
         }
     }
@@ -49,9 +292,624 @@ impl AppBuffer {
     pub fn clear(&mut self, color: u32, depth: usize) {
         self.color.iter_mut().for_each(|pixel| *pixel = color);
         self.depth.iter_mut().for_each(|pixel| *pixel = depth);
+        if let Some(ref mut coverage) = self.alpha {
+            coverage.fill(0);
+        }
+    }
+}
+
+impl Tile<'_> {
+    // The pixels of the band a rectangle given in window coordinates covers, keeping inside
+    // of the clip. Rows are counted from the top of the buffer.
+    fn visible_part(&self, x: isize, y: isize, w: usize, h: usize) -> (usize, usize, usize, usize) {
+        let (x, y) = (x - self.origin.0, y - self.top as isize - self.origin.1);
+        let (x, y, w, h) = clip_rectangle(x, y, w, h, self.width, self.rows);
+        let mut rect = Rect::new(x as f32, (y + self.top) as f32, w as f32, h as f32);
+        if let Some(clip) = self.clip {
+            rect = rect.intersect(&clip.translate(Position {
+                x: -self.origin.0 as f32,
+                y: -self.origin.1 as f32,
+            }));
+        }
+        (
+            rect.x as usize,
+            rect.y as usize,
+            rect.w as usize,
+            rect.h as usize,
+        )
+    }
+
+    fn is_clipped_out(&self) -> bool {
+        self.area().is_empty()
+    }
+
+    // What can be drawn on in the band, in window coordinates
+    pub fn area(&self) -> Rect {
+        let (x, y, w, h) = self.visible_part(
+            self.origin.0,
+            self.origin.1 + self.top as isize,
+            self.width,
+            self.rows,
+        );
+        Rect::new(
+            (x as isize + self.origin.0) as f32,
+            (y as isize + self.origin.1) as f32,
+            w as f32,
+            h as f32,
+        )
+    }
+
+    // Clears what of the band is inside of the clip
+    pub fn clear(&mut self, color: u32, depth: usize) {
+        let (x, y, w, h) = self.visible_part(
+            self.origin.0,
+            self.origin.1 + self.top as isize,
+            self.width,
+            self.rows,
+        );
+        for j in y..y + h {
+            let row = self.index(x, j);
+            self.color[row..row + w].fill(color);
+            self.depth[row..row + w].fill(depth);
+            if let Some(ref mut coverage) = self.alpha {
+                coverage[row..row + w].fill(0);
+            }
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        (y - self.top) * self.width + x
+    }
+
+    pub fn clip(&self) -> Option<Rect> {
+        self.clip
+    }
+
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+    }
+
+    pub fn draw_rectangle(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        color: u32,
+        depth: usize,
+    ) {
+        let (x, y, w, h) = self.visible_part(x, y, width, height);
+        for j in y..y + h {
+            let start = self.index(x, j);
+            if self.alpha.is_none() {
+                let colors = &mut self.color[start..start + w];
+                let depths = &mut self.depth[start..start + w];
+                for (c, d) in colors.iter_mut().zip(depths) {
+                    if *d <= depth {
+                        *c = color;
+                        *d = depth;
+                    }
+                }
+            } else {
+                for pos in start..start + w {
+                    self.put(pos, color, 1.0, depth);
+                }
+            }
+        }
+    }
+
+    pub fn blend_rectangle(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        color: Color,
+        depth: usize,
+    ) {
+        if color.a == 0xFF {
+            return self.draw_rectangle(x, y, width, height, color.to_u32(), depth);
+        }
+        if color.a == 0 {
+            return;
+        }
+        let (alpha, color) = (color.opacity(), color.to_u32());
+        let (x, y, w, h) = self.visible_part(x, y, width, height);
+        for j in y..y + h {
+            let start = self.index(x, j);
+            for pos in start..start + w {
+                self.put(pos, color, alpha, depth);
+            }
+        }
+    }
+
+    fn put(&mut self, pos: usize, color: u32, alpha: f32, depth: usize) {
+        if self.depth[pos] > depth {
+            return;
+        }
+        match self.alpha {
+            None if alpha >= 1.0 => self.color[pos] = color,
+            None => self.color[pos] = blend(self.color[pos], color, alpha),
+            Some(ref mut coverage) => {
+                // what is under it shows through where it isn't fully covered yet
+                let below = coverage[pos] as f32 / 255.0;
+                let total = alpha + below * (1.0 - alpha);
+                if total <= 0.0 {
+                    return;
+                }
+                self.color[pos] = blend(self.color[pos], color, alpha / total);
+                coverage[pos] = (total * 255.0).round() as u8;
+            }
+        }
+        self.depth[pos] = depth;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_rounded_rect(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        radius: f32,
+        paint: &Paint,
+        opacity: f32,
+        depth: usize,
+    ) {
+        let radius = radius.min(width.min(height) as f32 / 2.0);
+        if radius < 0.5 {
+            return self.paint_rectangle(x, y, width, height, paint, opacity, depth);
+        }
+        if paint.is_transparent() || opacity <= 0.0 {
+            return;
+        }
+        // edges of the rectangle in buffer pixels
+        let left = (x - self.origin.0) as f32;
+        let top = (y - self.origin.1) as f32;
+        let (right, bottom) = (left + width as f32, top + height as f32);
+        let corner = |p: f32, low: f32, high: f32| (p.max(low + radius)).min(high - radius);
+        let (vx, vy, w, h) = self.visible_part(x, y, width, height);
+        for j in vy..vy + h {
+            let py = j as f32 + 0.5;
+            let cy = corner(py, top, bottom);
+            let start = self.index(vx, j);
+            for (i, pos) in (start..start + w).enumerate() {
+                let px = (vx + i) as f32 + 0.5;
+                let cx = corner(px, left, right);
+                // pixels out of the corners are fully covered
+                let coverage = if cx == px || cy == py {
+                    1.0
+                } else {
+                    let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+                    (radius + 0.5 - distance).clamp(0.0, 1.0)
+                };
+                if coverage > 0.0 {
+                    self.put_paint(pos, vx + i, j, paint, opacity * coverage, depth);
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn paint_rectangle(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        paint: &Paint,
+        opacity: f32,
+        depth: usize,
+    ) {
+        if let Some(color) = paint.solid_color() {
+            let color = color.with_opacity(opacity);
+            return self.blend_rectangle(x, y, width, height, color, depth);
+        }
+        let (vx, vy, w, h) = self.visible_part(x, y, width, height);
+        for j in vy..vy + h {
+            let start = self.index(vx, j);
+            for (i, pos) in (start..start + w).enumerate() {
+                self.put_paint(pos, vx + i, j, paint, opacity, depth);
+            }
+        }
+    }
+
+    // Puts the color the paint has at the pixel (x, y) of the buffer
+    fn put_paint(
+        &mut self,
+        pos: usize,
+        x: usize,
+        y: usize,
+        paint: &Paint,
+        alpha: f32,
+        depth: usize,
+    ) {
+        let c = paint.color_at(x as isize + self.origin.0, y as isize + self.origin.1);
+        if c.a != 0 {
+            self.put(pos, c.to_u32(), c.opacity() * alpha, depth);
+        }
+    }
+
+    // The image sampled at the center of each pixel of the rectangle
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_image(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        image: &Image,
+        filter: FilterQuality,
+        opacity: f32,
+        depth: usize,
+    ) {
+        if image.width == 0 || image.height == 0 {
+            return;
+        }
+        let (vx, vy, w, h) = self.visible_part(x, y, width, height);
+        let sample = |p: isize, size: usize, image_size: usize| {
+            (p as f32 + 0.5) * image_size as f32 / size as f32
+        };
+        for j in vy..vy + h {
+            let sy = sample(j as isize + self.origin.1 - y, height, image.height);
+            let start = self.index(vx, j);
+            for (i, pos) in (start..start + w).enumerate() {
+                let sx = sample((vx + i) as isize + self.origin.0 - x, width, image.width);
+                let c = image.sample(sx, sy, filter);
+                if c.a != 0 {
+                    self.put(pos, c.to_u32(), c.opacity() * opacity, depth);
+                }
+            }
+        }
+    }
+
+    // The contours are closed and moved by `offset`. Each row of pixels is sampled on
+    // several lines, along which the part of each pixel inside of the shape is exact.
+    pub fn fill_contours(
+        &mut self,
+        contours: &[Contour],
+        offset: (isize, isize),
+        rule: FillRule,
+        paint: &Paint,
+        opacity: f32,
+        depth: usize,
+    ) {
+        if paint.is_transparent() || opacity <= 0.0 {
+            return;
+        }
+        // in buffer pixels
+        let shift = Position {
+            x: (offset.0 - self.origin.0) as f32,
+            y: (offset.1 - self.origin.1) as f32,
+        };
+        let edges: Vec<Edge> = contours
+            .iter()
+            .filter(|c| c.points.len() > 2)
+            .flat_map(|c| {
+                let n = c.points.len();
+                (0..n).filter_map(move |i| {
+                    Edge::new(c.points[i] + shift, c.points[(i + 1) % n] + shift)
+                })
+            })
+            .collect();
+        if edges.is_empty() {
+            return;
+        }
+        let (mut left, mut right) = (f32::INFINITY, f32::NEG_INFINITY);
+        let (mut top, mut bottom) = (f32::INFINITY, f32::NEG_INFINITY);
+        for e in &edges {
+            left = left.min(e.x0.min(e.x1));
+            right = right.max(e.x0.max(e.x1));
+            top = top.min(e.y0);
+            bottom = bottom.max(e.y1);
+        }
+        let (x, y) = (left.floor() as isize, top.floor() as isize);
+        let (vx, vy, w, h) = self.visible_part(
+            x + self.origin.0,
+            y + self.origin.1,
+            (right.ceil() as isize - x) as usize,
+            (bottom.ceil() as isize - y) as usize,
+        );
+        let (span_start, span_end) = (vx as f32, (vx + w) as f32);
+        let mut coverage = vec![0.0f32; w];
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        for j in vy..vy + h {
+            let row: Vec<&Edge> = edges
+                .iter()
+                .filter(|e| e.y0 < (j + 1) as f32 && e.y1 > j as f32)
+                .collect();
+            if row.is_empty() {
+                continue;
+            }
+            coverage.fill(0.0);
+            for s in 0..PATH_SAMPLES {
+                let sy = j as f32 + (s as f32 + 0.5) / PATH_SAMPLES as f32;
+                crossings.clear();
+                crossings.extend(
+                    row.iter()
+                        .filter(|e| e.y0 <= sy && sy < e.y1)
+                        .map(|e| (e.x_at(sy), e.winding)),
+                );
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut winding = 0;
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+                    let inside = match rule {
+                        FillRule::NonZero => winding != 0,
+                        FillRule::EvenOdd => winding % 2 != 0,
+                    };
+                    let (a, b) = (pair[0].0.max(span_start), pair[1].0.min(span_end));
+                    if inside && a < b {
+                        add_span(&mut coverage, a - span_start, b - span_start);
+                    }
+                }
+            }
+            let start = self.index(vx, j);
+            for (i, c) in coverage.iter().enumerate() {
+                let c = (c / PATH_SAMPLES as f32).min(1.0);
+                if c > 0.0 {
+                    self.put_paint(start + i, vx + i, j, paint, opacity * c, depth);
+                }
+            }
+        }
+    }
+
+    // The color where the mask, moved by `offset`, lets it through
+    pub fn draw_mask(
+        &mut self,
+        mask: &ShadowMask,
+        offset: (isize, isize),
+        color: Color,
+        depth: usize,
+    ) {
+        if color.a == 0 {
+            return;
+        }
+        let (mx, my) = (mask.x + offset.0, mask.y + offset.1);
+        let (alpha, rgb) = (color.opacity(), color.to_u32());
+        let (vx, vy, w, h) = self.visible_part(mx, my, mask.width, mask.height);
+        for j in vy..vy + h {
+            let row = (j as isize + self.origin.1 - my) as usize * mask.width;
+            let column = (vx as isize + self.origin.0 - mx) as usize;
+            let start = self.index(vx, j);
+            for (i, pos) in (start..start + w).enumerate() {
+                let a = mask.alpha[row + column + i];
+                if a != 0 {
+                    self.put(pos, rgb, alpha * a as f32 / 255.0, depth);
+                }
+            }
+        }
+    }
+
+    pub fn composite(&mut self, src: &AppBuffer, offset: (isize, isize), opacity: f32) {
+        let (sx, sy) = (src.origin.0 + offset.0, src.origin.1 + offset.1);
+        let (x, y, w, h) = self.visible_part(sx, sy, src.width, src.height);
+        for j in y..y + h {
+            let src_y = (j as isize + self.origin.1 - sy) as usize;
+            let start = self.index(x, j);
+            for (i, pos) in (start..start + w).enumerate() {
+                let src_x = (x as isize + i as isize + self.origin.0 - sx) as usize;
+                let src_pos = src.width * src_y + src_x;
+                let coverage = src
+                    .alpha
+                    .as_ref()
+                    .map_or(1.0, |a| a[src_pos] as f32 / 255.0);
+                if coverage > 0.0 {
+                    self.put(
+                        pos,
+                        src.color[src_pos],
+                        coverage * opacity,
+                        src.depth[src_pos],
+                    );
+                }
+            }
+        }
+    }
+
+    // Draws an offscreen buffer moved by `matrix`, from window coordinates to window
+    // coordinates. Each pixel is sampled where its center comes from, keeping the depth of
+    // the closest source pixel.
+    pub fn composite_transformed(
+        &mut self,
+        src: &AppBuffer,
+        matrix: &Matrix,
+        filter: FilterQuality,
+        opacity: f32,
+    ) {
+        let Some(inverse) = matrix.inverse() else {
+            return;
+        };
+        let (ox, oy) = (src.origin.0 as f32, src.origin.1 as f32);
+        let bounds = matrix.apply_rect(Rect::new(ox, oy, src.width as f32, src.height as f32));
+        let left = bounds.x.floor() as isize;
+        let top = bounds.y.floor() as isize;
+        let (x, y, w, h) = self.visible_part(
+            left,
+            top,
+            (bounds.right().ceil() as isize - left).max(0) as usize,
+            (bounds.bottom().ceil() as isize - top).max(0) as usize,
+        );
+        // color and coverage of a source pixel, nothing outside of the buffer
+        let texel = |i: isize, j: isize| {
+            if i < 0 || j < 0 || i as usize >= src.width || j as usize >= src.height {
+                return None;
+            }
+            let pos = j as usize * src.width + i as usize;
+            let coverage = src.alpha.as_ref().map_or(255, |a| a[pos]);
+            (coverage > 0).then(|| (Color::from(src.color[pos]), coverage, src.depth[pos]))
+        };
+        for j in y..y + h {
+            let start = self.index(x, j);
+            for (i, pos) in (start..start + w).enumerate() {
+                let p = inverse.apply(Position {
+                    x: (x + i) as f32 + self.origin.0 as f32 + 0.5,
+                    y: j as f32 + self.origin.1 as f32 + 0.5,
+                });
+                let (u, v) = (p.x - ox, p.y - oy);
+                match filter {
+                    FilterQuality::Nearest => {
+                        if let Some((c, coverage, depth)) =
+                            texel(u.floor() as isize, v.floor() as isize)
+                        {
+                            self.put(pos, c.to_u32(), coverage as f32 / 255.0 * opacity, depth);
+                        }
+                    }
+                    FilterQuality::Bilinear => {
+                        let (u, v) = (u - 0.5, v - 0.5);
+                        let (i0, j0) = (u.floor() as isize, v.floor() as isize);
+                        let (tx, ty) = (u - u.floor(), v - v.floor());
+                        // weighted by coverage, the depth of the heaviest one
+                        let (mut sum, mut r, mut g, mut b) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+                        let (mut depth, mut heaviest) = (0, 0.0f32);
+                        for (di, dj, weight) in [
+                            (0, 0, (1.0 - tx) * (1.0 - ty)),
+                            (1, 0, tx * (1.0 - ty)),
+                            (0, 1, (1.0 - tx) * ty),
+                            (1, 1, tx * ty),
+                        ] {
+                            let Some((c, coverage, d)) = texel(i0 + di, j0 + dj) else {
+                                continue;
+                            };
+                            let w = weight * coverage as f32 / 255.0;
+                            sum += w;
+                            r += c.r as f32 * w;
+                            g += c.g as f32 * w;
+                            b += c.b as f32 * w;
+                            if w > heaviest {
+                                (depth, heaviest) = (d, w);
+                            }
+                        }
+                        if sum > 0.0 {
+                            let channel = |v: f32| (v / sum).round().clamp(0.0, 255.0) as u8;
+                            let c = Color::rgb(channel(r), channel(g), channel(b));
+                            self.put(pos, c.to_u32(), sum.min(1.0) * opacity, depth);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text(
+        &mut self,
+        x: isize,
+        y: isize,
+        text: &str,
+        scale: usize,
+        paint: &Paint,
+        opacity: f32,
+        depth: usize,
+    ) {
+        let scale = scale.max(1);
+        let line_height = (font::LINE_HEIGHT * scale) as isize;
+        let advance = (font::ADVANCE * scale) as isize;
+        for (l, line) in text.split('\n').enumerate() {
+            let line_y = y + l as isize * line_height;
+            // lines out of the band are skipped
+            let (_, _, _, h) = self.visible_part(
+                self.origin.0,
+                line_y,
+                self.width,
+                font::GLYPH_HEIGHT * scale,
+            );
+            if h == 0 {
+                continue;
+            }
+            for (i, c) in line.chars().enumerate() {
+                let glyph_x = x + i as isize * advance;
+                for gy in 0..font::GLYPH_HEIGHT {
+                    for gx in 0..font::GLYPH_WIDTH {
+                        if font::glyph_pixel(c, gx, gy) {
+                            self.paint_rectangle(
+                                glyph_x + (gx * scale) as isize,
+                                line_y + (gy * scale) as isize,
+                                scale,
+                                scale,
+                                paint,
+                                opacity,
+                                depth,
+                            );
+                        }
+                    }
+                }
+            }
+        }
     }
 }
 
+// A side of a filled contour, going down from (x0, y0) to (x1, y1)
+struct Edge {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    // 1 if the contour goes down along it, -1 if it goes up
+    winding: i32,
+}
+
+impl Edge {
+    fn new(a: Position, b: Position) -> Option<Self> {
+        if a.y == b.y {
+            return None;
+        }
+        let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+        Some(Self {
+            x0: top.x,
+            y0: top.y,
+            x1: bottom.x,
+            y1: bottom.y,
+            winding,
+        })
+    }
+
+    fn x_at(&self, y: f32) -> f32 {
+        self.x0 + (self.x1 - self.x0) * (y - self.y0) / (self.y1 - self.y0)
+    }
+}
+
+// Adds the part of each pixel covered by the horizontal span from a to b
+fn add_span(coverage: &mut [f32], a: f32, b: f32) {
+    let (first, last) = (a as usize, b as usize);
+    if first == last {
+        coverage[first] += b - a;
+        return;
+    }
+    coverage[first] += (first + 1) as f32 - a;
+    for c in &mut coverage[first + 1..last] {
+        *c += 1.0;
+    }
+    if last < coverage.len() {
+        coverage[last] += b - last as f32;
+    }
+}
+
+// The four sides of an outline, as (x, y, w, h)
+pub(crate) fn outline_parts(rect: Rect, thickness: f32) -> Vec<(isize, isize, usize, usize)> {
+    let (x, y) = (rect.x as isize, rect.y as isize);
+    let (w, h, t) = (rect.w as usize, rect.h as usize, thickness as usize);
+    if w <= 2 * t || h <= 2 * t {
+        return vec![(x, y, w, h)];
+    }
+    let ti = t as isize;
+    vec![
+        (x, y, w, t),
+        (x, y + h as isize - ti, w, t),
+        (x, y + ti, t, h - 2 * t),
+        (x + w as isize - ti, y + ti, t, h - 2 * t),
+    ]
+}
+
+pub fn blend(dst: u32, src: u32, alpha: f32) -> u32 {
+    let a = (alpha.clamp(0.0, 1.0) * 256.0) as u32;
+    let channel = |shift: u32| {
+        let d = (dst >> shift) & 0xFF;
+        let s = (src >> shift) & 0xFF;
+        ((s * a + d * (256 - a)) >> 8) << shift
+    };
+    channel(16) | channel(8) | channel(0)
+}
+
 fn clip_rectangle(
     x: isize,
     y: isize,
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use crate::types::{Position, Rect, Size};
+
+use super::{DisplayList, FillRule, FilterQuality, Image, Paint, Path, Stroke};
+
+// What painters draw with, in coordinates local to what they paint. Clips left pushed are
+// popped when the canvas goes away.
+pub struct Canvas<'a> {
+    display_list: &'a mut DisplayList,
+    size: Size,
+    depth: usize,
+    clips: usize,
+}
+
+impl<'a> Canvas<'a> {
+    pub fn new(display_list: &'a mut DisplayList, size: Size, depth: usize) -> Self {
+        Self {
+            display_list,
+            size,
+            depth,
+            clips: 0,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, paint: impl Into<Paint>) {
+        let paint = paint.into();
+        match paint.solid_color() {
+            Some(color) => {
+                let (x, y, w, h) = pixels(rect);
+                self.display_list
+                    .blend_rectangle(x, y, w, h, color, self.depth);
+            }
+            None => self.fill_rounded_rect(rect, 0.0, paint),
+        }
+    }
+
+    pub fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, paint: impl Into<Paint>) {
+        let (x, y, w, h) = pixels(rect);
+        self.display_list
+            .draw_rounded_rect(x, y, w, h, radius, paint, self.depth);
+    }
+
+    pub fn fill_circle(&mut self, center: Position, radius: f32, paint: impl Into<Paint>) {
+        let circle = Path::circle(center.x, center.y, radius);
+        self.fill_path(&circle, FillRule::NonZero, paint);
+    }
+
+    pub fn draw_line(
+        &mut self,
+        from: Position,
+        to: Position,
+        stroke: &Stroke,
+        paint: impl Into<Paint>,
+    ) {
+        let line = Path::new().move_to(from.x, from.y).line_to(to.x, to.y);
+        self.stroke_path(&line, stroke, paint);
+    }
+
+    pub fn fill_path(&mut self, path: &Path, rule: FillRule, paint: impl Into<Paint>) {
+        self.display_list.fill_path(path, rule, paint, self.depth);
+    }
+
+    pub fn stroke_path(&mut self, path: &Path, stroke: &Stroke, paint: impl Into<Paint>) {
+        self.display_list
+            .stroke_path(path, stroke, paint, self.depth);
+    }
+
+    // `pos` is the top left corner of the first line
+    pub fn draw_text(&mut self, pos: Position, text: &str, scale: usize, paint: impl Into<Paint>) {
+        self.display_list.draw_text(
+            pos.x.round() as isize,
+            pos.y.round() as isize,
+            text,
+            scale,
+            paint,
+            self.depth,
+        );
+    }
+
+    pub fn draw_image(&mut self, rect: Rect, image: Arc<Image>, filter: FilterQuality) {
+        let (x, y, w, h) = pixels(rect);
+        self.display_list
+            .draw_image(x, y, w, h, image, filter, self.depth);
+    }
+
+    // Until the matching `pop_clip`, nothing is drawn outside of the rectangle
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.display_list.push_clip(rect);
+        self.clips += 1;
+    }
+
+    pub fn pop_clip(&mut self) {
+        if self.clips > 0 {
+            self.display_list.pop_clip();
+            self.clips -= 1;
+        }
+    }
+}
+
+impl Drop for Canvas<'_> {
+    fn drop(&mut self) {
+        while self.clips > 0 {
+            self.pop_clip();
+        }
+    }
+}
+
+fn pixels(rect: Rect) -> (isize, isize, usize, usize) {
+    (
+        rect.x.round() as isize,
+        rect.y.round() as isize,
+        rect.w.round().max(0.0) as usize,
+        rect.h.round().max(0.0) as usize,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rendering::Gradient, types::Color};
+
+    const SIZE: Size = Size { w: 50.0, h: 40.0 };
+
+    #[test]
+    fn rects_are_drawn_on_whole_pixels_at_the_canvas_depth() {
+        let mut list = DisplayList::new();
+        let mut canvas = Canvas::new(&mut list, SIZE, 3);
+        assert_eq!(canvas.size(), SIZE);
+        canvas.fill_rect(Rect::new(1.4, 2.6, 10.5, 4.2), Color::BLACK);
+        let gradient = Gradient::linear(
+            Position::default(),
+            Position { x: 10.0, y: 0.0 },
+            vec![(0.0, Color::BLACK), (1.0, Color::WHITE)],
+        );
+        canvas.fill_rect(Rect::new(0.0, 0.0, 10.0, -5.0), gradient);
+        canvas.draw_text(Position { x: 2.5, y: 3.4 }, "a", 1, Color::WHITE);
+        drop(canvas);
+        let lines: Vec<String> = list
+            .to_text()
+            .lines()
+            .map(|l| l.split(' ').take(4).collect::<Vec<_>>().join(" "))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "fill_rect 1 3 11x4",
+                "rounded_rect 0 0 10x0",
+                "text 3 3 scale=1",
+            ]
+        );
+        assert!(list.to_text().lines().all(|l| l.contains("z=3")));
+    }
+
+    #[test]
+    fn clips_left_pushed_are_popped() {
+        let mut list = DisplayList::new();
+        let mut canvas = Canvas::new(&mut list, SIZE, 0);
+        // nothing to pop yet
+        canvas.pop_clip();
+        canvas.push_clip(Rect::new(0.0, 0.0, 10.0, 10.0));
+        canvas.push_clip(Rect::new(2.0, 2.0, 5.0, 5.0));
+        canvas.pop_clip();
+        canvas.push_clip(Rect::new(1.0, 1.0, 5.0, 5.0));
+        drop(canvas);
+        assert_eq!(
+            list.to_text(),
+            "push_clip 0 0 10x10\n\
+             \x20 push_clip 2 2 5x5\n\
+             \x20 pop_clip\n\
+             \x20 push_clip 1 1 5x5\n\
+             \x20 pop_clip\n\
+             pop_clip\n"
+        );
+    }
+}
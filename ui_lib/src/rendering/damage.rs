@@ -0,0 +1,101 @@
+use crate::types::{Rect, Size};
+
+// Parts of the window that have to be painted again. The rectangles never overlap, so
+// nothing is blended twice when they are painted one after the other.
+#[derive(Default)]
+pub struct DamageRegion {
+    rects: Vec<Rect>,
+    everything: bool,
+}
+
+impl DamageRegion {
+    pub fn add(&mut self, rect: Rect) {
+        // whole pixels, so the edges get cleared too
+        let x = rect.x.floor();
+        let y = rect.y.floor();
+        let mut rect = Rect::new(x, y, rect.right().ceil() - x, rect.bottom().ceil() - y);
+        if rect.is_empty() || self.everything {
+            return;
+        }
+        while let Some(i) = self.rects.iter().position(|r| r.overlaps(&rect)) {
+            rect = rect.union(&self.rects.swap_remove(i));
+        }
+        self.rects.push(rect);
+    }
+
+    pub fn add_everything(&mut self) {
+        self.everything = true;
+        self.rects.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.everything && self.rects.is_empty()
+    }
+
+    // Empties the region, giving the rectangles to paint in a window of `size`
+    pub fn take(&mut self, size: Size) -> Vec<Rect> {
+        let window = Rect::new(0.0, 0.0, size.w, size.h);
+        let rects = if self.everything {
+            vec![window]
+        } else {
+            self.rects
+                .iter()
+                .map(|r| r.intersect(&window))
+                .filter(|r| !r.is_empty())
+                .collect()
+        };
+        *self = Self::default();
+        rects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Size = Size { w: 100.0, h: 100.0 };
+
+    #[test]
+    fn overlapping_rects_are_merged() {
+        let mut damage = DamageRegion::default();
+        damage.add(Rect::new(0.0, 0.0, 10.0, 10.0));
+        damage.add(Rect::new(50.0, 50.0, 10.0, 10.0));
+        damage.add(Rect::new(5.0, 5.0, 50.0, 10.0));
+        let mut rects = damage.take(WINDOW);
+        rects.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(0.0, 0.0, 55.0, 15.0),
+                Rect::new(50.0, 50.0, 10.0, 10.0)
+            ]
+        );
+        assert!(damage.is_empty());
+    }
+
+    #[test]
+    fn rects_grow_to_whole_pixels_and_stay_in_the_window() {
+        let mut damage = DamageRegion::default();
+        damage.add(Rect::new(0.5, 0.5, 1.0, 1.0));
+        damage.add(Rect::new(90.0, -5.0, 20.0, 10.0));
+        damage.add(Rect::new(200.0, 200.0, 10.0, 10.0));
+        let mut rects = damage.take(WINDOW);
+        rects.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(0.0, 0.0, 2.0, 2.0),
+                Rect::new(90.0, 0.0, 10.0, 5.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn everything_covers_the_window() {
+        let mut damage = DamageRegion::default();
+        assert!(damage.is_empty());
+        damage.add_everything();
+        damage.add(Rect::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(damage.take(WINDOW), vec![Rect::new(0.0, 0.0, 100.0, 100.0)]);
+    }
+}
@@ -0,0 +1,977 @@
+use crate::types::Color;
+
+use super::{Image, ImageError};
+
+// Guesses the format from the first bytes
+pub fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        decode_png(bytes)
+    } else if bytes.starts_with(b"BM") {
+        decode_bmp(bytes)
+    } else if bytes.len() >= 2 && bytes[0] == b'P' && (b'1'..=b'6').contains(&bytes[1]) {
+        decode_ppm(bytes)
+    } else {
+        Err(ImageError::UnknownFormat)
+    }
+}
+
+fn invalid(what: &str) -> ImageError {
+    ImageError::Invalid(what.to_string())
+}
+
+fn unsupported(what: &str) -> ImageError {
+    ImageError::Unsupported(what.to_string())
+}
+
+// Refuses sizes whose pixels wouldn't fit in memory before allocating them
+fn checked_size(width: usize, height: usize) -> Result<usize, ImageError> {
+    match width.checked_mul(height) {
+        Some(n) if n <= 1 << 28 => Ok(n),
+        _ => Err(unsupported("image too big")),
+    }
+}
+
+// PNG
+
+pub fn decode_png(bytes: &[u8]) -> Result<Image, ImageError> {
+    let mut pos = 8;
+    let mut header = None;
+    let mut palette: Vec<Color> = Vec::new();
+    let mut transparency: Vec<u8> = Vec::new();
+    let mut data = Vec::new();
+    loop {
+        let Some(chunk) = bytes.get(pos..pos + 8) else {
+            return Err(invalid("truncated PNG"));
+        };
+        let len = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as usize;
+        let kind = &chunk[4..8];
+        let body = bytes
+            .get(pos + 8..pos + 8 + len)
+            .ok_or_else(|| invalid("truncated PNG chunk"))?;
+        // and the CRC, which isn't checked
+        pos += 12 + len;
+        match kind {
+            b"IHDR" => header = Some(PngHeader::parse(body)?),
+            b"PLTE" => {
+                palette = body
+                    .chunks_exact(3)
+                    .map(|c| Color::rgb(c[0], c[1], c[2]))
+                    .collect()
+            }
+            b"tRNS" => transparency = body.to_vec(),
+            b"IDAT" => data.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+    let header = header.ok_or_else(|| invalid("PNG without header"))?;
+    checked_size(header.width, header.height)?;
+    let raw = zlib_decompress(&data)?;
+
+    let mut samples = vec![0u16; header.width * header.height * header.channels()];
+    let passes: &[(usize, usize, usize, usize)] = if header.interlaced {
+        // first column, first row, column step and row step of each Adam7 pass
+        &[
+            (0, 0, 8, 8),
+            (4, 0, 8, 8),
+            (0, 4, 4, 8),
+            (2, 0, 4, 4),
+            (0, 2, 2, 4),
+            (1, 0, 2, 2),
+            (0, 1, 1, 2),
+        ]
+    } else {
+        &[(0, 0, 1, 1)]
+    };
+    let mut offset = 0;
+    for &(x0, y0, dx, dy) in passes {
+        let w = (header.width + dx - 1 - x0) / dx;
+        let h = (header.height + dy - 1 - y0) / dy;
+        if w == 0 || h == 0 {
+            continue;
+        }
+        let stride = (w * header.bits_per_pixel()).div_ceil(8);
+        let size = (stride + 1) * h;
+        let pass = raw
+            .get(offset..offset + size)
+            .ok_or_else(|| invalid("not enough PNG data"))?;
+        offset += size;
+        let rows = unfilter(pass, stride, header.bytes_per_pixel())?;
+        for (j, row) in rows.chunks(stride).enumerate() {
+            let y = y0 + j * dy;
+            for i in 0..w {
+                let x = x0 + i * dx;
+                let start = (y * header.width + x) * header.channels();
+                for c in 0..header.channels() {
+                    samples[start + c] = header.sample(row, i * header.channels() + c);
+                }
+            }
+        }
+    }
+
+    let max = ((1u32 << header.depth) - 1) as f32;
+    let level = |s: u16| (s as f32 * 255.0 / max).round() as u8;
+    // the sample value of the one transparent gray or color
+    let key = |i: usize| {
+        transparency
+            .get(2 * i..2 * i + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let pixels = samples
+        .chunks_exact(header.channels())
+        .map(|s| match header.color_type {
+            0 => {
+                let g = level(s[0]);
+                let a = if key(0) == Some(s[0]) { 0 } else { 0xFF };
+                Color::argb(a, g, g, g)
+            }
+            2 => {
+                let (r, g, b) = (level(s[0]), level(s[1]), level(s[2]));
+                let transparent = (0..3).all(|i| key(i) == Some(s[i]));
+                Color::argb(if transparent { 0 } else { 0xFF }, r, g, b)
+            }
+            3 => {
+                let i = s[0] as usize;
+                let c = palette.get(i).copied().unwrap_or(Color::BLACK);
+                c.with_alpha(transparency.get(i).copied().unwrap_or(0xFF))
+            }
+            4 => {
+                let g = level(s[0]);
+                Color::argb(level(s[1]), g, g, g)
+            }
+            _ => Color::argb(level(s[3]), level(s[0]), level(s[1]), level(s[2])),
+        })
+        .collect();
+    Ok(Image::new(header.width, header.height, pixels))
+}
+
+struct PngHeader {
+    width: usize,
+    height: usize,
+    depth: usize,
+    color_type: u8,
+    interlaced: bool,
+}
+
+impl PngHeader {
+    fn parse(body: &[u8]) -> Result<Self, ImageError> {
+        if body.len() < 13 {
+            return Err(invalid("short PNG header"));
+        }
+        let header = Self {
+            width: u32::from_be_bytes([body[0], body[1], body[2], body[3]]) as usize,
+            height: u32::from_be_bytes([body[4], body[5], body[6], body[7]]) as usize,
+            depth: body[8] as usize,
+            color_type: body[9],
+            interlaced: body[12] == 1,
+        };
+        let depths: &[usize] = match header.color_type {
+            0 => &[1, 2, 4, 8, 16],
+            3 => &[1, 2, 4, 8],
+            2 | 4 | 6 => &[8, 16],
+            _ => return Err(invalid("unknown PNG color type")),
+        };
+        if !depths.contains(&header.depth) {
+            return Err(invalid("bad PNG bit depth"));
+        }
+        if body[10] != 0 || body[11] != 0 || body[12] > 1 {
+            return Err(unsupported("PNG compression, filter or interlace method"));
+        }
+        Ok(header)
+    }
+
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.depth
+    }
+
+    // What filters compare a byte with, at least one
+    fn bytes_per_pixel(&self) -> usize {
+        self.bits_per_pixel().div_ceil(8)
+    }
+
+    // The n-th sample of an unfiltered row
+    fn sample(&self, row: &[u8], n: usize) -> u16 {
+        match self.depth {
+            16 => u16::from_be_bytes([row[2 * n], row[2 * n + 1]]),
+            8 => row[n] as u16,
+            d => {
+                let per_byte = 8 / d;
+                let shift = 8 - d * (n % per_byte + 1);
+                ((row[n / per_byte] >> shift) & ((1 << d) - 1) as u8) as u16
+            }
+        }
+    }
+}
+
+// Rows of `stride` bytes, each after its filter type
+fn unfilter(data: &[u8], stride: usize, bpp: usize) -> Result<Vec<u8>, ImageError> {
+    let rows = data.len() / (stride + 1);
+    let mut out = vec![0u8; rows * stride];
+    for j in 0..rows {
+        let filter = data[j * (stride + 1)];
+        let line = &data[j * (stride + 1) + 1..(j + 1) * (stride + 1)];
+        let (done, current) = out.split_at_mut(j * stride);
+        let previous = if j > 0 {
+            &done[(j - 1) * stride..]
+        } else {
+            &[][..]
+        };
+        let current = &mut current[..stride];
+        for i in 0..stride {
+            let a = if i >= bpp { current[i - bpp] } else { 0 };
+            let b = previous.get(i).copied().unwrap_or(0);
+            let c = if i >= bpp {
+                previous.get(i - bpp).copied().unwrap_or(0)
+            } else {
+                0
+            };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(invalid("unknown PNG filter")),
+            };
+            current[i] = line[i].wrapping_add(predicted);
+        }
+    }
+    Ok(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+// Inflate, without checking the Adler-32 at the end
+fn zlib_decompress(data: &[u8]) -> Result<Vec<u8>, ImageError> {
+    if data.len() < 2
+        || data[0] & 0x0F != 8
+        || !(data[0] as u16 * 256 + data[1] as u16).is_multiple_of(31)
+    {
+        return Err(invalid("bad zlib header"));
+    }
+    if data[1] & 0x20 != 0 {
+        return Err(unsupported("zlib preset dictionary"));
+    }
+    let mut bits = BitReader {
+        data: &data[2..],
+        pos: 0,
+        bit: 0,
+    };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => {
+                bits.align();
+                let len = bits.read(16)? as usize;
+                let nlen = bits.read(16)? as usize;
+                if len != !nlen & 0xFFFF {
+                    return Err(invalid("bad stored block length"));
+                }
+                let block = bits
+                    .data
+                    .get(bits.pos..bits.pos + len)
+                    .ok_or_else(|| invalid("truncated stored block"))?;
+                out.extend_from_slice(block);
+                bits.pos += len;
+            }
+            1 => {
+                let mut lengths = [0u8; 288 + 32];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..288].fill(8);
+                lengths[288..].fill(5);
+                let literals = Huffman::new(&lengths[..288])?;
+                let distances = Huffman::new(&lengths[288..])?;
+                inflate_block(&mut bits, &literals, &distances, &mut out)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_tables(&mut bits)?;
+                inflate_block(&mut bits, &literals, &distances, &mut out)?;
+            }
+            _ => return Err(invalid("bad deflate block type")),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl BitReader<'_> {
+    // Least significant bit first
+    fn read(&mut self, count: u32) -> Result<u32, ImageError> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = self
+                .data
+                .get(self.pos)
+                .ok_or_else(|| invalid("truncated deflate data"))?;
+            value |= ((*byte as u32 >> self.bit) & 1) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+    }
+}
+
+// Canonical codes: how many codes of each length, and the symbols sorted by code
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, ImageError> {
+        let mut counts = [0u16; 16];
+        for &l in lengths {
+            counts[l as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for i in 1..16 {
+            offsets[i] = offsets[i - 1] + counts[i - 1];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &l) in lengths.iter().enumerate() {
+            if l != 0 {
+                symbols[offsets[l as usize] as usize] = symbol as u16;
+                offsets[l as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u16, ImageError> {
+        // first code of the current length, and index of its symbol
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= bits.read(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("bad Huffman code"))
+    }
+}
+
+fn dynamic_tables(bits: &mut BitReader) -> Result<(Huffman, Huffman), ImageError> {
+    const ORDER: [usize; 19] = [
+        16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+    ];
+    let literal_count = bits.read(5)? as usize + 257;
+    let distance_count = bits.read(5)? as usize + 1;
+    let code_count = bits.read(4)? as usize + 4;
+    let mut code_lengths = [0u8; 19];
+    for &i in &ORDER[..code_count] {
+        code_lengths[i] = bits.read(3)? as u8;
+    }
+    let codes = Huffman::new(&code_lengths)?;
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match codes.decode(bits)? {
+            l @ 0..=15 => (l as u8, 1),
+            16 => {
+                let previous = *lengths.last().ok_or_else(|| invalid("nothing to repeat"))?;
+                (previous, 3 + bits.read(2)? as usize)
+            }
+            17 => (0, 3 + bits.read(3)? as usize),
+            _ => (0, 11 + bits.read(7)? as usize),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+    if lengths.len() != literal_count + distance_count {
+        return Err(invalid("too many code lengths"));
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+fn inflate_block(
+    bits: &mut BitReader,
+    literals: &Huffman,
+    distances: &Huffman,
+    out: &mut Vec<u8>,
+) -> Result<(), ImageError> {
+    const LENGTHS: [u16; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+        131, 163, 195, 227, 258,
+    ];
+    const LENGTH_BITS: [u8; 29] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+    ];
+    const DISTANCES: [u16; 30] = [
+        1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+        2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+    ];
+    const DISTANCE_BITS: [u8; 30] = [
+        0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12,
+        13, 13,
+    ];
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let i = symbol - 257;
+                let len = LENGTHS[i] as usize + bits.read(LENGTH_BITS[i] as u32)? as usize;
+                let d = distances.decode(bits)? as usize;
+                if d >= 30 {
+                    return Err(invalid("bad deflate distance"));
+                }
+                let distance = DISTANCES[d] as usize + bits.read(DISTANCE_BITS[d] as u32)? as usize;
+                if distance > out.len() {
+                    return Err(invalid("deflate distance before the start"));
+                }
+                let start = out.len() - distance;
+                // the copy can overlap what it writes
+                for k in 0..len {
+                    out.push(out[start + k]);
+                }
+            }
+            _ => return Err(invalid("bad deflate length")),
+        }
+    }
+}
+
+// BMP
+
+pub fn decode_bmp(bytes: &[u8]) -> Result<Image, ImageError> {
+    let u16_at = |i: usize| -> Result<u16, ImageError> {
+        bytes
+            .get(i..i + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .ok_or_else(|| invalid("truncated BMP"))
+    };
+    let u32_at = |i: usize| -> Result<u32, ImageError> {
+        bytes
+            .get(i..i + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| invalid("truncated BMP"))
+    };
+    let data_offset = u32_at(10)? as usize;
+    let header_size = u32_at(14)? as usize;
+    if header_size < 40 {
+        return Err(unsupported("BMP core header"));
+    }
+    let width = u32_at(18)? as i32;
+    let height = u32_at(22)? as i32;
+    let bit_count = u16_at(28)?;
+    let compression = u32_at(30)?;
+    if width <= 0 || height == 0 {
+        return Err(invalid("bad BMP size"));
+    }
+    // rows are stored from the bottom one unless the height is negative
+    let (width, height, bottom_up) = (width as usize, height.unsigned_abs() as usize, height > 0);
+    checked_size(width, height)?;
+
+    // masks of the red, green, blue and alpha bits, an empty alpha mask meaning opaque
+    let masks = match (compression, bit_count) {
+        (0, 32) => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+        (0, 16) => [0x7C00, 0x03E0, 0x001F, 0],
+        (0, _) => [0; 4],
+        // right after the 40 bytes every header starts with
+        (3 | 6, 16 | 32) => {
+            let alpha = if compression == 6 || header_size > 40 {
+                u32_at(66)?
+            } else {
+                0
+            };
+            [u32_at(54)?, u32_at(58)?, u32_at(62)?, alpha]
+        }
+        _ => return Err(unsupported("compressed BMP")),
+    };
+    let palette: Vec<Color> = if bit_count <= 8 {
+        let colors = match u32_at(46)? {
+            0 => 1 << bit_count,
+            n => n as usize,
+        };
+        let start = 14 + header_size;
+        (0..colors)
+            .map(|i| {
+                let c = bytes
+                    .get(start + 4 * i..start + 4 * i + 3)
+                    .ok_or_else(|| invalid("truncated BMP palette"))?;
+                Ok(Color::rgb(c[2], c[1], c[0]))
+            })
+            .collect::<Result<_, ImageError>>()?
+    } else {
+        Vec::new()
+    };
+    let channel = |value: u32, mask: u32| {
+        if mask == 0 {
+            return 0xFF;
+        }
+        let max = mask >> mask.trailing_zeros();
+        (((value & mask) >> mask.trailing_zeros()) as f32 * 255.0 / max as f32).round() as u8
+    };
+
+    let stride = (width * bit_count as usize).div_ceil(32) * 4;
+    let mut pixels = Vec::with_capacity(width * height);
+    for j in 0..height {
+        let y = if bottom_up { height - 1 - j } else { j };
+        let row = bytes
+            .get(data_offset + y * stride..data_offset + (y + 1) * stride)
+            .ok_or_else(|| invalid("truncated BMP pixels"))?;
+        for x in 0..width {
+            let color = match bit_count {
+                1 | 2 | 4 | 8 => {
+                    let bits = bit_count as usize;
+                    let per_byte = 8 / bits;
+                    let shift = 8 - bits * (x % per_byte + 1);
+                    let i = (row[x / per_byte] >> shift) as usize & ((1 << bits) - 1);
+                    palette.get(i).copied().unwrap_or(Color::BLACK)
+                }
+                24 => Color::rgb(row[3 * x + 2], row[3 * x + 1], row[3 * x]),
+                16 | 32 => {
+                    let value = if bit_count == 16 {
+                        u16::from_le_bytes([row[2 * x], row[2 * x + 1]]) as u32
+                    } else {
+                        u32::from_le_bytes([
+                            row[4 * x],
+                            row[4 * x + 1],
+                            row[4 * x + 2],
+                            row[4 * x + 3],
+                        ])
+                    };
+                    Color::argb(
+                        channel(value, masks[3]),
+                        channel(value, masks[0]),
+                        channel(value, masks[1]),
+                        channel(value, masks[2]),
+                    )
+                }
+                _ => return Err(unsupported("BMP bit count")),
+            };
+            pixels.push(color);
+        }
+    }
+    Ok(Image::new(width, height, pixels))
+}
+
+// Netpbm: PBM, PGM and PPM, as text or binary
+
+pub fn decode_ppm(bytes: &[u8]) -> Result<Image, ImageError> {
+    let kind = bytes[1];
+    let mut pos = 2;
+    // whitespace separated numbers, skipping comments
+    let number = |pos: &mut usize| -> Result<u32, ImageError> {
+        loop {
+            match bytes.get(*pos) {
+                Some(b'#') => {
+                    while bytes.get(*pos).is_some_and(|b| *b != b'\n') {
+                        *pos += 1;
+                    }
+                }
+                Some(b) if b.is_ascii_whitespace() => *pos += 1,
+                _ => break,
+            }
+        }
+        let start = *pos;
+        while bytes.get(*pos).is_some_and(|b| b.is_ascii_digit()) {
+            *pos += 1;
+        }
+        std::str::from_utf8(&bytes[start..*pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| invalid("bad Netpbm number"))
+    };
+    let width = number(&mut pos)? as usize;
+    let height = number(&mut pos)? as usize;
+    let max = if kind == b'1' || kind == b'4' {
+        1
+    } else {
+        number(&mut pos)?
+    };
+    if max == 0 || max > 0xFFFF {
+        return Err(invalid("bad Netpbm maximum value"));
+    }
+    let count = checked_size(width, height)?;
+    let channels = if kind == b'3' || kind == b'6' { 3 } else { 1 };
+
+    let samples: Vec<u32> = match kind {
+        b'1' | b'2' | b'3' => (0..count * channels)
+            .map(|_| {
+                // bits of a PBM can be written without spaces
+                if kind == b'1' {
+                    while bytes
+                        .get(pos)
+                        .is_some_and(|b| !b.is_ascii_digit() && *b != b'#')
+                    {
+                        pos += 1;
+                    }
+                    if bytes.get(pos).is_some_and(|b| b.is_ascii_digit()) {
+                        pos += 1;
+                        return Ok((bytes[pos - 1] - b'0') as u32);
+                    }
+                }
+                number(&mut pos)
+            })
+            .collect::<Result<_, _>>()?,
+        b'4' => {
+            // a single whitespace before the data
+            let data = bytes.get(pos + 1..).unwrap_or_default();
+            let stride = width.div_ceil(8);
+            if data.len() < stride * height {
+                return Err(invalid("truncated PBM"));
+            }
+            (0..count)
+                .map(|i| {
+                    let (x, y) = (i % width, i / width);
+                    (data[y * stride + x / 8] >> (7 - x % 8)) as u32 & 1
+                })
+                .collect()
+        }
+        _ => {
+            let data = bytes.get(pos + 1..).unwrap_or_default();
+            let size = if max > 0xFF { 2 } else { 1 };
+            if data.len() < count * channels * size {
+                return Err(invalid("truncated Netpbm data"));
+            }
+            (0..count * channels)
+                .map(|i| match size {
+                    2 => u16::from_be_bytes([data[2 * i], data[2 * i + 1]]) as u32,
+                    _ => data[i] as u32,
+                })
+                .collect()
+        }
+    };
+    let level = |s: u32| (s.min(max) as f32 * 255.0 / max as f32).round() as u8;
+    let pixels = samples
+        .chunks_exact(channels)
+        .map(|s| match kind {
+            // 1 is black
+            b'1' | b'4' => {
+                let g = if s[0] == 1 { 0 } else { 0xFF };
+                Color::rgb(g, g, g)
+            }
+            b'2' | b'5' => {
+                let g = level(s[0]);
+                Color::rgb(g, g, g)
+            }
+            _ => Color::rgb(level(s[0]), level(s[1]), level(s[2])),
+        })
+        .collect();
+    Ok(Image::new(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::rgb(0xFF, 0, 0);
+    const BLUE: Color = Color::rgb(0, 0, 0xFF);
+
+    // Stored deflate blocks, with an Adler-32 that isn't checked
+    fn zlib_stored(raw: &[u8]) -> Vec<u8> {
+        let len = raw.len() as u16;
+        let mut zlib = vec![0x78, 0x01, 1];
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(raw);
+        zlib.extend_from_slice(&[0; 4]);
+        zlib
+    }
+
+    // Chunks with a CRC that isn't checked either
+    fn png(
+        width: u32,
+        height: u32,
+        depth: u8,
+        color_type: u8,
+        chunks: &[(&[u8; 4], &[u8])],
+    ) -> Vec<u8> {
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let chunks = [(b"IHDR", header.as_slice())]
+            .into_iter()
+            .chain(chunks.iter().copied())
+            .chain([(b"IEND", &[][..])]);
+        for (kind, body) in chunks {
+            png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            png.extend_from_slice(kind);
+            png.extend_from_slice(body);
+            png.extend_from_slice(&[0; 4]);
+        }
+        png
+    }
+
+    fn gray(g: u8) -> Color {
+        Color::rgb(g, g, g)
+    }
+
+    #[test]
+    fn inflates_fixed_and_dynamic_huffman_blocks() {
+        let fixed = [
+            0x78, 0xda, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01, 0x68, 0x03,
+            0x08, 0xb1,
+        ];
+        assert_eq!(zlib_decompress(&fixed).unwrap(), b"hello hello hello hello");
+        // 400 letters, (i * i + i / 7) % 26 from 'a'
+        let dynamic = [
+            0x78, 0xda, 0xed, 0xcc, 0xc1, 0x0d, 0xc0, 0x20, 0x08, 0x00, 0xc0, 0x59, 0x51, 0x31,
+            0xa2, 0x52, 0x25, 0x60, 0x50, 0xa7, 0xef, 0x0c, 0xfd, 0xf7, 0x06, 0x38, 0x08, 0x58,
+            0xe5, 0xb6, 0xf3, 0xe0, 0xd6, 0x39, 0xc4, 0x4e, 0x1e, 0x97, 0xa3, 0x71, 0xc1, 0x84,
+            0xf4, 0xac, 0x34, 0x82, 0x50, 0x70, 0x53, 0xf3, 0x58, 0x35, 0x49, 0x76, 0xe9, 0x54,
+            0xa8, 0xcb, 0x29, 0x9a, 0x57, 0x47, 0xd8, 0xbe, 0x21, 0xb3, 0x97, 0x55, 0xc1, 0x06,
+            0x77, 0x9e, 0x2b, 0x34, 0xaf, 0x67, 0x52, 0x02, 0xf8, 0xeb, 0xef, 0xf5, 0x0b, 0xa0,
+            0xa4, 0xaa, 0x7e,
+        ];
+        let expected: Vec<u8> = (0..400usize)
+            .map(|i| ((i * i + i / 7) % 26) as u8 + b'a')
+            .collect();
+        assert_eq!(zlib_decompress(&dynamic).unwrap(), expected);
+    }
+
+    #[test]
+    fn bad_zlib_streams_are_refused() {
+        assert_eq!(
+            zlib_decompress(&[0x78, 0x02, 0x03, 0x00]),
+            Err(invalid("bad zlib header"))
+        );
+        assert_eq!(
+            zlib_decompress(&[0x78, 0x20, 0x00]),
+            Err(unsupported("zlib preset dictionary"))
+        );
+        assert_eq!(
+            zlib_decompress(&[0x78, 0x01, 0x01, 0x05, 0x00, 0x00, 0x00]),
+            Err(invalid("bad stored block length"))
+        );
+        assert_eq!(
+            zlib_decompress(&[0x78, 0x01, 0x07]),
+            Err(invalid("bad deflate block type"))
+        );
+        assert_eq!(
+            zlib_decompress(&[0x78, 0xda, 0xcb, 0x48]),
+            Err(invalid("truncated deflate data"))
+        );
+    }
+
+    #[test]
+    fn decodes_png_color_types() {
+        let rgba = png(
+            2,
+            1,
+            8,
+            6,
+            &[(b"IDAT", &zlib_stored(&[0, 255, 0, 0, 255, 0, 0, 255, 128]))],
+        );
+        assert_eq!(
+            decode(&rgba).unwrap().pixels,
+            vec![RED, BLUE.with_alpha(128)]
+        );
+        // 2 bits per index, the first color transparent
+        let indexed = png(
+            3,
+            1,
+            2,
+            3,
+            &[
+                (b"PLTE", &[255, 0, 0, 0, 0, 255, 0, 0, 0]),
+                (b"tRNS", &[0]),
+                (b"IDAT", &zlib_stored(&[0, 0b0001_1000])),
+            ],
+        );
+        assert_eq!(
+            decode(&indexed).unwrap().pixels,
+            vec![RED.with_alpha(0), BLUE, Color::BLACK]
+        );
+        // 16 bits, the gray of the key transparent
+        let keyed = png(
+            2,
+            1,
+            16,
+            0,
+            &[
+                (b"tRNS", &[0x12, 0x34]),
+                (b"IDAT", &zlib_stored(&[0, 0x12, 0x34, 0xFF, 0xFF])),
+            ],
+        );
+        let pixels = decode(&keyed).unwrap().pixels;
+        assert_eq!(pixels[0].a, 0);
+        assert_eq!(pixels[1], Color::WHITE);
+    }
+
+    #[test]
+    fn png_rows_are_unfiltered() {
+        // Sub, then Up, then Average, then Paeth
+        let raw = [
+            1, 10, 5, 5, //
+            2, 1, 1, 1, //
+            3, 2, 2, 2, //
+            4, 1, 1, 1,
+        ];
+        let image = decode(&png(3, 4, 8, 0, &[(b"IDAT", &zlib_stored(&raw))])).unwrap();
+        let values: Vec<u8> = image.pixels.iter().map(|c| c.r).collect();
+        assert_eq!(values, vec![10, 15, 20, 11, 16, 21, 7, 13, 19, 8, 14, 20]);
+    }
+
+    #[test]
+    fn interlaced_pngs_are_put_back_together() {
+        // the first pass has the top left pixel, the sixth the top right one and the last
+        // the bottom row
+        let mut image = png(
+            2,
+            2,
+            8,
+            0,
+            &[(b"IDAT", &zlib_stored(&[0, 1, 0, 2, 0, 3, 4]))],
+        );
+        // interlace method in the header
+        image[28] = 1;
+        assert_eq!(
+            decode(&image).unwrap().pixels,
+            vec![gray(1), gray(2), gray(3), gray(4)]
+        );
+    }
+
+    #[test]
+    fn broken_pngs_are_refused() {
+        let image = png(2, 1, 8, 6, &[(b"IDAT", &zlib_stored(&[0, 255, 0]))]);
+        assert_eq!(decode(&image), Err(invalid("not enough PNG data")));
+        let image = png(1, 1, 3, 2, &[]);
+        assert_eq!(decode(&image), Err(invalid("bad PNG bit depth")));
+        let image = png(1, 1, 8, 0, &[]);
+        assert_eq!(decode(&image[..25]), Err(invalid("truncated PNG chunk")));
+        assert_eq!(decode(&image[..30]), Err(invalid("truncated PNG")));
+        assert_eq!(
+            decode(b"GIF89a").map(|i| i.width),
+            Err(ImageError::UnknownFormat)
+        );
+    }
+
+    // A 40 bytes header, then what goes between it and the pixels
+    fn bmp(
+        width: i32,
+        height: i32,
+        bit_count: u16,
+        compression: u32,
+        extra: &[u8],
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        let offset = 54 + extra.len() as u32;
+        let mut bmp = b"BM".to_vec();
+        bmp.extend_from_slice(&(offset + pixels.len() as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&offset.to_le_bytes());
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&width.to_le_bytes());
+        bmp.extend_from_slice(&height.to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&bit_count.to_le_bytes());
+        bmp.extend_from_slice(&compression.to_le_bytes());
+        bmp.extend_from_slice(&[0; 20]);
+        bmp.extend_from_slice(extra);
+        bmp.extend_from_slice(pixels);
+        bmp
+    }
+
+    #[test]
+    fn decodes_bmp_rows_from_the_bottom_or_the_top() {
+        // rows padded to 4 bytes, blue green red
+        let pixels = [0, 0, 255, 255, 0, 0, 0, 0, 255, 255, 255, 0, 0, 0, 0, 0];
+        let image = decode(&bmp(2, 2, 24, 0, &[], &pixels)).unwrap();
+        assert_eq!(image.pixels, vec![Color::WHITE, Color::BLACK, RED, BLUE]);
+        let image = decode(&bmp(2, -2, 24, 0, &[], &pixels)).unwrap();
+        assert_eq!(image.pixels, vec![RED, BLUE, Color::WHITE, Color::BLACK]);
+    }
+
+    #[test]
+    fn decodes_bmp_palettes_and_bit_fields() {
+        let palette = [0, 0, 255, 0, 255, 0, 0, 0];
+        let image = decode(&bmp(3, 1, 1, 0, &palette, &[0b0100_0000, 0, 0, 0])).unwrap();
+        assert_eq!(image.pixels, vec![RED, BLUE, RED]);
+
+        // 4 bits of each, alpha on top
+        let masks: Vec<u8> = [0x0F00u32, 0x00F0, 0x000F, 0xF000]
+            .iter()
+            .flat_map(|m| m.to_le_bytes())
+            .collect();
+        let mut image = bmp(2, 1, 16, 3, &masks, &[0x00, 0x8F, 0x0F, 0xF0]);
+        // a bigger header, holding the alpha mask
+        image[14] = 56;
+        assert_eq!(
+            decode(&image).unwrap().pixels,
+            vec![RED.with_alpha(0x88), BLUE]
+        );
+        assert_eq!(
+            decode(&bmp(1, 1, 24, 1, &[], &[0; 4])),
+            Err(unsupported("compressed BMP"))
+        );
+        assert_eq!(
+            decode(&bmp(2, 2, 24, 0, &[], &[0; 8])),
+            Err(invalid("truncated BMP pixels"))
+        );
+    }
+
+    #[test]
+    fn decodes_netpbm_as_text_and_binary() {
+        let ppm = decode(b"P3\n# two pixels\n2 1\n255\n255 0 0  0 0 255\n").unwrap();
+        assert_eq!(ppm.pixels, vec![RED, BLUE]);
+        // bits without spaces, 1 being black
+        let pbm = decode(b"P1 3 1 010").unwrap();
+        assert_eq!(pbm.pixels, vec![Color::WHITE, Color::BLACK, Color::WHITE]);
+        let pbm = decode(b"P4 3 1\n\xA0").unwrap();
+        assert_eq!(pbm.pixels, vec![Color::BLACK, Color::WHITE, Color::BLACK]);
+        let pgm = decode(b"P5 2 1 65535\n\x80\x00\xFF\xFF").unwrap();
+        assert_eq!(pgm.pixels, vec![gray(128), Color::WHITE]);
+        let ppm = decode(b"P6 1 1 15\n\x0F\x00\x05").unwrap();
+        assert_eq!(ppm.pixels, vec![Color::rgb(255, 0, 85)]);
+        assert_eq!(
+            decode(b"P6 2 2 255\n\x00"),
+            Err(invalid("truncated Netpbm data"))
+        );
+        assert_eq!(
+            decode(b"P2 1 1 0\n0"),
+            Err(invalid("bad Netpbm maximum value"))
+        );
+    }
+}
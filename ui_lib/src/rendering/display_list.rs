@@ -0,0 +1,960 @@
+use std::{borrow::Cow, fmt::Write, sync::Arc, thread};
+
+use crate::types::{Color, Matrix, Position, Rect};
+
+use super::{
+    appbuffer::{outline_parts, Tile},
+    font, AppBuffer, BoxShadow, Contour, FillRule, FilterQuality, Image, Paint, Path, Shader,
+    ShadowMask, Stroke,
+};
+
+// Rows drawn by a thread at a time: small enough to share the work, big enough for the
+// commands out of a band to be few
+const TILE_ROWS: usize = 32;
+
+#[derive(Clone)]
+pub enum DisplayCommand {
+    FillRect {
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        color: Color,
+        depth: usize,
+    },
+    RoundedRect {
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        radius: f32,
+        paint: Paint,
+        depth: usize,
+    },
+    Text {
+        x: isize,
+        y: isize,
+        text: String,
+        scale: usize,
+        paint: Paint,
+        depth: usize,
+    },
+    Image {
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        image: Arc<Image>,
+        filter: FilterQuality,
+        depth: usize,
+    },
+    // Strokes are recorded as the area they cover
+    Path {
+        contours: Arc<Vec<Contour>>,
+        rule: FillRule,
+        paint: Paint,
+        depth: usize,
+    },
+    // Blurred when recorded
+    Shadow {
+        mask: Arc<ShadowMask>,
+        color: Color,
+        depth: usize,
+    },
+    Composite {
+        surface: Arc<AppBuffer>,
+        offset: (isize, isize),
+        opacity: f32,
+    },
+    // The matrix goes from window coordinates to window coordinates
+    CompositeTransformed {
+        surface: Arc<AppBuffer>,
+        matrix: Matrix,
+        filter: FilterQuality,
+        opacity: f32,
+    },
+    // Each push is matched by a pop of the same kind. Clips are moved by the current
+    // transform, and opacities multiply each other.
+    PushClip(Rect),
+    PopClip,
+    PushTransform(Position),
+    PopTransform,
+    PushOpacity(f32),
+    PopOpacity,
+}
+
+// What render objects draw during a frame, in order. It is rasterized afterwards, possibly
+// by several threads at once.
+#[derive(Default, Clone)]
+pub struct DisplayList {
+    commands: Vec<DisplayCommand>,
+}
+
+// Clips, transforms and opacities pushed while replaying
+struct ReplayState {
+    clips: Vec<Option<Rect>>,
+    offsets: Vec<Position>,
+    opacities: Vec<f32>,
+}
+
+impl ReplayState {
+    fn offset(&self) -> (isize, isize) {
+        let offset = self.offsets.last().copied().unwrap_or_default();
+        (offset.x.round() as isize, offset.y.round() as isize)
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacities.last().copied().unwrap_or(1.0)
+    }
+
+    // Gradients are moved along with what they fill
+    fn place<'p>(&self, paint: &'p Paint) -> Cow<'p, Paint> {
+        let (dx, dy) = self.offset();
+        if (dx, dy) == (0, 0) || paint.solid_color().is_some() {
+            return Cow::Borrowed(paint);
+        }
+        Cow::Owned(paint.translate(Position {
+            x: dx as f32,
+            y: dy as f32,
+        }))
+    }
+}
+
+impl DisplayList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &[DisplayCommand] {
+        &self.commands
+    }
+
+    pub fn push(&mut self, command: DisplayCommand) {
+        self.commands.push(command);
+    }
+
+    // Opaque, whatever the top byte of `color`
+    pub fn draw_rectangle(
+        &mut self,
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        color: u32,
+        depth: usize,
+    ) {
+        self.blend_rectangle(x, y, w, h, Color::from(color), depth);
+    }
+
+    pub fn blend_rectangle(
+        &mut self,
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        color: Color,
+        depth: usize,
+    ) {
+        self.push(DisplayCommand::FillRect {
+            x,
+            y,
+            w,
+            h,
+            color,
+            depth,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_rounded_rect(
+        &mut self,
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        radius: f32,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.push(DisplayCommand::RoundedRect {
+            x,
+            y,
+            w,
+            h,
+            radius,
+            paint: paint.into(),
+            depth,
+        });
+    }
+
+    pub fn draw_outline(&mut self, rect: Rect, thickness: f32, color: Color, depth: usize) {
+        for (x, y, w, h) in outline_parts(rect, thickness) {
+            self.blend_rectangle(x, y, w, h, color, depth);
+        }
+    }
+
+    pub fn draw_text(
+        &mut self,
+        x: isize,
+        y: isize,
+        text: &str,
+        scale: usize,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.push(DisplayCommand::Text {
+            x,
+            y,
+            text: text.to_string(),
+            scale,
+            paint: paint.into(),
+            depth,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_image(
+        &mut self,
+        x: isize,
+        y: isize,
+        w: usize,
+        h: usize,
+        image: Arc<Image>,
+        filter: FilterQuality,
+        depth: usize,
+    ) {
+        self.push(DisplayCommand::Image {
+            x,
+            y,
+            w,
+            h,
+            image,
+            filter,
+            depth,
+        });
+    }
+
+    pub fn fill_path(
+        &mut self,
+        path: &Path,
+        rule: FillRule,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.push(DisplayCommand::Path {
+            contours: Arc::new(path.flatten()),
+            rule,
+            paint: paint.into(),
+            depth,
+        });
+    }
+
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        stroke: &Stroke,
+        paint: impl Into<Paint>,
+        depth: usize,
+    ) {
+        self.push(DisplayCommand::Path {
+            contours: Arc::new(stroke.outline(path)),
+            rule: FillRule::NonZero,
+            paint: paint.into(),
+            depth,
+        });
+    }
+
+    pub fn draw_shadow(
+        &mut self,
+        rect: Rect,
+        border_radius: f32,
+        shadow: &BoxShadow,
+        depth: usize,
+    ) {
+        if shadow.color.a == 0 {
+            return;
+        }
+        self.push(DisplayCommand::Shadow {
+            mask: Arc::new(shadow.mask(rect, border_radius)),
+            color: shadow.color,
+            depth,
+        });
+    }
+
+    pub fn composite(&mut self, surface: Arc<AppBuffer>, offset: (isize, isize), opacity: f32) {
+        self.push(DisplayCommand::Composite {
+            surface,
+            offset,
+            opacity,
+        });
+    }
+
+    pub fn composite_transformed(
+        &mut self,
+        surface: Arc<AppBuffer>,
+        matrix: Matrix,
+        filter: FilterQuality,
+        opacity: f32,
+    ) {
+        self.push(DisplayCommand::CompositeTransformed {
+            surface,
+            matrix,
+            filter,
+            opacity,
+        });
+    }
+
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.push(DisplayCommand::PushClip(rect));
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.push(DisplayCommand::PopClip);
+    }
+
+    pub fn push_transform(&mut self, offset: Position) {
+        self.push(DisplayCommand::PushTransform(offset));
+    }
+
+    pub fn pop_transform(&mut self) {
+        self.push(DisplayCommand::PopTransform);
+    }
+
+    pub fn push_opacity(&mut self, opacity: f32) {
+        self.push(DisplayCommand::PushOpacity(opacity));
+    }
+
+    pub fn pop_opacity(&mut self) {
+        self.push(DisplayCommand::PopOpacity);
+    }
+
+    pub fn rasterize(&self, buffer: &mut AppBuffer) {
+        self.replay(&mut buffer.tile(), &self.bounds());
+    }
+
+    // Same result as `rasterize`, to the byte: the bands of the buffer are shared between
+    // threads, each replaying the whole list on its own bands
+    pub fn rasterize_parallel(&self, buffer: &mut AppBuffer) {
+        let bounds = self.bounds();
+        rasterize_tiles(buffer.tiles(TILE_ROWS), cores(), |tile| {
+            self.replay(tile, &bounds)
+        });
+    }
+
+    // Clears each damaged rectangle to `background` and draws it again, all in one pass of
+    // the threads. The same as clearing and rasterizing the rectangles one after the other.
+    pub fn rasterize_damage(&self, buffer: &mut AppBuffer, damage: &[Rect], background: u32) {
+        let bounds = self.bounds();
+        let tiles = buffer
+            .tiles(TILE_ROWS)
+            .into_iter()
+            .filter(|t| damage.iter().any(|r| r.overlaps(&t.area())))
+            .collect();
+        rasterize_tiles(tiles, cores(), |tile| {
+            let clip = tile.clip();
+            for rect in damage {
+                tile.set_clip(Some(clip.map_or(*rect, |c| c.intersect(rect))));
+                if tile.area().is_empty() {
+                    continue;
+                }
+                tile.clear(background, 0);
+                self.replay(tile, &bounds);
+            }
+            tile.set_clip(clip);
+        });
+    }
+
+    // Where each drawing command may touch the window, none for the pushes and pops
+    fn bounds(&self) -> Vec<Option<Rect>> {
+        let mut offsets: Vec<Position> = Vec::new();
+        self.commands
+            .iter()
+            .map(|command| {
+                let offset = offsets.last().copied().unwrap_or_default();
+                let (dx, dy) = (offset.x.round() as isize, offset.y.round() as isize);
+                let rect = |x: isize, y: isize, w: usize, h: usize| {
+                    Some(Rect::new(
+                        (x + dx) as f32,
+                        (y + dy) as f32,
+                        w as f32,
+                        h as f32,
+                    ))
+                };
+                match command {
+                    DisplayCommand::FillRect { x, y, w, h, .. }
+                    | DisplayCommand::RoundedRect { x, y, w, h, .. }
+                    | DisplayCommand::Image { x, y, w, h, .. } => rect(*x, *y, *w, *h),
+                    DisplayCommand::Text {
+                        x, y, text, scale, ..
+                    } => {
+                        let scale = (*scale).max(1);
+                        let columns = text.split('\n').map(|l| l.chars().count()).max();
+                        let lines = text.split('\n').count();
+                        rect(
+                            *x,
+                            *y,
+                            columns.unwrap_or(0) * font::ADVANCE * scale,
+                            lines * font::LINE_HEIGHT * scale,
+                        )
+                    }
+                    DisplayCommand::Path { contours, .. } => {
+                        let points = contours.iter().flat_map(|c| c.points.iter());
+                        let (mut min, mut max) = (
+                            Position {
+                                x: f32::INFINITY,
+                                y: f32::INFINITY,
+                            },
+                            Position {
+                                x: f32::NEG_INFINITY,
+                                y: f32::NEG_INFINITY,
+                            },
+                        );
+                        for p in points {
+                            min = Position {
+                                x: min.x.min(p.x),
+                                y: min.y.min(p.y),
+                            };
+                            max = Position {
+                                x: max.x.max(p.x),
+                                y: max.y.max(p.y),
+                            };
+                        }
+                        if min.x > max.x || min.y > max.y {
+                            return None;
+                        }
+                        // rows and columns partly covered count
+                        Some(
+                            Rect::new(
+                                min.x + dx as f32,
+                                min.y + dy as f32,
+                                max.x - min.x,
+                                max.y - min.y,
+                            )
+                            .inflate(1.0),
+                        )
+                    }
+                    DisplayCommand::Shadow { mask, .. } => {
+                        rect(mask.x, mask.y, mask.width, mask.height)
+                    }
+                    DisplayCommand::Composite {
+                        surface, offset, ..
+                    } => {
+                        let (x, y) = surface.origin();
+                        rect(x + offset.0, y + offset.1, surface.width, surface.height)
+                    }
+                    DisplayCommand::CompositeTransformed {
+                        surface, matrix, ..
+                    } => {
+                        let (x, y) = surface.origin();
+                        let area = Rect::new(
+                            x as f32,
+                            y as f32,
+                            surface.width as f32,
+                            surface.height as f32,
+                        );
+                        let matrix = matrix.then(&Matrix::translation(dx as f32, dy as f32));
+                        // the filter reads the pixels around
+                        Some(matrix.apply_rect(area).inflate(1.0))
+                    }
+                    DisplayCommand::PushTransform(o) => {
+                        offsets.push(offset + *o);
+                        None
+                    }
+                    DisplayCommand::PopTransform => {
+                        offsets.pop();
+                        None
+                    }
+                    DisplayCommand::PushClip(_)
+                    | DisplayCommand::PopClip
+                    | DisplayCommand::PushOpacity(_)
+                    | DisplayCommand::PopOpacity => None,
+                }
+            })
+            .collect()
+    }
+
+    // `bounds` come from `Self::bounds`, the commands out of the tile are skipped
+    fn replay(&self, tile: &mut Tile, bounds: &[Option<Rect>]) {
+        let mut state = ReplayState {
+            clips: Vec::new(),
+            offsets: Vec::new(),
+            opacities: Vec::new(),
+        };
+        let area = tile.area();
+        for (command, bounds) in self.commands.iter().zip(bounds) {
+            if bounds.is_some_and(|b| !b.overlaps(&area)) {
+                continue;
+            }
+            let (dx, dy) = state.offset();
+            let opacity = state.opacity();
+            match command {
+                DisplayCommand::FillRect {
+                    x,
+                    y,
+                    w,
+                    h,
+                    color,
+                    depth,
+                } => tile.blend_rectangle(
+                    x + dx,
+                    y + dy,
+                    *w,
+                    *h,
+                    color.with_opacity(opacity),
+                    *depth,
+                ),
+                DisplayCommand::RoundedRect {
+                    x,
+                    y,
+                    w,
+                    h,
+                    radius,
+                    paint,
+                    depth,
+                } => tile.draw_rounded_rect(
+                    x + dx,
+                    y + dy,
+                    *w,
+                    *h,
+                    *radius,
+                    &state.place(paint),
+                    opacity,
+                    *depth,
+                ),
+                DisplayCommand::Text {
+                    x,
+                    y,
+                    text,
+                    scale,
+                    paint,
+                    depth,
+                } => tile.draw_text(
+                    x + dx,
+                    y + dy,
+                    text,
+                    *scale,
+                    &state.place(paint),
+                    opacity,
+                    *depth,
+                ),
+                DisplayCommand::Image {
+                    x,
+                    y,
+                    w,
+                    h,
+                    image,
+                    filter,
+                    depth,
+                } => tile.draw_image(x + dx, y + dy, *w, *h, image, *filter, opacity, *depth),
+                DisplayCommand::Path {
+                    contours,
+                    rule,
+                    paint,
+                    depth,
+                } => tile.fill_contours(
+                    contours,
+                    (dx, dy),
+                    *rule,
+                    &state.place(paint),
+                    opacity,
+                    *depth,
+                ),
+                DisplayCommand::Shadow { mask, color, depth } => {
+                    tile.draw_mask(mask, (dx, dy), color.with_opacity(opacity), *depth)
+                }
+                DisplayCommand::Composite {
+                    surface,
+                    offset,
+                    opacity: surface_opacity,
+                } => tile.composite(
+                    surface,
+                    (offset.0 + dx, offset.1 + dy),
+                    surface_opacity * opacity,
+                ),
+                DisplayCommand::CompositeTransformed {
+                    surface,
+                    matrix,
+                    filter,
+                    opacity: surface_opacity,
+                } => tile.composite_transformed(
+                    surface,
+                    &matrix.then(&Matrix::translation(dx as f32, dy as f32)),
+                    *filter,
+                    surface_opacity * opacity,
+                ),
+                DisplayCommand::PushClip(rect) => {
+                    state.clips.push(tile.clip());
+                    let rect = rect.translate(Position {
+                        x: dx as f32,
+                        y: dy as f32,
+                    });
+                    tile.set_clip(Some(tile.clip().map_or(rect, |c| c.intersect(&rect))));
+                }
+                DisplayCommand::PopClip => {
+                    if let Some(clip) = state.clips.pop() {
+                        tile.set_clip(clip);
+                    }
+                }
+                DisplayCommand::PushTransform(offset) => {
+                    let current = state.offsets.last().copied().unwrap_or_default();
+                    state.offsets.push(current + *offset);
+                }
+                DisplayCommand::PopTransform => {
+                    state.offsets.pop();
+                }
+                DisplayCommand::PushOpacity(o) => state.opacities.push(opacity * o),
+                DisplayCommand::PopOpacity => {
+                    state.opacities.pop();
+                }
+            }
+        }
+        // a clip left pushed doesn't leak to what is drawn next
+        if let Some(clip) = state.clips.into_iter().next() {
+            tile.set_clip(clip);
+        }
+    }
+
+    // One line per command, nested in the pushes, e.g. for comparing frames in tests
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let mut indent = 0usize;
+        for command in &self.commands {
+            if matches!(
+                command,
+                DisplayCommand::PopClip | DisplayCommand::PopTransform | DisplayCommand::PopOpacity
+            ) {
+                indent = indent.saturating_sub(1);
+            }
+            let _ = writeln!(text, "{}{}", "  ".repeat(indent), describe(command));
+            if matches!(
+                command,
+                DisplayCommand::PushClip(_)
+                    | DisplayCommand::PushTransform(_)
+                    | DisplayCommand::PushOpacity(_)
+            ) {
+                indent += 1;
+            }
+        }
+        text
+    }
+}
+
+fn cores() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// Draws the tiles with up to `threads` threads, the tiles given out in turn
+fn rasterize_tiles(tiles: Vec<Tile>, threads: usize, draw: impl Fn(&mut Tile) + Sync) {
+    if threads < 2 || tiles.len() < 2 {
+        for mut tile in tiles {
+            draw(&mut tile);
+        }
+        return;
+    }
+    let mut groups: Vec<Vec<Tile>> = (0..threads.min(tiles.len())).map(|_| Vec::new()).collect();
+    let count = groups.len();
+    for (i, tile) in tiles.into_iter().enumerate() {
+        groups[i % count].push(tile);
+    }
+    let draw = &draw;
+    thread::scope(|s| {
+        for group in groups {
+            s.spawn(move || {
+                for mut tile in group {
+                    draw(&mut tile);
+                }
+            });
+        }
+    });
+}
+
+fn color_text(color: &Color) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        color.r, color.g, color.b, color.a
+    )
+}
+
+fn paint_text(paint: &Paint) -> String {
+    let gradient = match &paint.shader {
+        Shader::Solid(color) => return color_text(color),
+        Shader::Gradient(g) => g,
+    };
+    let stops: Vec<String> = gradient
+        .stops
+        .iter()
+        .map(|(t, c)| format!("{t} {}", color_text(c)))
+        .collect();
+    let dither = if paint.dither { " dithered" } else { "" };
+    format!(
+        "{:?} {:?} [{}]{dither}",
+        gradient.shape,
+        gradient.tile_mode,
+        stops.join(", ")
+    )
+}
+
+fn describe(command: &DisplayCommand) -> String {
+    match command {
+        DisplayCommand::FillRect {
+            x,
+            y,
+            w,
+            h,
+            color,
+            depth,
+        } => format!("fill_rect {x} {y} {w}x{h} {} z={depth}", color_text(color)),
+        DisplayCommand::RoundedRect {
+            x,
+            y,
+            w,
+            h,
+            radius,
+            paint,
+            depth,
+        } => format!(
+            "rounded_rect {x} {y} {w}x{h} r={radius} {} z={depth}",
+            paint_text(paint)
+        ),
+        DisplayCommand::Text {
+            x,
+            y,
+            text,
+            scale,
+            paint,
+            depth,
+        } => format!(
+            "text {x} {y} scale={scale} {} z={depth} {text:?}",
+            paint_text(paint)
+        ),
+        DisplayCommand::Image {
+            x,
+            y,
+            w,
+            h,
+            image,
+            filter,
+            depth,
+        } => format!(
+            "image {x} {y} {w}x{h} from {}x{} {filter:?} z={depth}",
+            image.width, image.height
+        ),
+        DisplayCommand::Path {
+            contours,
+            rule,
+            paint,
+            depth,
+        } => format!(
+            "path {} contours {} points {rule:?} {} z={depth}",
+            contours.len(),
+            contours.iter().map(|c| c.points.len()).sum::<usize>(),
+            paint_text(paint)
+        ),
+        DisplayCommand::Shadow { mask, color, depth } => format!(
+            "shadow {} {} {}x{} {} z={depth}",
+            mask.x,
+            mask.y,
+            mask.width,
+            mask.height,
+            color_text(color)
+        ),
+        DisplayCommand::Composite {
+            surface,
+            offset,
+            opacity,
+        } => {
+            let (x, y) = surface.origin();
+            format!(
+                "composite {} {} {}x{} opacity={opacity}",
+                x + offset.0,
+                y + offset.1,
+                surface.width,
+                surface.height
+            )
+        }
+        DisplayCommand::CompositeTransformed {
+            surface,
+            matrix: m,
+            filter,
+            opacity,
+        } => {
+            let (x, y) = surface.origin();
+            format!(
+                "composite {x} {y} {}x{} matrix({} {} {} {} {} {}) {filter:?} opacity={opacity}",
+                surface.width, surface.height, m.a, m.b, m.c, m.d, m.e, m.f
+            )
+        }
+        DisplayCommand::PushClip(r) => format!("push_clip {} {} {}x{}", r.x, r.y, r.w, r.h),
+        DisplayCommand::PopClip => "pop_clip".to_string(),
+        DisplayCommand::PushTransform(p) => format!("push_transform {} {}", p.x, p.y),
+        DisplayCommand::PopTransform => "pop_transform".to_string(),
+        DisplayCommand::PushOpacity(o) => format!("push_opacity {o}"),
+        DisplayCommand::PopOpacity => "pop_opacity".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::Gradient;
+
+    const RED: Color = Color::rgb(0xFF, 0, 0);
+    const TRANSLUCENT: Color = Color::argb(0x80, 0, 0x80, 0xFF);
+
+    // every kind of command, across the bands of a 200x150 buffer
+    fn scene() -> DisplayList {
+        let mut list = DisplayList::new();
+        list.draw_rectangle(0, 0, 200, 150, 0xFFEEEEEE, 0);
+        list.blend_rectangle(10, 20, 150, 50, TRANSLUCENT, 1);
+        let gradient = Gradient::linear(
+            Position { x: 0.0, y: 0.0 },
+            Position { x: 0.0, y: 100.0 },
+            vec![(0.0, RED), (1.0, Color::BLACK)],
+        );
+        list.draw_rounded_rect(30, 25, 90, 80, 12.0, Paint::from(gradient).dither(true), 2);
+        list.draw_text(5, 60, "tiles\nagain", 2, Color::BLACK, 3);
+        let image = Image::new(2, 2, vec![RED, Color::WHITE, Color::BLACK, TRANSLUCENT]);
+        list.draw_image(140, 90, 40, 40, Arc::new(image), FilterQuality::Bilinear, 2);
+        list.push_transform(Position { x: 7.0, y: 9.0 });
+        list.push_opacity(0.5);
+        list.fill_path(&Path::circle(60.0, 100.0, 35.0), FillRule::NonZero, RED, 4);
+        list.stroke_path(
+            &Path::new().move_to(0.0, 0.0).line_to(190.0, 140.0),
+            &Stroke::new(3.0),
+            Color::BLACK,
+            4,
+        );
+        list.pop_opacity();
+        list.push_clip(Rect::new(20.0, 30.0, 100.0, 70.0));
+        list.draw_shadow(
+            Rect::new(50.0, 50.0, 40.0, 30.0),
+            6.0,
+            &BoxShadow::new(Color::BLACK, Position { x: 2.0, y: 3.0 }, 5.0),
+            5,
+        );
+        list.pop_clip();
+        list.pop_transform();
+        let mut surface = AppBuffer::offscreen(100, 30, 50, 70);
+        let mut inside = DisplayList::new();
+        inside.blend_rectangle(100, 30, 50, 70, TRANSLUCENT, 6);
+        inside.draw_text(102, 40, "in", 1, Color::WHITE, 7);
+        inside.rasterize(&mut surface);
+        let surface = Arc::new(surface);
+        list.composite(surface.clone(), (-20, 40), 0.75);
+        list.composite_transformed(
+            surface,
+            Matrix::rotation(0.3).then(&Matrix::translation(20.0, -10.0)),
+            FilterQuality::Bilinear,
+            1.0,
+        );
+        list
+    }
+
+    fn pixels(buffer: &AppBuffer) -> (Vec<u32>, Vec<usize>) {
+        (buffer.color.clone(), buffer.depth.clone())
+    }
+
+    #[test]
+    fn parallel_rasterizing_gives_the_same_bytes() {
+        let list = scene();
+        let mut serial = AppBuffer::new(200, 150);
+        list.rasterize(&mut serial);
+        let mut parallel = AppBuffer::new(200, 150);
+        list.rasterize_parallel(&mut parallel);
+        assert!(pixels(&serial) == pixels(&parallel));
+        // whatever the cores of the machine running the test
+        let mut threaded = AppBuffer::new(200, 150);
+        let bounds = list.bounds();
+        rasterize_tiles(threaded.tiles(TILE_ROWS), 3, |tile| {
+            list.replay(tile, &bounds)
+        });
+        assert!(pixels(&serial) == pixels(&threaded));
+        // something was drawn in every band
+        assert!(serial
+            .color
+            .chunks(200 * TILE_ROWS)
+            .all(|band| band.iter().any(|&p| p != 0)));
+    }
+
+    #[test]
+    fn damage_is_drawn_as_if_one_rect_after_the_other() {
+        let list = scene();
+        let damage = [
+            Rect::new(0.0, 0.0, 60.0, 40.0),
+            Rect::new(50.0, 35.0, 100.0, 80.0),
+            Rect::new(150.5, 120.0, 30.0, 30.0),
+        ];
+        let mut expected = AppBuffer::new(200, 150);
+        expected.clear(0x12345678, 9);
+        let mut buffer = AppBuffer::new(200, 150);
+        buffer.clear(0x12345678, 9);
+
+        for rect in damage {
+            expected.clear_rect(rect, 0xFFFFFFFF, 0);
+            expected.set_clip(Some(rect));
+            list.rasterize(&mut expected);
+        }
+        expected.set_clip(None);
+        list.rasterize_damage(&mut buffer, &damage, 0xFFFFFFFF);
+        assert!(pixels(&expected) == pixels(&buffer));
+        // out of the damage
+        assert_eq!(buffer.color[199], 0x12345678);
+    }
+
+    #[test]
+    fn bounds_follow_the_transforms() {
+        let mut list = DisplayList::new();
+        list.push_transform(Position { x: 10.0, y: 5.0 });
+        list.draw_text(0, 0, "ab\nc", 2, Color::BLACK, 0);
+        list.pop_transform();
+        list.fill_path(&Path::new(), FillRule::NonZero, RED, 0);
+        list.draw_rectangle(1, 2, 3, 4, 0, 0);
+        let text = Rect::new(
+            10.0,
+            5.0,
+            (2 * font::ADVANCE * 2) as f32,
+            (2 * font::LINE_HEIGHT * 2) as f32,
+        );
+        assert_eq!(
+            list.bounds(),
+            vec![
+                None,
+                Some(text),
+                None,
+                None,
+                Some(Rect::new(1.0, 2.0, 3.0, 4.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn text_nests_commands_in_their_pushes() {
+        let mut list = DisplayList::new();
+        list.push_clip(Rect::new(0.0, 0.0, 50.0, 40.0));
+        list.blend_rectangle(1, 2, 3, 4, TRANSLUCENT, 1);
+        list.push_opacity(0.5);
+        list.draw_text(5, 6, "hi", 2, RED, 2);
+        list.pop_opacity();
+        list.pop_clip();
+        // too many pops don't go below the left edge
+        list.pop_transform();
+        let gradient = Gradient::linear(
+            Position::default(),
+            Position { x: 10.0, y: 0.0 },
+            vec![(0.0, RED), (1.0, Color::BLACK)],
+        );
+        list.draw_rounded_rect(0, 0, 10, 10, 2.0, gradient, 0);
+        assert_eq!(
+            list.to_text(),
+            "push_clip 0 0 50x40\n\
+             \x20 fill_rect 1 2 3x4 #0080ff80 z=1\n\
+             \x20 push_opacity 0.5\n\
+             \x20   text 5 6 scale=2 #ff0000ff z=2 \"hi\"\n\
+             \x20 pop_opacity\n\
+             pop_clip\n\
+             pop_transform\n\
+             rounded_rect 0 0 10x10 r=2 Linear { start: Position { x: 0.0, y: 0.0 }, end: \
+             Position { x: 10.0, y: 0.0 } } Clamp [0 #ff0000ff, 1 #000000ff] z=0\n"
+        );
+    }
+}
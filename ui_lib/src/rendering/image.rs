@@ -0,0 +1,137 @@
+use std::{fmt, path::Path};
+
+use crate::types::Color;
+
+use super::decode;
+
+// Decoded pixels, row after row from the top left one
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(pixels.len(), width * height, "wrong number of pixels");
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // PNG, BMP or PPM, told apart by their first bytes
+    pub fn decode(bytes: &[u8]) -> Result<Self, ImageError> {
+        decode::decode(bytes)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        let bytes = std::fs::read(path).map_err(|e| ImageError::Io(e.to_string()))?;
+        Self::decode(&bytes)
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    // The part of the image in the rectangle, cut to the image
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let (x, y) = (x.min(self.width), y.min(self.height));
+        let (width, height) = (width.min(self.width - x), height.min(self.height - y));
+        let pixels = (y..y + height)
+            .flat_map(|j| self.pixels[j * self.width + x..j * self.width + x + width].iter())
+            .copied()
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    // The color at a position in pixels, (0, 0) being the top left corner of the image and
+    // pixel centers being at halves
+    pub fn sample(&self, x: f32, y: f32, filter: FilterQuality) -> Color {
+        let clamp = |v: f32, size: usize| (v.max(0.0) as usize).min(size - 1);
+        match filter {
+            FilterQuality::Nearest => self.pixel(clamp(x, self.width), clamp(y, self.height)),
+            FilterQuality::Bilinear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (clamp(x, self.width), clamp(y, self.height));
+                let (x1, y1) = (clamp(x + 1.0, self.width), clamp(y + 1.0, self.height));
+                let (tx, ty) = ((x - x.floor()).max(0.0), (y - y.floor()).max(0.0));
+                // weighted by alpha, so transparent pixels don't darken their neighbors
+                let mut sum = [0.0f32; 4];
+                for (px, py, w) in [
+                    (x0, y0, (1.0 - tx) * (1.0 - ty)),
+                    (x1, y0, tx * (1.0 - ty)),
+                    (x0, y1, (1.0 - tx) * ty),
+                    (x1, y1, tx * ty),
+                ] {
+                    let c = self.pixel(px, py);
+                    let a = c.a as f32 * w;
+                    sum[0] += a;
+                    sum[1] += c.r as f32 * a;
+                    sum[2] += c.g as f32 * a;
+                    sum[3] += c.b as f32 * a;
+                }
+                if sum[0] <= 0.0 {
+                    return Color::TRANSPARENT;
+                }
+                let channel = |v: f32| (v / sum[0]).round().clamp(0.0, 255.0) as u8;
+                Color::argb(
+                    sum[0].round().clamp(0.0, 255.0) as u8,
+                    channel(sum[1]),
+                    channel(sum[2]),
+                    channel(sum[3]),
+                )
+            }
+        }
+    }
+}
+
+// How an image drawn at another size picks its colors
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FilterQuality {
+    #[default]
+    Nearest,
+    // blends the four closest pixels, smoother when scaling up
+    Bilinear,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ImageError {
+    Io(String),
+    UnknownFormat,
+    Invalid(String),
+    Unsupported(String),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot read the image: {e}"),
+            Self::UnknownFormat => write!(f, "not a PNG, BMP or PPM image"),
+            Self::Invalid(what) => write!(f, "invalid image: {what}"),
+            Self::Unsupported(what) => write!(f, "unsupported image: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crops_are_cut_to_the_image() {
+        let pixels = (0..12).map(|i| Color::rgb(i, 0, 0)).collect();
+        let image = Image::new(4, 3, pixels);
+        let crop = image.crop(1, 1, 2, 5);
+        assert_eq!((crop.width, crop.height), (2, 2));
+        let reds: Vec<u8> = crop.pixels.iter().map(|c| c.r).collect();
+        assert_eq!(reds, vec![5, 6, 9, 10]);
+        let outside = image.crop(10, 1, 2, 2);
+        assert_eq!((outside.width, outside.height), (0, 2));
+        assert!(outside.pixels.is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+use super::{Image, ImageError};
+
+// Decoded images by the path they were read from, so that an image used in several places
+// or rebuilt every frame is only decoded once. Failures aren't remembered.
+#[derive(Default)]
+pub struct ImageCache {
+    images: HashMap<PathBuf, Arc<Image>>,
+}
+
+impl ImageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<Arc<Image>, ImageError> {
+        let path = path.as_ref();
+        if let Some(image) = self.images.get(path) {
+            return Ok(image.clone());
+        }
+        let image = Arc::new(Image::open(path)?);
+        self.images.insert(path.to_path_buf(), image.clone());
+        Ok(image)
+    }
+
+    // For a file that changed on disk
+    pub fn evict(&mut self, path: impl AsRef<Path>) -> Option<Arc<Image>> {
+        self.images.remove(path.as_ref())
+    }
+
+    pub fn clear(&mut self) {
+        self.images.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+}
+
+thread_local! {
+    static IMAGE_CACHE: Rc<RefCell<ImageCache>> = Rc::new(RefCell::new(ImageCache::new()));
+}
+
+// The cache used by image widgets loading from a path
+pub fn image_cache() -> Rc<RefCell<ImageCache>> {
+    IMAGE_CACHE.with(|c| c.clone())
+}
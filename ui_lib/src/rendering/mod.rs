@@ -1,7 +1,28 @@
 mod appbuffer;
+mod canvas;
+mod damage;
+mod decode;
+mod display_list;
+pub mod font;
+mod image;
+mod image_cache;
+mod paint;
+mod path;
 mod render_context;
 pub mod render_object;
+mod shadow;
+mod stroke;
+mod svg;
 
-pub use appbuffer::AppBuffer;
+pub use appbuffer::{AppBuffer, Tile};
+pub use canvas::Canvas;
+pub use damage::DamageRegion;
+pub use display_list::{DisplayCommand, DisplayList};
+pub use image::{FilterQuality, Image, ImageError};
+pub use image_cache::{image_cache, ImageCache};
+pub use paint::{Gradient, GradientShape, Paint, Shader, TileMode};
+pub use path::{Contour, FillRule, Path, PathSegment};
 pub use render_context::RenderContext;
 pub use render_context::RenderContextMut;
+pub use shadow::{BoxShadow, ShadowMask};
+pub use stroke::{LineCap, LineJoin, Stroke};
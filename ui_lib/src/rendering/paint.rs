@@ -0,0 +1,380 @@
+use std::f32::consts::PI;
+
+use crate::types::{Color, Position, Size};
+
+// Ordered dithering thresholds, in sixteenths
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+// What a gradient does past its first and last stops
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TileMode {
+    // the end colors go on
+    #[default]
+    Clamp,
+    Repeat,
+    // repeated, every other time backwards
+    Mirror,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum GradientShape {
+    // along the line, the stops at 0 and 1 being at `start` and `end`
+    Linear {
+        start: Position,
+        end: Position,
+    },
+    // going out from the center, 1 being at `radius`
+    Radial {
+        center: Position,
+        radius: f32,
+    },
+    // turning around the center from the start to the end angle, in radians: clockwise
+    // when the end angle is the larger, counterclockwise otherwise
+    Sweep {
+        center: Position,
+        start_angle: f32,
+        end_angle: f32,
+    },
+}
+
+// Positions are in the coordinates of what is drawn with it
+#[derive(Clone, PartialEq, Debug)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    // (offset between 0 and 1, color), in order
+    pub stops: Vec<(f32, Color)>,
+    pub tile_mode: TileMode,
+}
+
+impl Gradient {
+    pub fn linear(start: Position, end: Position, stops: Vec<(f32, Color)>) -> Self {
+        Self::new(GradientShape::Linear { start, end }, stops)
+    }
+
+    pub fn radial(center: Position, radius: f32, stops: Vec<(f32, Color)>) -> Self {
+        Self::new(GradientShape::Radial { center, radius }, stops)
+    }
+
+    pub fn sweep(
+        center: Position,
+        start_angle: f32,
+        end_angle: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Self {
+        let shape = GradientShape::Sweep {
+            center,
+            start_angle,
+            end_angle,
+        };
+        Self::new(shape, stops)
+    }
+
+    // Offsets are kept between 0 and 1, and the stops without one are dropped
+    fn new(shape: GradientShape, mut stops: Vec<(f32, Color)>) -> Self {
+        stops.retain(|s| !s.0.is_nan());
+        for stop in &mut stops {
+            stop.0 = stop.0.clamp(0.0, 1.0);
+        }
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            shape,
+            stops,
+            tile_mode: TileMode::default(),
+        }
+    }
+
+    pub fn tile_mode(mut self, tile_mode: TileMode) -> Self {
+        self.tile_mode = tile_mode;
+        self
+    }
+
+    pub fn translate(&self, offset: Position) -> Self {
+        let shape = match self.shape {
+            GradientShape::Linear { start, end } => GradientShape::Linear {
+                start: start + offset,
+                end: end + offset,
+            },
+            GradientShape::Radial { center, radius } => GradientShape::Radial {
+                center: center + offset,
+                radius,
+            },
+            GradientShape::Sweep {
+                center,
+                start_angle,
+                end_angle,
+            } => GradientShape::Sweep {
+                center: center + offset,
+                start_angle,
+                end_angle,
+            },
+        };
+        Self {
+            shape,
+            ..self.clone()
+        }
+    }
+
+    // For positions given as fractions of a box, the radius of the shorter side
+    pub fn scale(&self, size: Size) -> Self {
+        let scale = |p: Position| Position {
+            x: p.x * size.w,
+            y: p.y * size.h,
+        };
+        let shape = match self.shape {
+            GradientShape::Linear { start, end } => GradientShape::Linear {
+                start: scale(start),
+                end: scale(end),
+            },
+            GradientShape::Radial { center, radius } => GradientShape::Radial {
+                center: scale(center),
+                radius: radius * size.w.min(size.h),
+            },
+            GradientShape::Sweep {
+                center,
+                start_angle,
+                end_angle,
+            } => GradientShape::Sweep {
+                center: scale(center),
+                start_angle,
+                end_angle,
+            },
+        };
+        Self {
+            shape,
+            ..self.clone()
+        }
+    }
+
+    // Where the point falls between the first and last stops, before tiling
+    fn offset_at(&self, p: Position) -> f32 {
+        match self.shape {
+            GradientShape::Linear { start, end } => {
+                let (d, v) = (end - start, p - start);
+                let length = d.x * d.x + d.y * d.y;
+                if length <= 0.0 {
+                    return 0.0;
+                }
+                (v.x * d.x + v.y * d.y) / length
+            }
+            GradientShape::Radial { center, radius } => {
+                if radius <= 0.0 {
+                    return 1.0;
+                }
+                (p - center).length() / radius
+            }
+            GradientShape::Sweep {
+                center,
+                start_angle,
+                end_angle,
+            } => {
+                let sweep = end_angle - start_angle;
+                if sweep == 0.0 {
+                    return 0.0;
+                }
+                let v = p - center;
+                let turned = v.y.atan2(v.x) - start_angle;
+                let angle = if sweep > 0.0 { turned } else { -turned };
+                angle.rem_euclid(2.0 * PI) / sweep.abs()
+            }
+        }
+    }
+
+    // Channels as floats, for dithering. Points without an offset get the first color.
+    fn sample(&self, p: Position) -> [f32; 4] {
+        let channels = |c: &Color| [c.r as f32, c.g as f32, c.b as f32, c.a as f32];
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return [0.0; 4];
+        };
+        let t = self.offset_at(p);
+        if !t.is_finite() {
+            return channels(&first.1);
+        }
+        let t = match self.tile_mode {
+            TileMode::Clamp => t.clamp(0.0, 1.0),
+            TileMode::Repeat => t.rem_euclid(1.0),
+            TileMode::Mirror => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 {
+                    2.0 - t
+                } else {
+                    t
+                }
+            }
+        };
+        if t <= first.0 {
+            return channels(&first.1);
+        }
+        if t >= last.0 {
+            return channels(&last.1);
+        }
+        // stops built by hand may not be in order
+        let next = match self.stops.iter().position(|s| s.0 > t) {
+            Some(0) => return channels(&first.1),
+            Some(next) => next,
+            None => return channels(&last.1),
+        };
+        let (a, b) = (self.stops[next - 1], self.stops[next]);
+        let f = (t - a.0) / (b.0 - a.0);
+        let (a, b) = (channels(&a.1), channels(&b.1));
+        std::array::from_fn(|i| a[i] + (b[i] - a[i]) * f)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Shader {
+    Solid(Color),
+    Gradient(Gradient),
+}
+
+// How something is filled. Dithering spreads the rounding of gradients to 8 bits per
+// channel over neighbouring pixels, so that they show no bands.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Paint {
+    pub shader: Shader,
+    pub dither: bool,
+}
+
+impl Paint {
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            dither: false,
+        }
+    }
+
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    pub fn solid_color(&self) -> Option<Color> {
+        match self.shader {
+            Shader::Solid(c) => Some(c),
+            Shader::Gradient(_) => None,
+        }
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        match &self.shader {
+            Shader::Solid(c) => c.a == 0,
+            Shader::Gradient(g) => g.stops.iter().all(|s| s.1.a == 0),
+        }
+    }
+
+    pub fn translate(&self, offset: Position) -> Self {
+        match &self.shader {
+            Shader::Solid(_) => self.clone(),
+            Shader::Gradient(g) => Self {
+                shader: Shader::Gradient(g.translate(offset)),
+                dither: self.dither,
+            },
+        }
+    }
+
+    // The color of the pixel at (x, y)
+    pub fn color_at(&self, x: isize, y: isize) -> Color {
+        let gradient = match &self.shader {
+            Shader::Solid(c) => return *c,
+            Shader::Gradient(g) => g,
+        };
+        let [r, g, b, a] = gradient.sample(Position {
+            x: x as f32 + 0.5,
+            y: y as f32 + 0.5,
+        });
+        let threshold = if self.dither {
+            BAYER[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize] as f32 / 16.0 + 1.0 / 32.0
+        } else {
+            0.5
+        };
+        let channel = |v: f32| (v + threshold).floor().clamp(0.0, 255.0) as u8;
+        Color::argb(channel(a), channel(r), channel(g), channel(b))
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(value: Color) -> Self {
+        Self::new(Shader::Solid(value))
+    }
+}
+
+impl From<Gradient> for Paint {
+    fn from(value: Gradient) -> Self {
+        Self::new(Shader::Gradient(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::rgb(0xFF, 0, 0);
+    const BLUE: Color = Color::rgb(0, 0, 0xFF);
+
+    fn at(x: f32, y: f32) -> Position {
+        Position { x, y }
+    }
+
+    fn horizontal(stops: Vec<(f32, Color)>) -> Gradient {
+        Gradient::linear(at(0.0, 0.0), at(10.0, 0.0), stops)
+    }
+
+    fn red(gradient: &Gradient, p: Position) -> f32 {
+        gradient.sample(p)[0]
+    }
+
+    #[test]
+    fn stops_are_sorted_and_kept_between_0_and_1() {
+        let gradient = horizontal(vec![
+            (f32::INFINITY, BLUE),
+            (f32::NAN, Color::WHITE),
+            (-2.0, RED),
+            (0.5, Color::BLACK),
+        ]);
+        assert_eq!(
+            gradient.stops,
+            vec![(0.0, RED), (0.5, Color::BLACK), (1.0, BLUE)]
+        );
+    }
+
+    #[test]
+    fn colors_are_mixed_between_stops_and_tiled_past_them() {
+        let clamp = horizontal(vec![(0.0, RED), (1.0, BLUE)]);
+        assert_eq!(red(&clamp, at(5.0, 3.0)), 127.5);
+        assert_eq!(red(&clamp, at(-5.0, 0.0)), 255.0);
+        assert_eq!(red(&clamp, at(15.0, 0.0)), 0.0);
+        let repeat = clamp.clone().tile_mode(TileMode::Repeat);
+        assert_eq!(red(&repeat, at(12.5, 0.0)), 191.25);
+        let mirror = clamp.tile_mode(TileMode::Mirror);
+        assert_eq!(red(&mirror, at(12.5, 0.0)), 63.75);
+    }
+
+    #[test]
+    fn points_without_an_offset_get_the_first_color() {
+        let gradient = horizontal(vec![(0.2, RED), (0.8, BLUE)]);
+        assert_eq!(gradient.sample(at(f32::NAN, 0.0)), [255.0, 0.0, 0.0, 255.0]);
+        let radial = Gradient::radial(at(0.0, 0.0), 1.0, vec![(0.2, RED), (0.8, BLUE)]);
+        assert_eq!(
+            radial.sample(at(f32::INFINITY, 0.0)),
+            [255.0, 0.0, 0.0, 255.0]
+        );
+        // stops set by hand, whatever they are
+        let by_hand = Gradient {
+            stops: vec![(0.5, RED), (f32::NAN, BLUE), (0.1, Color::BLACK)],
+            ..gradient
+        };
+        for x in [0.0, 3.0, 6.0, 10.0, f32::NAN] {
+            by_hand.sample(at(x, 0.0));
+        }
+    }
+
+    #[test]
+    fn sweeps_turn_from_the_start_to_the_end_angle() {
+        let stops = vec![(0.0, RED), (1.0, BLUE)];
+        // y goes down, so clockwise on screen
+        let clockwise = Gradient::sweep(at(0.0, 0.0), 0.0, PI / 2.0, stops.clone());
+        assert!((red(&clockwise, at(1.0, 1.0)) - 127.5).abs() < 0.01);
+        let counterclockwise = Gradient::sweep(at(0.0, 0.0), 0.0, -PI / 2.0, stops);
+        assert!((red(&counterclockwise, at(1.0, -1.0)) - 127.5).abs() < 0.01);
+        assert_eq!(red(&counterclockwise, at(0.0, -1.0)), 0.0);
+    }
+}
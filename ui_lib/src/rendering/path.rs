@@ -0,0 +1,384 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::types::{Position, Rect};
+
+// Largest distance, in pixels, between a curve and the lines drawn in its place
+pub(crate) const TOLERANCE: f32 = 0.2;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PathSegment {
+    MoveTo(Position),
+    LineTo(Position),
+    QuadTo(Position, Position),
+    CubicTo(Position, Position, Position),
+    Close,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FillRule {
+    // inside where the contours around the point don't cancel each other
+    #[default]
+    NonZero,
+    // inside where an odd number of contours is around the point
+    EvenOdd,
+}
+
+// A polyline of a flattened path, closed when it loops back to its first point
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Contour {
+    pub points: Vec<Position>,
+    pub closed: bool,
+}
+
+// Outlines made of lines and curves, in pixels. Filling closes the open subpaths.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rect(rect: Rect) -> Self {
+        Self::new()
+            .move_to(rect.x, rect.y)
+            .line_to(rect.right(), rect.y)
+            .line_to(rect.right(), rect.bottom())
+            .line_to(rect.x, rect.bottom())
+            .close()
+    }
+
+    pub fn circle(cx: f32, cy: f32, radius: f32) -> Self {
+        Self::new().arc(cx, cy, radius, 0.0, 2.0 * PI).close()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    fn current(&self) -> Option<Position> {
+        let mut start = None;
+        let mut current = None;
+        for segment in &self.segments {
+            match segment {
+                PathSegment::MoveTo(p) => {
+                    start = Some(*p);
+                    current = Some(*p);
+                }
+                PathSegment::LineTo(p)
+                | PathSegment::QuadTo(_, p)
+                | PathSegment::CubicTo(_, _, p) => current = Some(*p),
+                PathSegment::Close => current = start,
+            }
+        }
+        current
+    }
+
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.segments.push(PathSegment::MoveTo(Position { x, y }));
+        self
+    }
+
+    // Starts at the given point when nothing was drawn yet
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.segments
+            .push(self.segment_to(PathSegment::LineTo(Position { x, y })));
+        self
+    }
+
+    pub fn quad_to(mut self, cx: f32, cy: f32, x: f32, y: f32) -> Self {
+        let segment = PathSegment::QuadTo(Position { x: cx, y: cy }, Position { x, y });
+        self.segments.push(self.segment_to(segment));
+        self
+    }
+
+    pub fn cubic_to(mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Self {
+        let segment = PathSegment::CubicTo(
+            Position { x: c1x, y: c1y },
+            Position { x: c2x, y: c2y },
+            Position { x, y },
+        );
+        self.segments.push(self.segment_to(segment));
+        self
+    }
+
+    fn segment_to(&self, segment: PathSegment) -> PathSegment {
+        match (self.current(), segment) {
+            (None, PathSegment::LineTo(p)) => PathSegment::MoveTo(p),
+            _ => segment,
+        }
+    }
+
+    // Part of the circle around (cx, cy), from `start` and turning by `sweep`, both in
+    // radians and clockwise on screen. It is joined by a line to what was drawn before.
+    pub fn arc(mut self, cx: f32, cy: f32, radius: f32, start: f32, sweep: f32) -> Self {
+        if !sweep.is_finite() {
+            return self;
+        }
+        // anything past a full turn draws the same circle again
+        let sweep = sweep.clamp(-TAU, TAU);
+        let center = Position { x: cx, y: cy };
+        let point = |angle: f32| center + Position::from_angle(angle) * radius;
+        let first = point(start);
+        self = match self.current() {
+            Some(_) => self.line_to(first.x, first.y),
+            None => self.move_to(first.x, first.y),
+        };
+        // cubics are close enough to a circle up to a quarter of it
+        let pieces = (sweep.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+        let step = sweep / pieces as f32;
+        let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+        for i in 0..pieces {
+            let (a, b) = (start + step * i as f32, start + step * (i + 1) as f32);
+            let (p0, p3) = (point(a), point(b));
+            let c1 = p0 + Position::from_angle(a + PI / 2.0) * k;
+            let c2 = p3 - Position::from_angle(b + PI / 2.0) * k;
+            self.segments.push(PathSegment::CubicTo(c1, c2, p3));
+        }
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        if self.current().is_some() {
+            self.segments.push(PathSegment::Close);
+        }
+        self
+    }
+
+    pub fn translate(&self, offset: Position) -> Self {
+        let segments = self
+            .segments
+            .iter()
+            .map(|s| match *s {
+                PathSegment::MoveTo(p) => PathSegment::MoveTo(p + offset),
+                PathSegment::LineTo(p) => PathSegment::LineTo(p + offset),
+                PathSegment::QuadTo(c, p) => PathSegment::QuadTo(c + offset, p + offset),
+                PathSegment::CubicTo(c1, c2, p) => {
+                    PathSegment::CubicTo(c1 + offset, c2 + offset, p + offset)
+                }
+                PathSegment::Close => PathSegment::Close,
+            })
+            .collect();
+        Self { segments }
+    }
+
+    // Contains the whole path, control points included
+    pub fn bounds(&self) -> Rect {
+        let mut points = self.segments.iter().flat_map(|s| match *s {
+            PathSegment::MoveTo(p) | PathSegment::LineTo(p) => vec![p],
+            PathSegment::QuadTo(c, p) => vec![c, p],
+            PathSegment::CubicTo(c1, c2, p) => vec![c1, c2, p],
+            PathSegment::Close => vec![],
+        });
+        let Some(first) = points.next() else {
+            return Rect::default();
+        };
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (
+                Position {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Position {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        });
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    // The curves replaced by lines, each subpath in its own contour
+    pub fn flatten(&self) -> Vec<Contour> {
+        let mut contours = Vec::new();
+        let mut contour = Contour::default();
+        for segment in &self.segments {
+            let last = contour.points.last().copied();
+            match *segment {
+                PathSegment::MoveTo(p) => {
+                    if contour.points.len() > 1 {
+                        contours.push(contour);
+                    }
+                    contour = Contour {
+                        points: vec![p],
+                        closed: false,
+                    };
+                }
+                PathSegment::LineTo(p) => contour.points.push(p),
+                PathSegment::QuadTo(c, p) => {
+                    let p0 = last.unwrap_or(c);
+                    let n = subdivisions(((p0 - c * 2.0) + p).length() / 4.0);
+                    contour.points.extend((1..=n).map(|i| {
+                        let t = i as f32 / n as f32;
+                        let a = Position::lerp(&p0, &c, t);
+                        let b = Position::lerp(&c, &p, t);
+                        Position::lerp(&a, &b, t)
+                    }));
+                }
+                PathSegment::CubicTo(c1, c2, p) => {
+                    let p0 = last.unwrap_or(c1);
+                    let d = ((p0 - c1 * 2.0) + c2)
+                        .length()
+                        .max(((c1 - c2 * 2.0) + p).length());
+                    let n = subdivisions(d * 3.0 / 4.0);
+                    contour.points.extend((1..=n).map(|i| {
+                        let t = i as f32 / n as f32;
+                        let a = Position::lerp(&p0, &c1, t);
+                        let b = Position::lerp(&c1, &c2, t);
+                        let c = Position::lerp(&c2, &p, t);
+                        let ab = Position::lerp(&a, &b, t);
+                        let bc = Position::lerp(&b, &c, t);
+                        Position::lerp(&ab, &bc, t)
+                    }));
+                }
+                PathSegment::Close => {
+                    if let Some(start) = contour.points.first().copied() {
+                        if contour.points.len() > 1 && last == Some(start) {
+                            contour.points.pop();
+                        }
+                        contours.push(Contour {
+                            points: std::mem::take(&mut contour.points),
+                            closed: true,
+                        });
+                        // what follows starts again from the start of the closed subpath
+                        contour.points.push(start);
+                    }
+                }
+            }
+        }
+        if contour.points.len() > 1 {
+            contours.push(contour);
+        }
+        contours
+    }
+}
+
+// Lines needed for a curve whose second derivative is `curvature` at most, so that it stays
+// within the tolerance
+fn subdivisions(curvature: f32) -> usize {
+    ((curvature / TOLERANCE).sqrt().ceil() as usize).clamp(1, 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rendering::{AppBuffer, DisplayList},
+        types::Color,
+    };
+
+    const RED: u32 = 0xFF0000;
+
+    fn fill(path: &Path, rule: FillRule) -> AppBuffer {
+        let mut list = DisplayList::new();
+        list.fill_path(path, rule, Color::from(RED), 0);
+        let mut buffer = AppBuffer::new(10, 10);
+        list.rasterize(&mut buffer);
+        buffer
+    }
+
+    fn pos(x: f32, y: f32) -> Position {
+        Position { x, y }
+    }
+
+    #[test]
+    fn arcs_ignore_non_finite_sweeps_and_stop_at_a_full_turn() {
+        let path = Path::new().move_to(1.0, 1.0);
+        for sweep in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let arc = path.clone().arc(5.0, 5.0, 2.0, 0.0, sweep);
+            assert_eq!(arc.segments(), path.segments());
+        }
+        // a move to the start and four quarter circles
+        let arc = Path::new().arc(5.0, 5.0, 2.0, 0.0, 1e30);
+        assert_eq!(arc.segments().len(), 5);
+        assert_eq!(
+            Path::new().arc(5.0, 5.0, 2.0, 0.0, -1e30).segments().len(),
+            5
+        );
+    }
+
+    #[test]
+    fn subpaths_flatten_to_contours() {
+        let rect = Path::rect(Rect::new(1.0, 2.0, 3.0, 4.0)).flatten();
+        assert_eq!(
+            rect,
+            vec![Contour {
+                points: vec![pos(1.0, 2.0), pos(4.0, 2.0), pos(4.0, 6.0), pos(1.0, 6.0)],
+                closed: true,
+            }]
+        );
+        // a line without a start starts the path, a lone move draws nothing, and lines
+        // after a close start from where the closed subpath did
+        let path = Path::new()
+            .line_to(1.0, 1.0)
+            .line_to(2.0, 1.0)
+            .close()
+            .line_to(1.0, 5.0)
+            .move_to(9.0, 9.0);
+        assert_eq!(
+            path.flatten(),
+            vec![
+                Contour {
+                    points: vec![pos(1.0, 1.0), pos(2.0, 1.0)],
+                    closed: true,
+                },
+                Contour {
+                    points: vec![pos(1.0, 1.0), pos(1.0, 5.0)],
+                    closed: false,
+                },
+            ]
+        );
+        assert_eq!(path.bounds(), Rect::new(1.0, 1.0, 8.0, 8.0));
+    }
+
+    #[test]
+    fn curves_stay_within_the_tolerance() {
+        let center = pos(50.0, 50.0);
+        let contours = Path::circle(50.0, 50.0, 40.0).flatten();
+        assert_eq!(contours.len(), 1);
+        let points = &contours[0].points;
+        assert!(points.len() > 8);
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            assert!(((*a - center).length() - 40.0).abs() <= TOLERANCE);
+            // the middle of each line is where it strays most
+            let middle = Position::lerp(a, &b, 0.5);
+            assert!(40.0 - (middle - center).length() <= TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn filling_covers_the_inside_and_blends_the_edges() {
+        let buffer = fill(
+            &Path::rect(Rect::new(2.5, 2.0, 3.5, 4.0)),
+            FillRule::NonZero,
+        );
+        let row = |y: usize| &buffer.color[y * 10..y * 10 + 10];
+        assert_eq!(row(1), [0; 10]);
+        assert_eq!(row(3)[3..6], [RED; 3]);
+        assert_eq!(row(3)[6], 0);
+        // half of the pixel is covered
+        let red = row(3)[2] >> 16;
+        assert!((0x70..0x90).contains(&red), "{red:x}");
+        assert_eq!(row(6), [0; 10]);
+    }
+
+    #[test]
+    fn fill_rules_decide_about_inner_contours() {
+        let ring = Path::rect(Rect::new(1.0, 1.0, 8.0, 8.0))
+            .move_to(3.0, 3.0)
+            .line_to(7.0, 3.0)
+            .line_to(7.0, 7.0)
+            .line_to(3.0, 7.0)
+            .close();
+        assert_eq!(fill(&ring, FillRule::NonZero).color[5 * 10 + 5], RED);
+        assert_eq!(fill(&ring, FillRule::EvenOdd).color[5 * 10 + 5], 0);
+        assert_eq!(fill(&ring, FillRule::EvenOdd).color[2 * 10 + 2], RED);
+    }
+}
@@ -1,15 +1,47 @@
-use crate::types::Position;
+use crate::{
+    inputs::{focus::ROOT_SCOPE, FocusEntry},
+    types::{Matrix, Position},
+};
 
-use super::{render_object::RenderObject, AppBuffer};
+use super::{render_object::RenderObject, DisplayList};
 
 pub struct RenderContext<'a> {
-    pub appbuffer: &'a mut AppBuffer,
+    // what is drawn gets recorded here, to be rasterized once the whole tree is painted
+    pub display_list: &'a mut DisplayList,
+    // filled while rendering, used for focus traversal
+    pub focusables: Vec<FocusEntry>,
+}
+
+impl<'a> RenderContext<'a> {
+    pub fn new(display_list: &'a mut DisplayList) -> Self {
+        Self {
+            display_list,
+            focusables: Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct RenderContextMut {
     pub position: Position,
+    // from where things are drawn to where they show on the window, through the transforms
+    // of the `Transform` objects above
+    pub transform: Matrix,
     pub elevation: usize,
+    pub opacity: f32,
+    pub focus_scope: usize,
+}
+
+impl Default for RenderContextMut {
+    fn default() -> Self {
+        Self {
+            position: Position::default(),
+            transform: Matrix::IDENTITY,
+            elevation: 0,
+            opacity: 1.0,
+            focus_scope: ROOT_SCOPE,
+        }
+    }
 }
 
 impl RenderContextMut {
@@ -19,6 +51,7 @@ impl RenderContextMut {
         RenderContextMut {
             position,
             elevation,
+            ..self.clone()
         }
     }
 }
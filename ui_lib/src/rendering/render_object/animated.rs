@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use crate::{
+    animation::{ImplicitAnimation, Lerp},
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+// Wraps a render object and, before each layout, feeds it the current value of an
+// implicit animation through `apply`.
+pub struct AnimatedRenderObject<R: RenderObject, T: Lerp + PartialEq> {
+    inner: R,
+    animation: ImplicitAnimation<T>,
+    apply: fn(&mut R, T),
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl<R: RenderObject, T: Lerp + PartialEq> AnimatedRenderObject<R, T> {
+    pub fn new(inner: R, animation: ImplicitAnimation<T>, apply: fn(&mut R, T)) -> Self {
+        Self {
+            inner,
+            animation,
+            apply,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl<R: RenderObject, T: Lerp + PartialEq> RenderObject for AnimatedRenderObject<R, T> {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(&self.inner);
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(&mut self.inner);
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.inner.paint(context, context_mut.update(self));
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn layout_changed(&self) -> bool {
+        self.animation.has_changed()
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        (self.apply)(&mut self.inner, self.animation.value());
+        self.inner.mark_needs_layout();
+        self.inner.layout(constraints)
+    }
+}
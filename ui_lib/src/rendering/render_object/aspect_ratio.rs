@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct AspectRatioRenderObject {
+    child: Option<Box<dyn RenderObject>>,
+    // width / height
+    aspect_ratio: f32,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl AspectRatioRenderObject {
+    pub fn new(child: Option<Box<dyn RenderObject>>, aspect_ratio: f32) -> Self {
+        assert!(aspect_ratio > 0.0 && aspect_ratio.is_finite());
+        Self {
+            child,
+            aspect_ratio,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    // Largest size with the ratio in the constraints, as wide as possible when the width is
+    // bounded, as tall as possible otherwise
+    fn apply_aspect_ratio(&self, constraints: &BoxConstraints) -> Size {
+        let ratio = self.aspect_ratio;
+        let ideal = if constraints.has_bounded_width() {
+            Size {
+                w: constraints.max_width,
+                h: constraints.max_width / ratio,
+            }
+        } else if constraints.has_bounded_height() {
+            Size {
+                w: constraints.max_height * ratio,
+                h: constraints.max_height,
+            }
+        } else {
+            // nothing to grow into, use the size the child would like
+            let w = self.max_intrinsic_width(f32::INFINITY);
+            Size { w, h: w / ratio }
+        };
+        if ideal.w <= 0.0 || ideal.h <= 0.0 {
+            return constraints.smallest();
+        }
+        constraints.constrain_size_and_attempt_to_preserve_aspect_ratio(ideal)
+    }
+}
+
+impl RenderObject for AspectRatioRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(ref c) = self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(ref mut c) = self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.max_intrinsic_width(height)
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        if height.is_finite() {
+            return height * self.aspect_ratio;
+        }
+        self.child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_width(height))
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.max_intrinsic_height(width)
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        if width.is_finite() {
+            return width / self.aspect_ratio;
+        }
+        self.child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_height(width))
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        if let Some(ref c) = self.child {
+            c.paint(context, context_mut.update(self));
+        }
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.apply_aspect_ratio(&constraints);
+        // without bounds, the size comes from the child
+        let uses_intrinsics = !constraints.has_bounded_width() && !constraints.has_bounded_height();
+        if let Some(ref mut c) = self.child {
+            c.layout_with(BoxConstraints::tight(size), uses_intrinsics);
+        }
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_object::ConstrainedRenderObject;
+
+    #[test]
+    fn fills_the_width_when_it_is_bounded() {
+        let mut ro = AspectRatioRenderObject::new(None, 2.0);
+        let size = ro.layout(BoxConstraints::loose(Size { w: 100.0, h: 100.0 }));
+        assert_eq!(size, Size { w: 100.0, h: 50.0 });
+    }
+
+    #[test]
+    fn shrinks_to_fit_the_height() {
+        let mut ro = AspectRatioRenderObject::new(None, 0.5);
+        let size = ro.layout(BoxConstraints::loose(Size { w: 100.0, h: 100.0 }));
+        assert_eq!(size, Size { w: 50.0, h: 100.0 });
+        let size = ro.layout(BoxConstraints::tight_for_height(40.0));
+        assert_eq!(size, Size { w: 20.0, h: 40.0 });
+    }
+
+    #[test]
+    fn unbounded_constraints_use_the_child_width() {
+        let child = ConstrainedRenderObject::new(None, BoxConstraints::tight_for(60.0, 10.0));
+        let mut ro = AspectRatioRenderObject::new(Some(Box::new(child)), 3.0);
+        assert_eq!(ro.max_intrinsic_height(90.0), 30.0);
+        let size = ro.layout(BoxConstraints::default());
+        assert_eq!(size, Size { w: 60.0, h: 20.0 });
+    }
+}
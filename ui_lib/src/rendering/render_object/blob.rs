@@ -2,10 +2,10 @@ use std::collections::HashMap;
 
 use crate::{
     rendering::{RenderContext, RenderContextMut},
-    types::{BoxConstraints, Position, Size},
+    types::{BoxConstraints, Color, Position, Size},
 };
 
-use super::{RenderObject, RenderObjectProp};
+use super::{LayoutCache, RenderObject, RenderObjectProp};
 
 pub struct BlobRenderObject {
     color: u32,
@@ -13,6 +13,7 @@ pub struct BlobRenderObject {
 
     props: HashMap<String, RenderObjectProp>,
     render_pos: Position,
+    layout_cache: LayoutCache,
     render_size: Size,
 }
 
@@ -24,6 +25,7 @@ impl BlobRenderObject {
 
             props: HashMap::new(),
             render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
             render_size: Size::default(),
         }
     }
@@ -35,6 +37,22 @@ impl RenderObject for BlobRenderObject {
         self.render_size
     }
 
+    fn min_intrinsic_width(&self, _height: f32) -> f32 {
+        self.size.w
+    }
+
+    fn max_intrinsic_width(&self, _height: f32) -> f32 {
+        self.size.w
+    }
+
+    fn min_intrinsic_height(&self, _width: f32) -> f32 {
+        self.size.h
+    }
+
+    fn max_intrinsic_height(&self, _width: f32) -> f32 {
+        self.size.h
+    }
+
     fn set_render_pos(&mut self, render_pos: Position) {
         self.render_pos = render_pos;
     }
@@ -43,14 +61,22 @@ impl RenderObject for BlobRenderObject {
         self.render_pos
     }
 
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
         let context_mut = context_mut.update(self);
-        context.appbuffer.draw_rectangle(
+        context.display_list.blend_rectangle(
             context_mut.position.x as isize,
             context_mut.position.y as isize,
             self.render_size.w as usize,
             self.render_size.h as usize,
-            self.color,
+            Color::from(self.color).with_opacity(context_mut.opacity),
             context_mut.elevation,
         );
     }
@@ -5,7 +5,7 @@ use crate::{
     types::{BoxConstraints, Position, Size},
 };
 
-use super::{RenderObject, RenderObjectProp};
+use super::{LayoutCache, RenderObject, RenderObjectProp};
 
 pub struct ConstrainedRenderObject {
     child: Option<Box<dyn RenderObject>>,
@@ -13,6 +13,7 @@ pub struct ConstrainedRenderObject {
 
     props: HashMap<String, RenderObjectProp>,
     render_pos: Position,
+    layout_cache: LayoutCache,
 }
 
 impl ConstrainedRenderObject {
@@ -25,14 +26,80 @@ impl ConstrainedRenderObject {
             additionnal_constraints,
             props: HashMap::new(),
             render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    pub fn set_constraints(&mut self, additionnal_constraints: BoxConstraints) {
+        self.additionnal_constraints = additionnal_constraints;
+    }
+
+    // The child's answer, unless the additional constraints force the size
+    fn intrinsic_width(&self, height: f32, child: fn(&dyn RenderObject, f32) -> f32) -> f32 {
+        let cns = &self.additionnal_constraints;
+        if cns.has_bounded_width() && cns.has_tight_width() {
+            return cns.min_width;
+        }
+        let width = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| child(c.as_ref(), height));
+        if cns.has_infinite_width() {
+            width
+        } else {
+            cns.constrain_width(width)
+        }
+    }
+
+    fn intrinsic_height(&self, width: f32, child: fn(&dyn RenderObject, f32) -> f32) -> f32 {
+        let cns = &self.additionnal_constraints;
+        if cns.has_bounded_height() && cns.has_tight_height() {
+            return cns.min_height;
+        }
+        let height = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| child(c.as_ref(), width));
+        if cns.has_infinite_height() {
+            height
+        } else {
+            cns.constrain_height(height)
         }
     }
 }
 
 impl RenderObject for ConstrainedRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(ref c) = self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(ref mut c) = self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.intrinsic_width(height, |c, h| c.min_intrinsic_width(h))
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        self.intrinsic_width(height, |c, h| c.max_intrinsic_width(h))
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.intrinsic_height(width, |c, w| c.min_intrinsic_height(w))
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.intrinsic_height(width, |c, w| c.max_intrinsic_height(w))
+    }
+
     fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
         if let Some(ref c) = self.child {
-            c.render(context, context_mut.update(self));
+            c.paint(context, context_mut.update(self));
         }
     }
 
@@ -44,6 +111,14 @@ impl RenderObject for ConstrainedRenderObject {
         self.render_pos
     }
 
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
         self.props.get(prop)
     }
@@ -54,9 +129,7 @@ impl RenderObject for ConstrainedRenderObject {
 
     fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
         match self.child {
-            Some(ref mut c) => {
-                c.calculate_layout(self.additionnal_constraints.enforce(&constraints))
-            }
+            Some(ref mut c) => c.layout(self.additionnal_constraints.enforce(&constraints)),
             None => self
                 .additionnal_constraints
                 .enforce(&constraints)
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use crate::{
+    animation::Lerp,
+    rendering::{RenderContext, RenderContextMut},
+    types::{Alignment, BoxConstraints, Color, Insets, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContainerStyle {
+    pub color: Color,
+    pub constraints: Option<BoxConstraints>,
+    pub alignment: Option<Alignment>,
+    pub padding: Insets,
+}
+
+impl Default for ContainerStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::TRANSPARENT,
+            constraints: None,
+            alignment: None,
+            padding: Insets::default(),
+        }
+    }
+}
+
+impl Lerp for ContainerStyle {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        Self {
+            color: Color::lerp(&a.color, &b.color, t),
+            constraints: match (&a.constraints, &b.constraints) {
+                (Some(a), Some(b)) => Some(BoxConstraints::lerp(a, b, t)),
+                (None, Some(b)) => Some(BoxConstraints::lerp(&BoxConstraints::default(), b, t)),
+                (Some(a), None) => Some(BoxConstraints::lerp(a, &BoxConstraints::default(), t)),
+                (None, None) => None,
+            },
+            alignment: match (a.alignment, b.alignment) {
+                (Some(a), Some(b)) => Some(Alignment::lerp(&a, &b, t)),
+                (a, b) => {
+                    if t < 0.5 {
+                        a
+                    } else {
+                        b
+                    }
+                }
+            },
+            padding: Insets::lerp(&a.padding, &b.padding, t),
+        }
+    }
+}
+
+pub struct ContainerRenderObject {
+    child: Option<Box<dyn RenderObject>>,
+    style: ContainerStyle,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+    render_size: Size,
+}
+
+impl ContainerRenderObject {
+    pub fn new(child: Option<Box<dyn RenderObject>>, style: ContainerStyle) -> Self {
+        Self {
+            child,
+            style,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+            render_size: Size::default(),
+        }
+    }
+
+    pub fn set_style(&mut self, style: ContainerStyle) {
+        self.style = style;
+    }
+
+    fn constrain_intrinsic_width(&self, width: f32) -> f32 {
+        match self.style.constraints {
+            Some(ref c) => c.constrain_width(width),
+            None => width,
+        }
+    }
+
+    fn constrain_intrinsic_height(&self, height: f32) -> f32 {
+        match self.style.constraints {
+            Some(ref c) => c.constrain_height(height),
+            None => height,
+        }
+    }
+}
+
+impl RenderObject for ContainerRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(ref c) = self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(ref mut c) = self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        context.display_list.blend_rectangle(
+            context_mut.position.x as isize,
+            context_mut.position.y as isize,
+            self.render_size.w as usize,
+            self.render_size.h as usize,
+            self.style.color.with_opacity(context_mut.opacity),
+            context_mut.elevation,
+        );
+        if let Some(ref c) = self.child {
+            c.paint(context, context_mut);
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        let padding = self.style.padding;
+        let inner = (height - padding.vertical()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_width(inner));
+        self.constrain_intrinsic_width(child + padding.horizontal())
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        let padding = self.style.padding;
+        let inner = (height - padding.vertical()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_width(inner));
+        self.constrain_intrinsic_width(child + padding.horizontal())
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        let padding = self.style.padding;
+        let inner = (width - padding.horizontal()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_height(inner));
+        self.constrain_intrinsic_height(child + padding.vertical())
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        let padding = self.style.padding;
+        let inner = (width - padding.horizontal()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_height(inner));
+        self.constrain_intrinsic_height(child + padding.vertical())
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let constraints = match self.style.constraints {
+            Some(ref c) => c.enforce(&constraints),
+            None => constraints,
+        };
+        let padding = self.style.padding;
+        let c = match self.child {
+            Some(ref mut c) => c,
+            None => {
+                // without a child, fill the available space
+                self.render_size = constraints.constrain(Size {
+                    w: if constraints.has_bounded_width() {
+                        constraints.max_width
+                    } else {
+                        0.0
+                    },
+                    h: if constraints.has_bounded_height() {
+                        constraints.max_height
+                    } else {
+                        0.0
+                    },
+                });
+                return self.render_size;
+            }
+        };
+        let inner_constraints = constraints.deflate(padding);
+        let child_size = match self.style.alignment {
+            Some(_) => c.layout(inner_constraints.loosen()),
+            None => c.layout(inner_constraints),
+        };
+        let expand = self.style.alignment.is_some();
+        self.render_size = constraints.constrain(Size {
+            w: if expand && constraints.has_bounded_width() {
+                constraints.max_width
+            } else {
+                child_size.w + padding.horizontal()
+            },
+            h: if expand && constraints.has_bounded_height() {
+                constraints.max_height
+            } else {
+                child_size.h + padding.vertical()
+            },
+        });
+        let (px, py) = self.style.alignment.unwrap_or(Alignment::TopLeft).loc();
+        let free_w = self.render_size.w - padding.horizontal() - child_size.w;
+        let free_h = self.render_size.h - padding.vertical() - child_size.h;
+        c.set_render_pos(Position {
+            x: padding.left + free_w * (px + 1.0) / 2.0,
+            y: padding.top + free_h * (py + 1.0) / 2.0,
+        });
+        self.render_size
+    }
+}
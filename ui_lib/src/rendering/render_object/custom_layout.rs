@@ -0,0 +1,420 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+// Lays out the children of a `CustomMultiChildLayout`, which are found by the id given to
+// them with `LayoutId`
+pub trait MultiChildLayoutDelegate {
+    fn size(&self, constraints: &BoxConstraints) -> Size {
+        constraints.biggest()
+    }
+    // Each child should be laid out once, the others are drawn with their last layout
+    fn perform_layout(&self, layout: &mut MultiChildLayout, size: Size);
+}
+
+pub struct MultiChildLayout<'a> {
+    children: &'a mut [Box<dyn RenderObject>],
+    ids: HashMap<usize, usize>,
+}
+
+impl MultiChildLayout<'_> {
+    pub fn has_child(&self, id: usize) -> bool {
+        self.ids.contains_key(&id)
+    }
+
+    pub fn child_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.ids.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    // None when no child has that id
+    pub fn layout_child(&mut self, id: usize, constraints: BoxConstraints) -> Option<Size> {
+        let i = *self.ids.get(&id)?;
+        Some(self.children[i].layout(constraints))
+    }
+
+    // Does nothing when no child has that id
+    pub fn position_child(&mut self, id: usize, pos: Position) {
+        if let Some(&i) = self.ids.get(&id) {
+            self.children[i].set_render_pos(pos);
+        }
+    }
+}
+
+pub struct CustomMultiChildLayoutRenderObject {
+    children: Vec<Box<dyn RenderObject>>,
+    delegate: Rc<dyn MultiChildLayoutDelegate>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl CustomMultiChildLayoutRenderObject {
+    pub fn new(
+        children: Vec<Box<dyn RenderObject>>,
+        delegate: Rc<dyn MultiChildLayoutDelegate>,
+    ) -> Self {
+        Self {
+            children,
+            delegate,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for CustomMultiChildLayoutRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in &self.children {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in &mut self.children {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.max_intrinsic_width(height)
+    }
+
+    // The delegate only knows its size for given constraints
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        let width = self
+            .delegate
+            .size(&BoxConstraints::tight_for_height(height))
+            .w;
+        if width.is_finite() {
+            width
+        } else {
+            0.0
+        }
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.max_intrinsic_height(width)
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        let height = self
+            .delegate
+            .size(&BoxConstraints::tight_for_width(width))
+            .h;
+        if height.is_finite() {
+            height
+        } else {
+            0.0
+        }
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        for c in &self.children {
+            c.paint(context, context_mut.update(self));
+        }
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let size = constraints.constrain(self.delegate.size(&constraints));
+        let ids = self
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| Some((c.get_prop_usize("layout_id")?, i)))
+            .collect();
+        let mut layout = MultiChildLayout {
+            children: &mut self.children,
+            ids,
+        };
+        self.delegate.perform_layout(&mut layout, size);
+        size
+    }
+}
+
+// Places the children of a `Flow` when painting, so moving them doesn't need a new layout
+pub trait FlowDelegate {
+    fn size(&self, constraints: &BoxConstraints) -> Size {
+        constraints.biggest()
+    }
+    fn child_constraints(&self, _index: usize, constraints: &BoxConstraints) -> BoxConstraints {
+        constraints.loosen()
+    }
+    // Children are drawn in the order `paint_child` is called, the ones not painted are hidden
+    fn paint_children(&self, painter: &mut FlowPainter);
+    // Whether the children have to be painted again though the layout didn't change
+    fn should_repaint(&self) -> bool {
+        false
+    }
+}
+
+pub struct FlowPainter<'a, 'b> {
+    children: &'a [Box<dyn RenderObject>],
+    sizes: &'a [Size],
+    size: Size,
+    context: &'a mut RenderContext<'b>,
+    context_mut: RenderContextMut,
+}
+
+impl FlowPainter<'_, '_> {
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn child_size(&self, index: usize) -> Size {
+        self.sizes[index]
+    }
+
+    pub fn paint_child(&mut self, index: usize, offset: Position) {
+        self.paint_child_with_opacity(index, offset, 1.0);
+    }
+
+    pub fn paint_child_with_opacity(&mut self, index: usize, offset: Position, opacity: f32) {
+        let context_mut = RenderContextMut {
+            position: self.context_mut.position + offset,
+            ..self.context_mut.clone()
+        };
+        if opacity >= 1.0 {
+            return self.children[index].paint(self.context, context_mut);
+        }
+        self.context.display_list.push_opacity(opacity);
+        self.children[index].paint(self.context, context_mut);
+        self.context.display_list.pop_opacity();
+    }
+}
+
+pub struct FlowRenderObject {
+    children: Vec<Box<dyn RenderObject>>,
+    delegate: Rc<dyn FlowDelegate>,
+    child_sizes: Vec<Size>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+    render_size: Size,
+}
+
+impl FlowRenderObject {
+    pub fn new(children: Vec<Box<dyn RenderObject>>, delegate: Rc<dyn FlowDelegate>) -> Self {
+        Self {
+            children,
+            delegate,
+            child_sizes: Vec::new(),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+            render_size: Size::default(),
+        }
+    }
+}
+
+impl RenderObject for FlowRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in &self.children {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in &mut self.children {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn paint_changed(&self) -> bool {
+        self.delegate.should_repaint()
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let mut painter = FlowPainter {
+            children: &self.children,
+            sizes: &self.child_sizes,
+            size: self.render_size,
+            context,
+            context_mut: context_mut.update(self),
+        };
+        self.delegate.paint_children(&mut painter);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.render_size = constraints.constrain(self.delegate.size(&constraints));
+        let delegate = &self.delegate;
+        self.child_sizes = self
+            .children
+            .iter_mut()
+            .enumerate()
+            .map(|(i, c)| {
+                let size = c.layout(delegate.child_constraints(i, &constraints));
+                // the offset is given when painting
+                c.set_render_pos(Position::default());
+                size
+            })
+            .collect();
+        self.render_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_object::ConstrainedRenderObject;
+
+    fn child(id: usize, w: f32, h: f32) -> Box<dyn RenderObject> {
+        let mut c: Box<dyn RenderObject> = Box::new(ConstrainedRenderObject::new(
+            None,
+            BoxConstraints::tight_for(w, h),
+        ));
+        c.set_prop_usize("layout_id", id);
+        c
+    }
+
+    // Puts the child 1 under the child 0, and the child 2 on the right if there is one
+    struct Stack;
+
+    impl MultiChildLayoutDelegate for Stack {
+        fn perform_layout(&self, layout: &mut MultiChildLayout, size: Size) {
+            let loose = BoxConstraints::loose(size);
+            let header = layout.layout_child(0, loose.clone()).unwrap_or_default();
+            layout.position_child(0, Position::default());
+            layout.layout_child(1, loose.clone());
+            layout.position_child(
+                1,
+                Position {
+                    x: 0.0,
+                    y: header.h,
+                },
+            );
+            if let Some(side) = layout.layout_child(2, loose) {
+                layout.position_child(
+                    2,
+                    Position {
+                        x: size.w - side.w,
+                        y: 0.0,
+                    },
+                );
+            }
+        }
+    }
+
+    fn positions(ro: &dyn RenderObject) -> Vec<Position> {
+        let mut positions = Vec::new();
+        ro.visit_children(&mut |c| positions.push(c.get_render_pos()));
+        positions
+    }
+
+    #[test]
+    fn delegate_places_children_by_id() {
+        let mut ro = CustomMultiChildLayoutRenderObject::new(
+            vec![
+                child(1, 10.0, 10.0),
+                child(0, 50.0, 20.0),
+                child(7, 5.0, 5.0),
+            ],
+            Rc::new(Stack),
+        );
+        let size = ro.layout(BoxConstraints::tight_for(100.0, 100.0));
+        assert_eq!(size, Size { w: 100.0, h: 100.0 });
+        let positions = positions(&ro);
+        assert_eq!(positions[0], Position { x: 0.0, y: 20.0 });
+        assert_eq!(positions[1], Position::default());
+        // the child 2 is missing, the child 7 isn't known to the delegate
+        assert_eq!(positions[2], Position::default());
+    }
+
+    #[test]
+    fn unknown_ids_are_ignored() {
+        let mut children = vec![child(3, 10.0, 10.0)];
+        let mut layout = MultiChildLayout {
+            children: &mut children,
+            ids: HashMap::from([(3, 0)]),
+        };
+        assert!(layout.has_child(3));
+        assert_eq!(layout.child_ids(), vec![3]);
+        assert_eq!(layout.layout_child(4, BoxConstraints::default()), None);
+        layout.position_child(4, Position { x: 1.0, y: 1.0 });
+        assert_eq!(
+            layout.layout_child(3, BoxConstraints::default()),
+            Some(Size { w: 10.0, h: 10.0 })
+        );
+    }
+
+    struct Loose;
+
+    impl FlowDelegate for Loose {
+        fn paint_children(&self, _painter: &mut FlowPainter) {}
+    }
+
+    #[test]
+    fn flow_lays_children_out_with_loose_constraints() {
+        let mut ro = FlowRenderObject::new(
+            vec![child(0, 30.0, 40.0), child(1, 500.0, 10.0)],
+            Rc::new(Loose),
+        );
+        let size = ro.layout(BoxConstraints::tight_for(100.0, 50.0));
+        assert_eq!(size, Size { w: 100.0, h: 50.0 });
+        assert_eq!(
+            ro.child_sizes,
+            vec![Size { w: 30.0, h: 40.0 }, Size { w: 100.0, h: 10.0 }]
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use crate::{
+    inputs::MouseStatus,
+    rendering::{Canvas, RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Rect, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+// Draws the content of a `CustomPaint`, with (0, 0) at its top left corner
+pub trait Painter {
+    fn paint(&self, canvas: &mut Canvas, size: Size);
+    // Whether to paint again though the size didn't change, e.g. after the data shown did
+    fn should_repaint(&self) -> bool {
+        false
+    }
+    // Whether a point is on what was painted, for shapes not filling their whole box
+    fn hit_test(&self, _position: Position, _size: Size) -> bool {
+        true
+    }
+}
+
+// Given the mouse position local to the painter, when it is over what was painted
+pub type PaintMouseCallback = Rc<dyn Fn(Position, &MouseStatus)>;
+
+pub struct CustomPaintRenderObject {
+    painter: Rc<dyn Painter>,
+    child: Option<Box<dyn RenderObject>>,
+    // used without a child, as big as the constraints allow
+    size: Size,
+    on_mouse: Option<PaintMouseCallback>,
+    drawn_at: Cell<(Rect, usize)>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl CustomPaintRenderObject {
+    pub fn new(
+        painter: Rc<dyn Painter>,
+        child: Option<Box<dyn RenderObject>>,
+        size: Size,
+        on_mouse: Option<PaintMouseCallback>,
+    ) -> Self {
+        Self {
+            painter,
+            child,
+            size,
+            on_mouse,
+            drawn_at: Cell::new((Rect::default(), 0)),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for CustomPaintRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(c) = &self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(c) = &mut self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(self.size.w, |c| c.min_intrinsic_width(height))
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(self.size.w, |c| c.max_intrinsic_width(height))
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(self.size.h, |c| c.min_intrinsic_height(width))
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(self.size.h, |c| c.max_intrinsic_height(width))
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn paint_changed(&self) -> bool {
+        self.painter.should_repaint()
+    }
+
+    fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+        if let Some(c) = &mut self.child {
+            c.handle_mouse_event(mouse);
+        }
+        let Some(on_mouse) = &self.on_mouse else {
+            return;
+        };
+        let (rect, elevation) = self.drawn_at.get();
+        let Some((x, y, _)) = mouse.pos else {
+            return;
+        };
+        let local = Position {
+            x: x as f32 - rect.x,
+            y: y as f32 - rect.y,
+        };
+        if mouse.is_hovering(rect.pos(), rect.size(), elevation)
+            && self.painter.hit_test(local, rect.size())
+        {
+            on_mouse(local, mouse);
+        }
+    }
+
+    // The painter draws behind the child, and only inside of its own box so that the damage
+    // tracking stays right
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let size = self.layout_cache.size();
+        self.drawn_at.set((
+            Rect::from_pos_size(context_mut.position, size),
+            context_mut.elevation,
+        ));
+        context.display_list.push_transform(context_mut.position);
+        let mut canvas = Canvas::new(context.display_list, size, context_mut.elevation);
+        canvas.push_clip(Rect::from_pos_size(Position::default(), size));
+        self.painter.paint(&mut canvas, size);
+        drop(canvas);
+        context.display_list.pop_transform();
+
+        if let Some(c) = &self.child {
+            c.paint(context, context_mut);
+        }
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        match &mut self.child {
+            Some(c) => c.layout(constraints),
+            None => constraints.constrain(self.size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{
+        rendering::{render_object::ConstrainedRenderObject, DamageRegion, DisplayList},
+        types::Color,
+    };
+
+    // Fills its box but is only hit inside of the circle in it, painted again when `dirty`
+    // is set
+    #[derive(Default)]
+    struct Dot {
+        dirty: Cell<bool>,
+        sizes: RefCell<Vec<Size>>,
+    }
+
+    impl Painter for Dot {
+        fn paint(&self, canvas: &mut Canvas, size: Size) {
+            self.sizes.borrow_mut().push(size);
+            self.dirty.set(false);
+            canvas.fill_rect(Rect::from_pos_size(Position::default(), size), Color::BLACK);
+        }
+        fn should_repaint(&self) -> bool {
+            self.dirty.get()
+        }
+        fn hit_test(&self, position: Position, size: Size) -> bool {
+            let center = Position {
+                x: size.w / 2.0,
+                y: size.h / 2.0,
+            };
+            (position - center).length() <= size.w.min(size.h) / 2.0
+        }
+    }
+
+    fn paint(ro: &dyn RenderObject) -> String {
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        let context_mut = RenderContextMut {
+            position: Position { x: 10.0, y: 20.0 },
+            ..RenderContextMut::default()
+        };
+        ro.paint(&mut context, context_mut);
+        list.to_text()
+    }
+
+    #[test]
+    fn the_size_comes_from_the_child_or_the_constraints() {
+        let dot = Rc::new(Dot::default());
+        let mut alone =
+            CustomPaintRenderObject::new(dot.clone(), None, Size { w: 30.0, h: 30.0 }, None);
+        assert_eq!(alone.max_intrinsic_width(f32::INFINITY), 30.0);
+        assert_eq!(
+            alone.layout(BoxConstraints::loose(Size { w: 20.0, h: 100.0 })),
+            Size { w: 20.0, h: 30.0 }
+        );
+        let child = ConstrainedRenderObject::new(None, BoxConstraints::tight_for(12.0, 8.0));
+        let mut wrapping = CustomPaintRenderObject::new(
+            dot,
+            Some(Box::new(child)),
+            Size { w: 30.0, h: 30.0 },
+            None,
+        );
+        assert_eq!(wrapping.max_intrinsic_height(f32::INFINITY), 8.0);
+        assert_eq!(
+            wrapping.layout(BoxConstraints::default()),
+            Size { w: 12.0, h: 8.0 }
+        );
+    }
+
+    #[test]
+    fn painters_draw_in_their_own_clipped_box() {
+        let dot = Rc::new(Dot::default());
+        let mut ro =
+            CustomPaintRenderObject::new(dot.clone(), None, Size { w: 30.0, h: 20.0 }, None);
+        ro.layout(BoxConstraints::default());
+        assert_eq!(
+            paint(&ro),
+            "push_transform 10 20\n\
+             \x20 push_clip 0 0 30x20\n\
+             \x20   fill_rect 0 0 30x20 #000000ff z=0\n\
+             \x20 pop_clip\n\
+             pop_transform\n"
+        );
+        assert_eq!(*dot.sizes.borrow(), vec![Size { w: 30.0, h: 20.0 }]);
+    }
+
+    #[test]
+    fn painters_ask_to_be_painted_again() {
+        let dot = Rc::new(Dot::default());
+        let mut ro =
+            CustomPaintRenderObject::new(dot.clone(), None, Size { w: 30.0, h: 20.0 }, None);
+        ro.layout(BoxConstraints::default());
+        paint(&ro);
+        let mut damage = DamageRegion::default();
+        ro.collect_damage(&mut damage);
+        damage.take(Size { w: 100.0, h: 100.0 });
+        ro.collect_damage(&mut damage);
+        assert!(damage.is_empty());
+
+        dot.dirty.set(true);
+        ro.collect_damage(&mut damage);
+        assert_eq!(
+            damage.take(Size { w: 100.0, h: 100.0 }),
+            vec![Rect::new(10.0, 20.0, 30.0, 20.0)]
+        );
+    }
+
+    #[test]
+    fn the_mouse_is_given_where_the_painter_hits() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let on_mouse: PaintMouseCallback = {
+            let seen = seen.clone();
+            Rc::new(move |pos, _| seen.borrow_mut().push(pos))
+        };
+        let mut ro = CustomPaintRenderObject::new(
+            Rc::new(Dot::default()),
+            None,
+            Size { w: 20.0, h: 20.0 },
+            Some(on_mouse),
+        );
+        ro.layout(BoxConstraints::default());
+        paint(&ro);
+        let at = |x, y| MouseStatus::now(Some((x, y, 0)), false, false, false, 0.0);
+        ro.handle_mouse_event(&at(20, 30));
+        // in the box, out of the circle
+        ro.handle_mouse_event(&at(11, 21));
+        // out of the box
+        ro.handle_mouse_event(&at(50, 30));
+        assert_eq!(*seen.borrow(), vec![Position { x: 10.0, y: 10.0 }]);
+    }
+}
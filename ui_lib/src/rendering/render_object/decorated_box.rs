@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{BoxShadow, Gradient, Paint, RenderContext, RenderContextMut},
+    types::{BoxConstraints, Color, Position, Rect, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct BoxDecoration {
+    pub color: Color,
+    // drawn over the color, with positions as fractions of the box: (0, 0) is the top left
+    // corner and (1, 1) the bottom right one
+    pub gradient: Option<Gradient>,
+    pub border_radius: f32,
+    pub dither: bool,
+    // drawn under the box, in order
+    pub shadows: Vec<BoxShadow>,
+}
+
+pub struct DecoratedBoxRenderObject {
+    child: Option<Box<dyn RenderObject>>,
+    decoration: BoxDecoration,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl DecoratedBoxRenderObject {
+    pub fn new(child: Option<Box<dyn RenderObject>>, decoration: BoxDecoration) -> Self {
+        Self {
+            child,
+            decoration,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    pub fn set_decoration(&mut self, decoration: BoxDecoration) {
+        if decoration != self.decoration {
+            self.decoration = decoration;
+            self.mark_needs_paint();
+        }
+    }
+}
+
+impl RenderObject for DecoratedBoxRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(c) = &self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(c) = &mut self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let size = self.layout_cache.size();
+        let (w, h) = (size.w as usize, size.h as usize);
+        let decoration = &self.decoration;
+        let depth = context_mut.elevation;
+        let list = &mut *context.display_list;
+        let rect = Rect::from_pos_size(context_mut.position, size);
+        for shadow in &decoration.shadows {
+            let shadow = BoxShadow {
+                color: shadow.color.with_opacity(context_mut.opacity),
+                ..*shadow
+            };
+            list.draw_shadow(rect, decoration.border_radius, &shadow, depth);
+        }
+        list.push_transform(context_mut.position);
+        if decoration.color.a != 0 {
+            let color = decoration.color.with_opacity(context_mut.opacity);
+            list.draw_rounded_rect(0, 0, w, h, decoration.border_radius, color, depth);
+        }
+        if let Some(gradient) = &decoration.gradient {
+            let paint = Paint::from(gradient.scale(size)).dither(decoration.dither);
+            list.draw_rounded_rect(0, 0, w, h, decoration.border_radius, paint, depth);
+        }
+        list.pop_transform();
+        if let Some(c) = &self.child {
+            c.paint(context, context_mut);
+        }
+    }
+
+    fn paint_bounds(&self, rect: Rect) -> Rect {
+        self.decoration
+            .shadows
+            .iter()
+            .fold(rect, |area, shadow| area.union(&shadow.bounds(rect)))
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        match &mut self.child {
+            Some(c) => c.layout(constraints),
+            None => constraints.constrain(Size::default()),
+        }
+    }
+}
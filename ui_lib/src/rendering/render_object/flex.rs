@@ -5,7 +5,7 @@ use crate::types::{
     Size, TextDirection, VerticalDirection,
 };
 
-use super::{RenderObject, RenderObjectProp};
+use super::{LayoutCache, RenderObject, RenderObjectProp};
 
 pub struct RenderFlex {
     children: Vec<Box<dyn RenderObject>>,
@@ -17,9 +17,11 @@ pub struct RenderFlex {
     vertical_direction: VerticalDirection,
     text_baseline: Option<()>,
     clip_behavior: Option<()>,
+    spacing: f32,
 
     props: HashMap<String, RenderObjectProp>,
     render_pos: Position,
+    layout_cache: LayoutCache,
 }
 
 struct LayoutSizes {
@@ -77,37 +79,129 @@ fn get_vs(
         .collect()
 }
 
+// Splits `free` between the flexible children, given as (flex, min, max), in proportion to
+// their flex. The room refused by the children reaching their min or max goes to the others,
+// until every child is within its bounds.
+fn distribute_flex(free: f32, items: &[(f32, f32, f32)]) -> Vec<f32> {
+    let mut sizes = vec![0f32; items.len()];
+    let mut targets = vec![0f32; items.len()];
+    let mut frozen = vec![false; items.len()];
+    loop {
+        let mut remaining_space = free;
+        let mut remaining_flex = 0f32;
+        for (i, (flex, _, _)) in items.iter().enumerate() {
+            if frozen[i] {
+                remaining_space -= sizes[i];
+            } else {
+                remaining_flex += flex;
+            }
+        }
+        if remaining_flex <= 0.0 {
+            return sizes;
+        }
+        let remaining_space = remaining_space.max(0.0);
+
+        let mut violation = 0f32;
+        for (i, (flex, min, max)) in items.iter().enumerate() {
+            if !frozen[i] {
+                targets[i] = remaining_space * flex / remaining_flex;
+                sizes[i] = targets[i].clamp(*min, *max);
+                violation += sizes[i] - targets[i];
+            }
+        }
+        if violation.abs() < 1e-3 {
+            return sizes;
+        }
+        // children that took more than their share leave less for the others and the
+        // other way around, those that bounded in the direction of the total are final
+        for i in 0..items.len() {
+            if !frozen[i] {
+                frozen[i] = if violation > 0.0 {
+                    sizes[i] > targets[i]
+                } else {
+                    sizes[i] < targets[i]
+                };
+            }
+        }
+    }
+}
+
 impl RenderFlex {
-    pub fn new(
-        children: Vec<Box<dyn RenderObject>>,
-        direction: Axis,
-        main_axis_size: MainAxisSize,
-        main_axis_alignment: MainAxisAlignment,
-        cross_axis_alignment: CrossAxisAlignment,
-        text_direction: TextDirection,
-        vertical_direction: VerticalDirection,
-        text_baseline: Option<()>,
-        clip_behavior: Option<()>,
-    ) -> Self {
+    // Starts packed at the start of the main axis and centered across it, with the
+    // builder methods below changing the rest
+    pub fn new(children: Vec<Box<dyn RenderObject>>, direction: Axis) -> Self {
         Self {
             children,
             direction,
-            main_axis_size,
-            main_axis_alignment,
-            cross_axis_alignment,
-            text_direction,
-            vertical_direction,
-            text_baseline,
-            clip_behavior,
+            main_axis_size: MainAxisSize::Max,
+            main_axis_alignment: MainAxisAlignment::Start,
+            cross_axis_alignment: CrossAxisAlignment::Center,
+            text_direction: TextDirection::Ltr,
+            vertical_direction: VerticalDirection::Down,
+            text_baseline: None,
+            clip_behavior: None,
+            spacing: 0.0,
             props: HashMap::new(),
             render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
         }
     }
 
+    pub fn main_axis_size(mut self, main_axis_size: MainAxisSize) -> Self {
+        self.main_axis_size = main_axis_size;
+        self
+    }
+
+    pub fn main_axis_alignment(mut self, main_axis_alignment: MainAxisAlignment) -> Self {
+        self.main_axis_alignment = main_axis_alignment;
+        self
+    }
+
+    pub fn cross_axis_alignment(mut self, cross_axis_alignment: CrossAxisAlignment) -> Self {
+        self.cross_axis_alignment = cross_axis_alignment;
+        self
+    }
+
+    pub fn text_direction(mut self, text_direction: TextDirection) -> Self {
+        self.text_direction = text_direction;
+        self
+    }
+
+    pub fn vertical_direction(mut self, vertical_direction: VerticalDirection) -> Self {
+        self.vertical_direction = vertical_direction;
+        self
+    }
+
+    pub fn text_baseline(mut self, text_baseline: Option<()>) -> Self {
+        self.text_baseline = text_baseline;
+        self
+    }
+
+    pub fn clip_behavior(mut self, clip_behavior: Option<()>) -> Self {
+        self.clip_behavior = clip_behavior;
+        self
+    }
+
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
     fn get_flex(&self, child: &dyn RenderObject) -> i32 {
         child.get_prop_i32("flex").unwrap_or(0)
     }
 
+    // Main axis extents set on `Flexible`, the max being infinite when not set
+    fn get_flex_extents(&self, child: &dyn RenderObject) -> (f32, f32) {
+        let min = child.get_prop_f32("flex_min").unwrap_or(0.0);
+        let max = child.get_prop_f32("flex_max").unwrap_or(f32::INFINITY);
+        (min, max.max(min))
+    }
+
+    fn total_spacing(&self) -> f32 {
+        self.spacing * self.children.len().saturating_sub(1) as f32
+    }
+
     fn get_fit(&self, child: &dyn RenderObject) -> FlexFit {
         child.get_prop_i32("fit").unwrap_or(0).into()
     }
@@ -130,6 +224,70 @@ impl RenderFlex {
         self.cross_axis_alignment != CrossAxisAlignment::Baseline
     }
 
+    // Size along `axis` when the other axis is `extent` big. Along the main axis, flexible
+    // children get the room that gives each of them their intrinsic size. Across it, they get
+    // their share of the room inflexible children leave on the main axis.
+    fn intrinsic_size(
+        &self,
+        axis: Axis,
+        extent: f32,
+        child_size: fn(&dyn RenderObject, f32) -> f32,
+    ) -> f32 {
+        if !self.can_compute_intrinsics() {
+            return 0.0;
+        }
+        let mut total_flex = 0;
+        if axis == self.direction {
+            let mut inflexible_size = 0f32;
+            let mut max_flex_fraction = 0f32;
+            let mut flexible = Vec::new();
+            for c in &self.children {
+                let flex = self.get_flex(c.as_ref());
+                let size = child_size(c.as_ref(), extent);
+                if flex > 0 {
+                    let (min, max) = self.get_flex_extents(c.as_ref());
+                    max_flex_fraction = max_flex_fraction.max(size.clamp(min, max) / flex as f32);
+                    flexible.push((flex as f32, min, max));
+                } else {
+                    inflexible_size += size;
+                }
+            }
+            // the share each child gets is clamped by its extents like in the layout
+            let flexible_size: f32 = flexible
+                .iter()
+                .map(|(flex, min, max)| (max_flex_fraction * flex).clamp(*min, *max))
+                .sum();
+            flexible_size + inflexible_size + self.total_spacing()
+        } else {
+            let mut inflexible_space = self.total_spacing();
+            let mut max_cross_size = 0f32;
+            for c in &self.children {
+                let flex = self.get_flex(c.as_ref());
+                if flex > 0 {
+                    total_flex += flex;
+                    continue;
+                }
+                let main_size = match self.direction {
+                    Axis::Horizontal => c.max_intrinsic_width(f32::INFINITY),
+                    Axis::Vertical => c.max_intrinsic_height(f32::INFINITY),
+                };
+                inflexible_space += main_size;
+                max_cross_size = max_cross_size.max(child_size(c.as_ref(), main_size));
+            }
+            let space_per_flex = 0f32.max(extent - inflexible_space) / total_flex.max(1) as f32;
+            for c in &self.children {
+                let flex = self.get_flex(c.as_ref());
+                if flex > 0 {
+                    let (min, max) = self.get_flex_extents(c.as_ref());
+                    let main_size = (space_per_flex * flex as f32).clamp(min, max);
+                    let size = child_size(c.as_ref(), main_size);
+                    max_cross_size = max_cross_size.max(size);
+                }
+            }
+            max_cross_size
+        }
+    }
+
     fn position_children(&mut self, sizes: &[Size], main_size: f32, cross_size: f32) {
         let mut free_space = 0f32;
         for s in sizes {
@@ -138,9 +296,11 @@ impl RenderFlex {
                 Axis::Vertical => s.h,
             }
         }
-        let (before, between, _) = self
-            .main_axis_alignment
-            .distribute_free_space(main_size - free_space, sizes.len());
+        let (before, between, _) = self.main_axis_alignment.distribute_free_space(
+            main_size - free_space,
+            sizes.len(),
+            self.spacing,
+        );
         let backwards = (self.direction == Axis::Horizontal
             && self.text_direction == TextDirection::Rtl)
             || (self.direction == Axis::Vertical
@@ -188,7 +348,6 @@ impl RenderFlex {
     }
 
     fn compute_sizes(&mut self, constraints: BoxConstraints) -> (LayoutSizes, Vec<Size>) {
-        let mut total_flex = 0;
         let max_main_size = match self.direction {
             Axis::Horizontal => constraints.max_width,
             Axis::Vertical => constraints.max_height,
@@ -197,13 +356,10 @@ impl RenderFlex {
         let mut cross_size = 0f32;
         let mut allocated_size = 0f32;
         let mut sizes = Vec::new();
-        let mut last_flex_child_id = -1;
-        for (i, c) in self.children.iter_mut().enumerate() {
+        for c in self.children.iter_mut() {
             let flex = c.get_prop_i32("flex").unwrap_or(0);
             if flex > 0 {
                 sizes.push(Size { w: 0.0, h: 0.0 });
-                total_flex += flex;
-                last_flex_child_id = i as isize;
             } else {
                 let inner_constraints = match self.cross_axis_alignment {
                     CrossAxisAlignment::Stretch => match self.direction {
@@ -227,7 +383,7 @@ impl RenderFlex {
                         },
                     },
                 };
-                let child_size = c.calculate_layout(inner_constraints);
+                let child_size = c.layout(inner_constraints);
                 sizes.push(child_size);
                 let (m_size, c_size) = match self.direction {
                     Axis::Horizontal => (child_size.w, child_size.h),
@@ -238,32 +394,43 @@ impl RenderFlex {
             }
         }
 
-        let free_space = 0f32.max(if can_flex { max_main_size } else { 0.0 } - allocated_size);
-        let mut allocated_flex_space = 0f32;
+        let total_spacing = self.total_spacing();
+        let free_space =
+            0f32.max(if can_flex { max_main_size } else { 0.0 } - allocated_size - total_spacing);
 
-        let space_per_flex = if can_flex && total_flex > 0 {
-            free_space / (total_flex as f32)
+        let flex_extents: Vec<(f32, f32)> = self
+            .children
+            .iter()
+            .map(|c| self.get_flex_extents(c.as_ref()))
+            .collect();
+        let flex_items: Vec<(f32, f32, f32)> = self
+            .children
+            .iter()
+            .zip(&flex_extents)
+            .filter(|(c, _)| self.get_flex(c.as_ref()) > 0)
+            .map(|(c, (min, max))| (self.get_flex(c.as_ref()) as f32, *min, *max))
+            .collect();
+        let mut flex_sizes = if can_flex {
+            distribute_flex(free_space, &flex_items)
         } else {
-            f32::NAN
-        };
+            Vec::new()
+        }
+        .into_iter();
+
         for (i, c) in self.children.iter_mut().enumerate() {
             let flex = c.get_prop_i32("flex").unwrap_or(0);
             if flex > 0 {
-                let max_child_extent = if can_flex {
-                    if i as isize == last_flex_child_id {
-                        free_space - allocated_flex_space
-                    } else {
-                        space_per_flex * flex as f32
-                    }
-                } else {
-                    f32::INFINITY
+                let (min_extent, max_extent) = flex_extents[i];
+                let max_child_extent = match flex_sizes.next() {
+                    Some(size) => size,
+                    None => max_extent,
                 };
                 let min_child_extent = match c.get_prop_i32("fit").unwrap_or(0).into() {
                     FlexFit::Tight => {
                         assert!(max_child_extent < f32::INFINITY);
                         max_child_extent
                     }
-                    FlexFit::Loose => 0.0,
+                    FlexFit::Loose => min_extent.min(max_child_extent),
                 };
                 let inner_constraints = match self.cross_axis_alignment {
                     CrossAxisAlignment::Stretch => match self.direction {
@@ -295,7 +462,7 @@ impl RenderFlex {
                         },
                     },
                 };
-                let child_size = c.calculate_layout(inner_constraints);
+                let child_size = c.layout(inner_constraints);
                 sizes[i] = child_size;
                 let (m_size, c_size) = match self.direction {
                     Axis::Horizontal => (child_size.w, child_size.h),
@@ -303,7 +470,6 @@ impl RenderFlex {
                 };
                 assert!(m_size <= max_child_extent);
                 allocated_size += m_size;
-                allocated_flex_space += max_child_extent;
                 cross_size = cross_size.max(c_size);
             }
         }
@@ -311,7 +477,7 @@ impl RenderFlex {
         let ideal_size = if can_flex && self.main_axis_size == MainAxisSize::Max {
             max_main_size
         } else {
-            allocated_size
+            allocated_size + total_spacing
         };
 
         return (
@@ -325,6 +491,34 @@ impl RenderFlex {
 }
 
 impl RenderObject for RenderFlex {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in &self.children {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in &mut self.children {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.intrinsic_size(Axis::Horizontal, height, |c, h| c.min_intrinsic_width(h))
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        self.intrinsic_size(Axis::Horizontal, height, |c, h| c.max_intrinsic_width(h))
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.intrinsic_size(Axis::Vertical, width, |c, w| c.min_intrinsic_height(w))
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.intrinsic_size(Axis::Vertical, width, |c, w| c.max_intrinsic_height(w))
+    }
+
     fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
         self.props.insert(prop.to_string(), val);
     }
@@ -341,13 +535,21 @@ impl RenderObject for RenderFlex {
         self.render_pos
     }
 
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn render(
         &self,
         context: &mut crate::rendering::RenderContext,
         context_mut: crate::rendering::RenderContextMut,
     ) {
         for c in &self.children {
-            c.render(context, context_mut.update(self));
+            c.paint(context, context_mut.update(self));
         }
     }
 
@@ -381,3 +583,149 @@ impl RenderObject for RenderFlex {
         size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_object::ConstrainedRenderObject;
+
+    fn sized(w: f32, h: f32) -> Box<dyn RenderObject> {
+        Box::new(ConstrainedRenderObject::new(
+            None,
+            BoxConstraints::tight_for(w, h),
+        ))
+    }
+
+    fn flexible(
+        mut child: Box<dyn RenderObject>,
+        flex: i32,
+        extents: (f32, f32),
+    ) -> Box<dyn RenderObject> {
+        child.set_prop_i32("flex", flex);
+        child.set_prop_f32("flex_min", extents.0);
+        child.set_prop_f32("flex_max", extents.1);
+        child
+    }
+
+    fn row(children: Vec<Box<dyn RenderObject>>, spacing: f32) -> RenderFlex {
+        RenderFlex::new(children, Axis::Horizontal)
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .spacing(spacing)
+    }
+
+    #[test]
+    fn main_axis_intrinsics_give_flexible_children_their_share() {
+        let row = row(
+            vec![
+                sized(10.0, 10.0),
+                flexible(sized(20.0, 10.0), 1, (0.0, f32::INFINITY)),
+                flexible(sized(10.0, 10.0), 2, (0.0, f32::INFINITY)),
+            ],
+            5.0,
+        );
+        // 20 per flex unit
+        assert_eq!(row.max_intrinsic_width(f32::INFINITY), 80.0);
+    }
+
+    #[test]
+    fn main_axis_intrinsics_clamp_flexible_children() {
+        let clamped = row(
+            vec![
+                flexible(sized(100.0, 10.0), 1, (0.0, 30.0)),
+                flexible(sized(5.0, 10.0), 1, (15.0, 40.0)),
+            ],
+            0.0,
+        );
+        // the first child stops at its max, the second one gets as much
+        assert_eq!(clamped.max_intrinsic_width(f32::INFINITY), 60.0);
+        let grown = row(
+            vec![
+                flexible(sized(5.0, 10.0), 1, (0.0, f32::INFINITY)),
+                flexible(sized(5.0, 10.0), 1, (25.0, f32::INFINITY)),
+            ],
+            0.0,
+        );
+        assert_eq!(grown.min_intrinsic_width(f32::INFINITY), 50.0);
+    }
+
+    fn main_sizes(flex: &RenderFlex) -> Vec<(f32, f32)> {
+        let mut sizes = Vec::new();
+        flex.visit_children(&mut |c| {
+            let w = c.layout_cache().unwrap().size().w;
+            sizes.push((c.get_render_pos().x, w));
+        });
+        sizes
+    }
+
+    #[test]
+    fn distribute_flex_gives_refused_room_to_the_others() {
+        const INF: f32 = f32::INFINITY;
+        assert_eq!(
+            distribute_flex(90.0, &[(1.0, 0.0, INF), (2.0, 0.0, INF)]),
+            vec![30.0, 60.0]
+        );
+        assert_eq!(
+            distribute_flex(90.0, &[(1.0, 0.0, 10.0), (1.0, 0.0, INF)]),
+            vec![10.0, 80.0]
+        );
+        assert_eq!(
+            distribute_flex(90.0, &[(1.0, 70.0, INF), (1.0, 0.0, INF)]),
+            vec![70.0, 20.0]
+        );
+        assert_eq!(
+            distribute_flex(10.0, &[(1.0, 0.0, 2.0), (1.0, 0.0, 3.0)]),
+            vec![2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn spacing_is_taken_before_sharing_the_room() {
+        let mut row = row(
+            vec![
+                sized(20.0, 10.0),
+                flexible(sized(0.0, 10.0), 1, (0.0, f32::INFINITY)),
+                flexible(sized(0.0, 10.0), 3, (0.0, f32::INFINITY)),
+            ],
+            10.0,
+        );
+        let size = row.layout(BoxConstraints::tight_for(120.0, 10.0));
+        assert_eq!(size, Size { w: 120.0, h: 10.0 });
+        assert_eq!(
+            main_sizes(&row),
+            vec![(0.0, 20.0), (30.0, 20.0), (60.0, 60.0)]
+        );
+    }
+
+    #[test]
+    fn flexible_children_stay_within_their_extents() {
+        let mut row = row(
+            vec![
+                flexible(sized(0.0, 10.0), 1, (0.0, 25.0)),
+                flexible(sized(0.0, 10.0), 1, (40.0, f32::INFINITY)),
+                flexible(sized(0.0, 10.0), 1, (0.0, f32::INFINITY)),
+            ],
+            0.0,
+        );
+        row.layout(BoxConstraints::tight_for(100.0, 10.0));
+        assert_eq!(
+            main_sizes(&row),
+            vec![(0.0, 25.0), (25.0, 40.0), (65.0, 35.0)]
+        );
+    }
+
+    #[test]
+    fn alignment_spreads_the_free_room() {
+        let mut row = RenderFlex::new(vec![sized(10.0, 10.0), sized(10.0, 10.0)], Axis::Horizontal)
+            .main_axis_alignment(MainAxisAlignment::SpaceBetween)
+            .spacing(5.0);
+        row.layout(BoxConstraints::tight_for(100.0, 10.0));
+        assert_eq!(main_sizes(&row), vec![(0.0, 10.0), (90.0, 10.0)]);
+        let mut row = RenderFlex::new(vec![sized(10.0, 10.0), sized(10.0, 10.0)], Axis::Horizontal)
+            .main_axis_size(MainAxisSize::Min)
+            .text_direction(TextDirection::Rtl)
+            .spacing(5.0);
+        let size = row.layout(BoxConstraints::loose(Size { w: 100.0, h: 10.0 }));
+        assert_eq!(size.w, 25.0);
+        assert_eq!(main_sizes(&row), vec![(15.0, 10.0), (0.0, 10.0)]);
+    }
+}
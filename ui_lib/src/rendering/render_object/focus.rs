@@ -0,0 +1,280 @@
+use std::{cell::Cell, collections::HashMap};
+
+use crate::{
+    inputs::{FocusEntry, FocusNode, KeyEvent, KeyEventResult},
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Color, Position, Rect, Size},
+};
+
+use super::{KeyCallback, LayoutCache, RenderObject, RenderObjectProp};
+
+// Width of the focus ring, drawn around the focused object
+const FOCUS_RING_WIDTH: f32 = 2.0;
+
+pub struct FocusRenderObject {
+    child: Box<dyn RenderObject>,
+    node: FocusNode,
+    on_key: Option<KeyCallback>,
+    focus_ring: Option<Color>,
+    // whether the ring was drawn last time
+    painted_focus: Cell<bool>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+    render_size: Size,
+}
+
+impl FocusRenderObject {
+    pub fn new(
+        child: Box<dyn RenderObject>,
+        node: FocusNode,
+        on_key: Option<KeyCallback>,
+        focus_ring: Option<Color>,
+    ) -> Self {
+        Self {
+            child,
+            node,
+            on_key,
+            focus_ring,
+            painted_focus: Cell::new(false),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+            render_size: Size::default(),
+        }
+    }
+}
+
+impl RenderObject for FocusRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn contains_focus(&self) -> bool {
+        self.node.has_focus() || self.child.contains_focus()
+    }
+
+    fn handle_key_event(&mut self, event: &KeyEvent) -> KeyEventResult {
+        if !self.contains_focus() {
+            return KeyEventResult::Ignored;
+        }
+        match self.child.handle_key_event(event) {
+            KeyEventResult::Ignored if self.node.has_focus() => match self.on_key {
+                Some(ref on_key) if on_key(event) => KeyEventResult::Handled,
+                _ => KeyEventResult::Ignored,
+            },
+            result => result,
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let rect = Rect::from_pos_size(context_mut.position, self.render_size);
+        if self.node.can_request_focus() {
+            context.focusables.push(FocusEntry {
+                node: self.node.clone(),
+                scope: context_mut.focus_scope,
+                rect: context_mut.transform.apply_rect(rect),
+            });
+        }
+        self.child.paint(context, context_mut.clone());
+        let focused = self.node.has_focus();
+        self.painted_focus.set(focused);
+        if let Some(color) = self.focus_ring {
+            if focused {
+                context.display_list.draw_outline(
+                    rect.inflate(FOCUS_RING_WIDTH),
+                    FOCUS_RING_WIDTH,
+                    color.with_opacity(context_mut.opacity),
+                    context_mut.elevation,
+                );
+            }
+        }
+    }
+
+    fn paint_bounds(&self, rect: Rect) -> Rect {
+        match self.focus_ring {
+            Some(_) => rect.inflate(FOCUS_RING_WIDTH),
+            None => rect,
+        }
+    }
+
+    fn paint_changed(&self) -> bool {
+        self.focus_ring.is_some() && self.node.has_focus() != self.painted_focus.get()
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.render_size = self.child.layout(constraints);
+        self.render_size
+    }
+}
+
+pub struct FocusScopeRenderObject {
+    child: Box<dyn RenderObject>,
+    node: FocusNode,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+    render_size: Size,
+}
+
+impl FocusScopeRenderObject {
+    pub fn new(child: Box<dyn RenderObject>, node: FocusNode) -> Self {
+        Self {
+            child,
+            node,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+            render_size: Size::default(),
+        }
+    }
+}
+
+impl RenderObject for FocusScopeRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn contains_focus(&self) -> bool {
+        self.node.has_focus() || self.child.contains_focus()
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        if self.node.can_request_focus() {
+            context.focusables.push(FocusEntry {
+                node: self.node.clone(),
+                scope: context_mut.focus_scope,
+                rect: context_mut
+                    .transform
+                    .apply_rect(Rect::from_pos_size(context_mut.position, self.render_size)),
+            });
+        }
+        let context_mut = RenderContextMut {
+            focus_scope: self.node.id(),
+            ..context_mut
+        };
+        self.child.paint(context, context_mut);
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.render_size = self.child.layout(constraints);
+        self.render_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::{render_object::ConstrainedRenderObject, DamageRegion, DisplayList};
+
+    fn paint(ro: &dyn RenderObject) -> Vec<FocusEntry> {
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        let context_mut = RenderContextMut {
+            position: Position { x: 10.0, y: 10.0 },
+            ..RenderContextMut::default()
+        };
+        ro.paint(&mut context, context_mut);
+        context.focusables
+    }
+
+    fn damage(ro: &mut dyn RenderObject) -> Vec<Rect> {
+        let mut damage = DamageRegion::default();
+        ro.collect_damage(&mut damage);
+        damage.take(Size { w: 100.0, h: 100.0 })
+    }
+
+    #[test]
+    fn focus_changes_repaint_the_ring() {
+        let node = FocusNode::new();
+        let child = ConstrainedRenderObject::new(None, BoxConstraints::tight_for(20.0, 10.0));
+        let mut ro =
+            FocusRenderObject::new(Box::new(child), node.clone(), None, Some(Color::BLACK));
+        ro.layout(BoxConstraints::default());
+        let focusables = paint(&ro);
+        assert_eq!(focusables.len(), 1);
+        assert_eq!(focusables[0].rect, Rect::new(10.0, 10.0, 20.0, 10.0));
+        damage(&mut ro);
+        assert!(damage(&mut ro).is_empty());
+
+        node.request_focus();
+        assert!(ro.paint_changed());
+        assert_eq!(damage(&mut ro), vec![Rect::new(8.0, 8.0, 24.0, 14.0)]);
+        paint(&ro);
+        assert!(damage(&mut ro).is_empty());
+        node.unfocus();
+        assert_eq!(damage(&mut ro), vec![Rect::new(8.0, 8.0, 24.0, 14.0)]);
+    }
+
+    #[test]
+    fn objects_without_a_ring_ignore_focus_changes() {
+        let node = FocusNode::new();
+        let child = ConstrainedRenderObject::new(None, BoxConstraints::tight_for(20.0, 10.0));
+        let mut ro = FocusRenderObject::new(Box::new(child), node.clone(), None, None);
+        ro.layout(BoxConstraints::default());
+        paint(&ro);
+        damage(&mut ro);
+        node.request_focus();
+        assert!(damage(&mut ro).is_empty());
+    }
+}
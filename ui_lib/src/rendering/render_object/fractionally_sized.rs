@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{Alignment, BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct FractionallySizedRenderObject {
+    child: Option<Box<dyn RenderObject>>,
+    // the child keeps the incoming constraints on the axes without a factor
+    width_factor: Option<f32>,
+    height_factor: Option<f32>,
+    alignment: Alignment,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl FractionallySizedRenderObject {
+    pub fn new(
+        child: Option<Box<dyn RenderObject>>,
+        width_factor: Option<f32>,
+        height_factor: Option<f32>,
+        alignment: Alignment,
+    ) -> Self {
+        Self {
+            child,
+            width_factor,
+            height_factor,
+            alignment,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    fn inner_constraints(&self, constraints: &BoxConstraints) -> BoxConstraints {
+        let mut inner = constraints.clone();
+        if let Some(f) = self.width_factor {
+            let w = constraints.max_width * f;
+            inner.min_width = w;
+            inner.max_width = w;
+        }
+        if let Some(f) = self.height_factor {
+            let h = constraints.max_height * f;
+            inner.min_height = h;
+            inner.max_height = h;
+        }
+        inner
+    }
+}
+
+impl RenderObject for FractionallySizedRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(ref c) = self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(ref mut c) = self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_width(height));
+        child / self.width_factor.unwrap_or(1.0)
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_width(height));
+        child / self.width_factor.unwrap_or(1.0)
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_height(width));
+        child / self.height_factor.unwrap_or(1.0)
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_height(width));
+        child / self.height_factor.unwrap_or(1.0)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        if let Some(ref c) = self.child {
+            c.paint(context, context_mut.update(self));
+        }
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let inner = self.inner_constraints(&constraints);
+        let c = match self.child {
+            Some(ref mut c) => c,
+            None => return constraints.constrain(inner.constrain(Size::default())),
+        };
+        let child_size = c.layout(inner);
+        let size = constraints.constrain(child_size);
+        let (px, py) = self.alignment.loc();
+        c.set_render_pos(Position {
+            x: (size.w - child_size.w) * (px + 1.0) / 2.0,
+            y: (size.h - child_size.h) * (py + 1.0) / 2.0,
+        });
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_object::ConstrainedRenderObject;
+
+    fn child(w: f32, h: f32) -> Option<Box<dyn RenderObject>> {
+        Some(Box::new(ConstrainedRenderObject::new(
+            None,
+            BoxConstraints {
+                min_width: 0.0,
+                max_width: w,
+                min_height: 0.0,
+                max_height: h,
+            },
+        )))
+    }
+
+    #[test]
+    fn child_gets_a_fraction_of_the_room() {
+        let mut ro = FractionallySizedRenderObject::new(
+            child(f32::INFINITY, f32::INFINITY),
+            Some(0.5),
+            Some(0.25),
+            Alignment::Center,
+        );
+        let size = ro.layout(BoxConstraints::tight_for(200.0, 100.0));
+        assert_eq!(size, Size { w: 200.0, h: 100.0 });
+        let mut child_pos = Position::default();
+        ro.visit_children(&mut |c| child_pos = c.get_render_pos());
+        assert_eq!(child_pos, Position { x: 50.0, y: 37.5 });
+    }
+
+    #[test]
+    fn axes_without_a_factor_keep_the_constraints() {
+        let mut ro = FractionallySizedRenderObject::new(
+            child(30.0, 30.0),
+            Some(0.5),
+            None,
+            Alignment::TopLeft,
+        );
+        let size = ro.layout(BoxConstraints::loose(Size { w: 200.0, h: 100.0 }));
+        assert_eq!(size, Size { w: 100.0, h: 0.0 });
+    }
+}
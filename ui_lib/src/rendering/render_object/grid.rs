@@ -0,0 +1,632 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size, TrackSize},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct RenderGrid {
+    children: Vec<Box<dyn RenderObject>>,
+    columns: Vec<TrackSize>,
+    rows: Vec<TrackSize>,
+    column_gap: f32,
+    row_gap: f32,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+// Cell area of a child, set by `GridCell` through props
+#[derive(Clone, Copy)]
+struct Placement {
+    column: usize,
+    row: usize,
+    column_span: usize,
+    row_span: usize,
+    // stretched to fill the area when None
+    alignment: Option<(f32, f32)>,
+}
+
+// What a child spanning `span` tracks from `start` needs
+struct TrackItem {
+    start: usize,
+    span: usize,
+    content: f32,
+}
+
+fn finite_or_zero(v: f32) -> f32 {
+    if v.is_finite() {
+        v
+    } else {
+        0.0
+    }
+}
+
+// Whether the size of one of the tracks crossed depends on the content
+fn spans_content(tracks: &[TrackSize], start: usize, span: usize, bounded: bool) -> bool {
+    tracks[start..start + span].iter().any(|t| match t {
+        TrackSize::Auto | TrackSize::MinMax(..) => true,
+        TrackSize::Fr(_) => !bounded,
+        TrackSize::Px(_) => false,
+    })
+}
+
+fn size_tracks(tracks: &[TrackSize], items: &[TrackItem], available: f32, gap: f32) -> Vec<f32> {
+    let bounded = available.is_finite();
+    let mut content = vec![0f32; tracks.len()];
+    for item in items.iter().filter(|i| i.span == 1) {
+        content[item.start] = content[item.start].max(item.content);
+    }
+    let mut sizes: Vec<f32> = tracks
+        .iter()
+        .zip(&content)
+        .map(|(t, c)| t.resolve(*c))
+        .collect();
+
+    // children spanning several tracks grow the content-sized ones they cross
+    for item in items.iter().filter(|i| i.span > 1) {
+        let range = item.start..item.start + item.span;
+        let current = sizes[range.clone()].iter().sum::<f32>() + gap * (item.span - 1) as f32;
+        let growable: Vec<usize> = range
+            .filter(|i| spans_content(tracks, *i, 1, bounded))
+            .collect();
+        if item.content > current && !growable.is_empty() {
+            let extra = (item.content - current) / growable.len() as f32;
+            for i in growable {
+                sizes[i] = match tracks[i] {
+                    TrackSize::MinMax(_, max) => (sizes[i] + extra).min(max),
+                    _ => sizes[i] + extra,
+                };
+            }
+        }
+    }
+
+    let total_fr: f32 = tracks
+        .iter()
+        .map(|t| match t {
+            TrackSize::Fr(f) => *f,
+            _ => 0.0,
+        })
+        .sum();
+    if bounded && total_fr > 0.0 {
+        let used: f32 = tracks
+            .iter()
+            .zip(&sizes)
+            .filter(|(t, _)| !t.is_flexible())
+            .map(|(_, s)| *s)
+            .sum::<f32>()
+            + gap * tracks.len().saturating_sub(1) as f32;
+        let free = (available - used).max(0.0);
+        for (t, s) in tracks.iter().zip(sizes.iter_mut()) {
+            if let TrackSize::Fr(f) = t {
+                *s = free * f / total_fr;
+            }
+        }
+    }
+    sizes
+}
+
+// Start of each track, and the total extent
+fn track_offsets(sizes: &[f32], gap: f32) -> (Vec<f32>, f32) {
+    let mut offsets = Vec::with_capacity(sizes.len());
+    let mut pos = 0f32;
+    for (i, s) in sizes.iter().enumerate() {
+        if i != 0 {
+            pos += gap;
+        }
+        offsets.push(pos);
+        pos += s;
+    }
+    (offsets, pos)
+}
+
+fn span_extent(offsets: &[f32], sizes: &[f32], start: usize, span: usize) -> f32 {
+    let last = start + span - 1;
+    offsets[last] + sizes[last] - offsets[start]
+}
+
+impl RenderGrid {
+    pub fn new(
+        children: Vec<Box<dyn RenderObject>>,
+        columns: Vec<TrackSize>,
+        rows: Vec<TrackSize>,
+        column_gap: f32,
+        row_gap: f32,
+    ) -> Self {
+        Self {
+            children,
+            columns,
+            rows,
+            column_gap,
+            row_gap,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    // Children without a cell take the first free one, in reading order, after the ones
+    // placed in a given column or row, which take the first free cell along it. Tracks are
+    // added as `Auto` when children are placed past the defined ones.
+    fn place_children(&self) -> (Vec<Placement>, usize, usize) {
+        let requested: Vec<(Placement, Option<usize>, Option<usize>)> = self
+            .children
+            .iter()
+            .map(|c| {
+                let column = c.get_prop_usize("grid_column");
+                let row = c.get_prop_usize("grid_row");
+                let placement = Placement {
+                    column: column.unwrap_or(0),
+                    row: row.unwrap_or(0),
+                    column_span: c.get_prop_usize("grid_column_span").unwrap_or(1).max(1),
+                    row_span: c.get_prop_usize("grid_row_span").unwrap_or(1).max(1),
+                    alignment: c
+                        .get_prop_f32("grid_align_x")
+                        .zip(c.get_prop_f32("grid_align_y")),
+                };
+                (placement, column, row)
+            })
+            .collect();
+        let columns = requested
+            .iter()
+            .filter(|(_, column, _)| column.is_some())
+            .map(|(p, _, _)| p.column + p.column_span)
+            .fold(self.columns.len().max(1), usize::max);
+
+        let mut occupied: Vec<Vec<bool>> = Vec::new();
+        let occupy = |occupied: &mut Vec<Vec<bool>>, p: &Placement| {
+            while occupied.len() < p.row + p.row_span {
+                occupied.push(vec![false; columns]);
+            }
+            for row in &mut occupied[p.row..p.row + p.row_span] {
+                row[p.column..p.column + p.column_span].fill(true);
+            }
+        };
+        let is_free = |occupied: &Vec<Vec<bool>>, p: &Placement| {
+            (p.row..p.row + p.row_span).all(|r| {
+                r >= occupied.len()
+                    || occupied[r][p.column..p.column + p.column_span]
+                        .iter()
+                        .all(|o| !o)
+            })
+        };
+
+        let mut placements: Vec<Option<Placement>> = vec![None; requested.len()];
+        for (i, (p, column, row)) in requested.iter().enumerate() {
+            if column.is_some() && row.is_some() {
+                occupy(&mut occupied, p);
+                placements[i] = Some(*p);
+            }
+        }
+        for (i, (requested, column, row)) in requested.iter().enumerate() {
+            let p = match (column, row) {
+                (Some(_), None) => {
+                    let mut p = *requested;
+                    while !is_free(&occupied, &p) {
+                        p.row += 1;
+                    }
+                    p
+                }
+                (None, Some(_)) => {
+                    let column_span = requested.column_span.min(columns);
+                    let free = (0..=columns - column_span).find(|c| {
+                        is_free(
+                            &occupied,
+                            &Placement {
+                                column: *c,
+                                column_span,
+                                ..*requested
+                            },
+                        )
+                    });
+                    // overlaps the start of the row when it is full
+                    Placement {
+                        column: free.unwrap_or(0),
+                        column_span,
+                        ..*requested
+                    }
+                }
+                _ => continue,
+            };
+            occupy(&mut occupied, &p);
+            placements[i] = Some(p);
+        }
+        let mut cursor = (0, 0);
+        for (requested, placement) in requested.iter().map(|r| r.0).zip(&mut placements) {
+            if placement.is_some() {
+                continue;
+            }
+            let mut p = Placement {
+                column: cursor.1,
+                row: cursor.0,
+                column_span: requested.column_span.min(columns),
+                ..requested
+            };
+            while p.column + p.column_span > columns || !is_free(&occupied, &p) {
+                p.column += 1;
+                if p.column + p.column_span > columns {
+                    p.column = 0;
+                    p.row += 1;
+                }
+            }
+            cursor = (p.row, p.column + p.column_span);
+            occupy(&mut occupied, &p);
+            *placement = Some(p);
+        }
+        let rows = occupied.len().max(self.rows.len());
+        (placements.into_iter().flatten().collect(), columns, rows)
+    }
+
+    // Where the children go, with the defined tracks completed by `Auto` ones
+    fn tracks(&self) -> (Vec<Placement>, Vec<TrackSize>, Vec<TrackSize>) {
+        let (placements, column_count, row_count) = self.place_children();
+        let mut columns = self.columns.clone();
+        columns.resize(column_count, TrackSize::Auto);
+        let mut rows = self.rows.clone();
+        rows.resize(row_count, TrackSize::Auto);
+        (placements, columns, rows)
+    }
+
+    // Only the children in content-sized tracks are measured, with `measure` given an
+    // infinite height, the others get the size of their cells
+    fn column_sizes(
+        &self,
+        placements: &[Placement],
+        columns: &[TrackSize],
+        available: f32,
+        measure: fn(&dyn RenderObject, f32) -> f32,
+    ) -> Vec<f32> {
+        let bounded = available.is_finite();
+        let items: Vec<TrackItem> = self
+            .children
+            .iter()
+            .zip(placements)
+            .filter(|(_, p)| spans_content(columns, p.column, p.column_span, bounded))
+            .map(|(c, p)| TrackItem {
+                start: p.column,
+                span: p.column_span,
+                content: finite_or_zero(measure(c.as_ref(), f32::INFINITY)),
+            })
+            .collect();
+        size_tracks(columns, &items, available, self.column_gap)
+    }
+
+    // Like `column_sizes`, `measure` being given the width of the cells
+    fn row_sizes(
+        &self,
+        placements: &[Placement],
+        rows: &[TrackSize],
+        column_sizes: &[f32],
+        available: f32,
+        measure: fn(&dyn RenderObject, f32) -> f32,
+    ) -> Vec<f32> {
+        let (column_offsets, _) = track_offsets(column_sizes, self.column_gap);
+        let bounded = available.is_finite();
+        let items: Vec<TrackItem> = self
+            .children
+            .iter()
+            .zip(placements)
+            .filter(|(_, p)| spans_content(rows, p.row, p.row_span, bounded))
+            .map(|(c, p)| {
+                let w = span_extent(&column_offsets, column_sizes, p.column, p.column_span);
+                TrackItem {
+                    start: p.row,
+                    span: p.row_span,
+                    content: finite_or_zero(measure(c.as_ref(), w)),
+                }
+            })
+            .collect();
+        size_tracks(rows, &items, available, self.row_gap)
+    }
+
+    fn intrinsic_width(&self, measure: fn(&dyn RenderObject, f32) -> f32) -> f32 {
+        let (placements, columns, _) = self.tracks();
+        let sizes = self.column_sizes(&placements, &columns, f32::INFINITY, measure);
+        track_offsets(&sizes, self.column_gap).1
+    }
+
+    // Columns are sized for `width` like in the layout
+    fn intrinsic_height(&self, width: f32, measure: fn(&dyn RenderObject, f32) -> f32) -> f32 {
+        let (placements, columns, rows) = self.tracks();
+        let column_sizes = self.column_sizes(&placements, &columns, width, |c, h| {
+            c.max_intrinsic_width(h)
+        });
+        let sizes = self.row_sizes(&placements, &rows, &column_sizes, f32::INFINITY, measure);
+        track_offsets(&sizes, self.row_gap).1
+    }
+}
+
+impl RenderObject for RenderGrid {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in &self.children {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in &mut self.children {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, _height: f32) -> f32 {
+        self.intrinsic_width(|c, h| c.min_intrinsic_width(h))
+    }
+
+    fn max_intrinsic_width(&self, _height: f32) -> f32 {
+        self.intrinsic_width(|c, h| c.max_intrinsic_width(h))
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.intrinsic_height(width, |c, w| c.min_intrinsic_height(w))
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.intrinsic_height(width, |c, w| c.max_intrinsic_height(w))
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        for c in &self.children {
+            c.paint(context, context_mut.update(self));
+        }
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let (placements, columns, rows) = self.tracks();
+        let bounded_width = constraints.max_width.is_finite();
+        let bounded_height = constraints.max_height.is_finite();
+        let column_sizes =
+            self.column_sizes(&placements, &columns, constraints.max_width, |c, h| {
+                c.max_intrinsic_width(h)
+            });
+        let (column_offsets, width) = track_offsets(&column_sizes, self.column_gap);
+        let row_sizes = self.row_sizes(
+            &placements,
+            &rows,
+            &column_sizes,
+            constraints.max_height,
+            |c, w| c.max_intrinsic_height(w),
+        );
+        let (row_offsets, height) = track_offsets(&row_sizes, self.row_gap);
+
+        for (c, p) in self.children.iter_mut().zip(&placements) {
+            let cell = Size {
+                w: span_extent(&column_offsets, &column_sizes, p.column, p.column_span),
+                h: span_extent(&row_offsets, &row_sizes, p.row, p.row_span),
+            };
+            let origin = Position {
+                x: column_offsets[p.column],
+                y: row_offsets[p.row],
+            };
+            let pos = match p.alignment {
+                None => {
+                    // content-sized cells depend on the intrinsic size of the child
+                    let measured = spans_content(&columns, p.column, p.column_span, bounded_width)
+                        || spans_content(&rows, p.row, p.row_span, bounded_height);
+                    c.layout_with(BoxConstraints::tight(cell), measured);
+                    origin
+                }
+                Some((ax, ay)) => {
+                    let size = c.layout(BoxConstraints::loose(cell));
+                    origin
+                        + Position {
+                            x: (cell.w - size.w) * (ax + 1.0) / 2.0,
+                            y: (cell.h - size.h) * (ay + 1.0) / 2.0,
+                        }
+                }
+            };
+            c.set_render_pos(pos);
+        }
+
+        constraints.constrain(Size {
+            w: width,
+            h: height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::{rendering::render_object::RenderFlex, types::Axis};
+
+    // Wants to be `size` big and counts its layouts
+    struct Probe {
+        size: Size,
+        layouts: Rc<Cell<usize>>,
+        props: HashMap<String, RenderObjectProp>,
+        render_pos: Position,
+    }
+
+    impl RenderObject for Probe {
+        fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+            self.layouts.set(self.layouts.get() + 1);
+            constraints.constrain(self.size)
+        }
+        fn min_intrinsic_width(&self, _height: f32) -> f32 {
+            self.size.w
+        }
+        fn max_intrinsic_width(&self, _height: f32) -> f32 {
+            self.size.w
+        }
+        fn min_intrinsic_height(&self, _width: f32) -> f32 {
+            self.size.h
+        }
+        fn max_intrinsic_height(&self, _width: f32) -> f32 {
+            self.size.h
+        }
+        fn set_render_pos(&mut self, render_pos: Position) {
+            self.render_pos = render_pos;
+        }
+        fn get_render_pos(&self) -> Position {
+            self.render_pos
+        }
+        fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+            self.props.get(prop)
+        }
+        fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+            self.props.insert(prop.to_string(), val);
+        }
+    }
+
+    fn probe(w: f32, h: f32, layouts: &Rc<Cell<usize>>) -> Box<dyn RenderObject> {
+        Box::new(Probe {
+            size: Size { w, h },
+            layouts: layouts.clone(),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+        })
+    }
+
+    fn positions(grid: &RenderGrid) -> Vec<(f32, f32)> {
+        let mut positions = Vec::new();
+        grid.visit_children(&mut |c| {
+            let pos = c.get_render_pos();
+            positions.push((pos.x, pos.y));
+        });
+        positions
+    }
+
+    #[test]
+    fn tracks_are_fixed_content_sized_or_shared() {
+        let layouts = Rc::new(Cell::new(0));
+        let mut grid = RenderGrid::new(
+            vec![
+                probe(30.0, 10.0, &layouts),
+                probe(50.0, 25.0, &layouts),
+                probe(10.0, 10.0, &layouts),
+                probe(500.0, 10.0, &layouts),
+                probe(10.0, 40.0, &layouts),
+            ],
+            vec![
+                TrackSize::Px(20.0),
+                TrackSize::Auto,
+                TrackSize::Fr(1.0),
+                TrackSize::Fr(3.0),
+            ],
+            vec![TrackSize::MinMax(0.0, 20.0)],
+            10.0,
+            5.0,
+        );
+        let size = grid.layout(BoxConstraints::loose(Size { w: 200.0, h: 200.0 }));
+        // 20 + 50 + 25 + 75 and the gaps
+        assert_eq!(size, Size { w: 200.0, h: 65.0 });
+        assert_eq!(
+            positions(&grid),
+            vec![
+                (0.0, 0.0),
+                (30.0, 0.0),
+                (90.0, 0.0),
+                (125.0, 0.0),
+                (0.0, 25.0)
+            ]
+        );
+        // every child is laid out once, with the size of its cell
+        assert_eq!(layouts.get(), 5);
+    }
+
+    #[test]
+    fn spanning_children_grow_the_auto_tracks_they_cross() {
+        let layouts = Rc::new(Cell::new(0));
+        let mut wide = probe(100.0, 10.0, &layouts);
+        wide.set_prop_usize("grid_column_span", 2);
+        let mut grid = RenderGrid::new(
+            vec![probe(20.0, 10.0, &layouts), wide],
+            vec![TrackSize::Auto, TrackSize::Px(30.0)],
+            vec![],
+            10.0,
+            0.0,
+        );
+        let size = grid.layout(BoxConstraints::default());
+        assert_eq!(size, Size { w: 100.0, h: 20.0 });
+    }
+
+    #[test]
+    fn cells_given_a_single_axis_keep_it() {
+        let layouts = Rc::new(Cell::new(0));
+        let mut in_column = probe(10.0, 10.0, &layouts);
+        in_column.set_prop_usize("grid_column", 1);
+        let mut in_row = probe(10.0, 10.0, &layouts);
+        in_row.set_prop_usize("grid_row", 2);
+        let mut explicit = probe(10.0, 10.0, &layouts);
+        explicit.set_prop_usize("grid_column", 1);
+        explicit.set_prop_usize("grid_row", 0);
+        let mut grid = RenderGrid::new(
+            vec![in_column, in_row, probe(10.0, 10.0, &layouts), explicit],
+            vec![TrackSize::Px(10.0), TrackSize::Px(10.0)],
+            vec![],
+            0.0,
+            0.0,
+        );
+        grid.layout(BoxConstraints::default());
+        assert_eq!(
+            positions(&grid),
+            vec![(10.0, 10.0), (0.0, 20.0), (0.0, 0.0), (10.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn intrinsic_sizes_treat_shares_as_content_sized() {
+        let layouts = Rc::new(Cell::new(0));
+        let grid = RenderGrid::new(
+            vec![
+                probe(30.0, 10.0, &layouts),
+                probe(50.0, 25.0, &layouts),
+                probe(10.0, 40.0, &layouts),
+            ],
+            vec![TrackSize::Px(20.0), TrackSize::Fr(1.0)],
+            vec![TrackSize::Px(15.0)],
+            10.0,
+            5.0,
+        );
+        assert_eq!(grid.min_intrinsic_width(f32::INFINITY), 80.0);
+        assert_eq!(grid.max_intrinsic_width(f32::INFINITY), 80.0);
+        assert_eq!(grid.max_intrinsic_height(100.0), 60.0);
+        assert_eq!(layouts.get(), 0);
+    }
+
+    #[test]
+    fn expanded_children_in_fixed_cells_get_bounded_constraints() {
+        let mut expanded = RenderFlex::new(vec![], Axis::Horizontal);
+        expanded.set_prop_i32("flex", 1);
+        let row = RenderFlex::new(vec![Box::new(expanded)], Axis::Horizontal);
+        let mut grid = RenderGrid::new(
+            vec![Box::new(row)],
+            vec![TrackSize::Px(40.0)],
+            vec![TrackSize::Px(30.0)],
+            0.0,
+            0.0,
+        );
+        let size = grid.layout(BoxConstraints::default());
+        assert_eq!(size, Size { w: 40.0, h: 30.0 });
+    }
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    rendering::{FilterQuality, Image, RenderContext, RenderContextMut},
+    types::{Alignment, BoxConstraints, BoxFit, Position, Rect, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct ImageRenderObject {
+    // nothing is drawn without one, e.g. when it couldn't be loaded
+    image: Option<Arc<Image>>,
+    // the size of the image when not given
+    width: Option<f32>,
+    height: Option<f32>,
+    fit: BoxFit,
+    alignment: Alignment,
+    filter: FilterQuality,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl ImageRenderObject {
+    pub fn new(
+        image: Option<Arc<Image>>,
+        width: Option<f32>,
+        height: Option<f32>,
+        fit: BoxFit,
+        alignment: Alignment,
+        filter: FilterQuality,
+    ) -> Self {
+        Self {
+            image,
+            width,
+            height,
+            fit,
+            alignment,
+            filter,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    fn image_size(&self) -> Size {
+        self.image.as_ref().map_or(Size::default(), |i| Size {
+            w: i.width as f32,
+            h: i.height as f32,
+        })
+    }
+
+    // The given width and height, the missing one keeping the aspect ratio of the image
+    fn preferred_size(&self) -> Size {
+        let image = self.image_size();
+        let ratio = |a: f32, b: f32| if b > 0.0 { a / b } else { 1.0 };
+        match (self.width, self.height) {
+            (Some(w), Some(h)) => Size { w, h },
+            (Some(w), None) => Size {
+                w,
+                h: w * ratio(image.h, image.w),
+            },
+            (None, Some(h)) => Size {
+                w: h * ratio(image.w, image.h),
+                h,
+            },
+            (None, None) => image,
+        }
+    }
+
+    fn size_for(&self, constraints: BoxConstraints) -> Size {
+        let size = self.preferred_size();
+        if size.w <= 0.0 || size.h <= 0.0 {
+            return constraints.constrain(size);
+        }
+        constraints.constrain_size_and_attempt_to_preserve_aspect_ratio(size)
+    }
+}
+
+impl RenderObject for ImageRenderObject {
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.max_intrinsic_width(height)
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        self.size_for(BoxConstraints::tight_for_height(height)).w
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.max_intrinsic_height(width)
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.size_for(BoxConstraints::tight_for_width(width)).h
+    }
+
+    // The image is fit in the box then aligned in it, and cut where it goes past it
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let Some(image) = &self.image else {
+            return;
+        };
+        let size = self.layout_cache.size();
+        let drawn = self.fit.apply(self.image_size(), size);
+        if drawn.w < 1.0 || drawn.h < 1.0 || context_mut.opacity <= 0.0 {
+            return;
+        }
+        let (px, py) = self.alignment.loc();
+        let pos = context_mut.position
+            + Position {
+                x: (size.w - drawn.w) * (px + 1.0) / 2.0,
+                y: (size.h - drawn.h) * (py + 1.0) / 2.0,
+            };
+        let list = &mut *context.display_list;
+        let clipped = drawn.w > size.w || drawn.h > size.h;
+        if clipped {
+            list.push_clip(Rect::from_pos_size(context_mut.position, size));
+        }
+        list.draw_image(
+            pos.x.round() as isize,
+            pos.y.round() as isize,
+            drawn.w.round() as usize,
+            drawn.h.round() as usize,
+            image.clone(),
+            self.filter,
+            context_mut.elevation,
+        );
+        if clipped {
+            list.pop_clip();
+        }
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.size_for(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rendering::DisplayList, types::Color};
+
+    fn image(w: usize, h: usize) -> Option<Arc<Image>> {
+        Some(Arc::new(Image::new(w, h, vec![Color::BLACK; w * h])))
+    }
+
+    fn paint(ro: &dyn RenderObject) -> String {
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        let context_mut = RenderContextMut {
+            position: Position { x: 10.0, y: 10.0 },
+            ..RenderContextMut::default()
+        };
+        ro.paint(&mut context, context_mut);
+        list.to_text()
+    }
+
+    #[test]
+    fn the_missing_side_keeps_the_aspect_ratio() {
+        let fit = BoxFit::Contain;
+        let filter = FilterQuality::Nearest;
+        let mut ro = ImageRenderObject::new(
+            image(40, 20),
+            Some(60.0),
+            None,
+            fit,
+            Alignment::Center,
+            filter,
+        );
+        assert_eq!(
+            ro.layout(BoxConstraints::default()),
+            Size { w: 60.0, h: 30.0 }
+        );
+        assert_eq!(ro.max_intrinsic_width(10.0), 20.0);
+        let mut ro =
+            ImageRenderObject::new(image(40, 20), None, None, fit, Alignment::Center, filter);
+        assert_eq!(
+            ro.layout(BoxConstraints::loose(Size { w: 20.0, h: 100.0 })),
+            Size { w: 20.0, h: 10.0 }
+        );
+        let mut ro = ImageRenderObject::new(None, None, None, fit, Alignment::Center, filter);
+        assert_eq!(ro.layout(BoxConstraints::default()), Size::default());
+        assert_eq!(paint(&ro), "");
+    }
+
+    #[test]
+    fn images_are_aligned_in_the_box_and_cut_past_it() {
+        let filter = FilterQuality::Nearest;
+        let mut contained = ImageRenderObject::new(
+            image(40, 20),
+            Some(40.0),
+            Some(40.0),
+            BoxFit::Contain,
+            Alignment::BottomRight,
+            filter,
+        );
+        contained.layout(BoxConstraints::default());
+        assert_eq!(
+            paint(&contained),
+            "image 10 30 40x20 from 40x20 Nearest z=0\n"
+        );
+        let mut covering = ImageRenderObject::new(
+            image(40, 20),
+            Some(40.0),
+            Some(40.0),
+            BoxFit::Cover,
+            Alignment::Center,
+            filter,
+        );
+        covering.layout(BoxConstraints::default());
+        assert_eq!(
+            paint(&covering),
+            "push_clip 10 10 40x40\n\
+             \x20 image -10 10 80x40 from 40x20 Nearest z=0\n\
+             pop_clip\n"
+        );
+    }
+}
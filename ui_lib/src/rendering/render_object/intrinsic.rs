@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+// Rounds `size` up to a multiple of `step`, when there is one
+fn apply_step(size: f32, step: Option<f32>) -> f32 {
+    match step {
+        Some(step) if step > 0.0 && size.is_finite() => (size / step).ceil() * step,
+        _ => size,
+    }
+}
+
+// Sizes its child to the child's max intrinsic width
+pub struct IntrinsicWidthRenderObject {
+    child: Box<dyn RenderObject>,
+    step_width: Option<f32>,
+    step_height: Option<f32>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl IntrinsicWidthRenderObject {
+    pub fn new(
+        child: Box<dyn RenderObject>,
+        step_width: Option<f32>,
+        step_height: Option<f32>,
+    ) -> Self {
+        Self {
+            child,
+            step_width,
+            step_height,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for IntrinsicWidthRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.max_intrinsic_width(height)
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        apply_step(self.child.max_intrinsic_width(height), self.step_width)
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        let width = width.min(self.max_intrinsic_width(f32::INFINITY));
+        apply_step(self.child.min_intrinsic_height(width), self.step_height)
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        let width = width.min(self.max_intrinsic_width(f32::INFINITY));
+        apply_step(self.child.max_intrinsic_height(width), self.step_height)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.child.paint(context, context_mut.update(self));
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let mut constraints = constraints;
+        let mut uses_intrinsics = false;
+        if !constraints.has_tight_width() {
+            let width = self.max_intrinsic_width(constraints.max_height);
+            constraints = constraints.tighten_width(width);
+            uses_intrinsics = true;
+        }
+        if self.step_height.is_some() {
+            let height = self.max_intrinsic_height(constraints.max_width);
+            constraints = constraints.tighten_height(height);
+            uses_intrinsics = true;
+        }
+        self.child.layout_with(constraints, uses_intrinsics)
+    }
+}
+
+// Sizes its child to the child's max intrinsic height
+pub struct IntrinsicHeightRenderObject {
+    child: Box<dyn RenderObject>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl IntrinsicHeightRenderObject {
+    pub fn new(child: Box<dyn RenderObject>) -> Self {
+        Self {
+            child,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for IntrinsicHeightRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.max_intrinsic_height(width)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.child.paint(context, context_mut.update(self));
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let mut constraints = constraints;
+        let uses_intrinsics = !constraints.has_tight_height();
+        if uses_intrinsics {
+            let height = self.child.max_intrinsic_height(constraints.max_width);
+            constraints = constraints.tighten_height(height);
+        }
+        self.child.layout_with(constraints, uses_intrinsics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rendering::render_object::{ConstrainedRenderObject, RenderWrap},
+        types::{Axis, MainAxisAlignment, TextDirection, VerticalDirection, WrapCrossAlignment},
+    };
+
+    fn sized(w: f32, h: f32) -> Box<dyn RenderObject> {
+        Box::new(ConstrainedRenderObject::new(
+            None,
+            BoxConstraints::tight_for(w, h),
+        ))
+    }
+
+    fn wrap() -> Box<dyn RenderObject> {
+        Box::new(RenderWrap::new(
+            vec![sized(30.0, 10.0), sized(30.0, 20.0), sized(30.0, 10.0)],
+            Axis::Horizontal,
+            MainAxisAlignment::Start,
+            5.0,
+            MainAxisAlignment::Start,
+            5.0,
+            WrapCrossAlignment::Start,
+            TextDirection::Ltr,
+            VerticalDirection::Down,
+        ))
+    }
+
+    #[test]
+    fn intrinsic_width_puts_a_wrap_on_a_single_run() {
+        let mut ro = IntrinsicWidthRenderObject::new(wrap(), None, None);
+        let size = ro.layout(BoxConstraints::loose(Size { w: 500.0, h: 500.0 }));
+        assert_eq!(size, Size { w: 100.0, h: 20.0 });
+    }
+
+    #[test]
+    fn intrinsic_width_rounds_up_to_the_steps() {
+        let mut ro = IntrinsicWidthRenderObject::new(sized(33.0, 12.0), Some(10.0), Some(5.0));
+        assert_eq!(ro.min_intrinsic_width(f32::INFINITY), 40.0);
+        let size = ro.layout(BoxConstraints::loose(Size { w: 500.0, h: 500.0 }));
+        assert_eq!(size, Size { w: 40.0, h: 15.0 });
+        assert_eq!(ro.max_intrinsic_height(f32::INFINITY), 15.0);
+    }
+
+    #[test]
+    fn intrinsic_height_sizes_a_wrap_for_the_given_width() {
+        let mut ro = IntrinsicHeightRenderObject::new(wrap());
+        let size = ro.layout(BoxConstraints::tight_for_width(65.0));
+        assert_eq!(size, Size { w: 65.0, h: 35.0 });
+        assert_eq!(ro.min_intrinsic_height(30.0), 50.0);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+    widgets::Widget,
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub type LayoutWidgetBuilder = Rc<dyn Fn(&BoxConstraints) -> Box<dyn Widget>>;
+
+pub struct LayoutBuilderRenderObject {
+    builder: LayoutWidgetBuilder,
+    child: Option<Box<dyn RenderObject>>,
+    // constraints the child was built for
+    built_for: Option<BoxConstraints>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl LayoutBuilderRenderObject {
+    pub fn new(builder: LayoutWidgetBuilder) -> Self {
+        Self {
+            builder,
+            child: None,
+            built_for: None,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for LayoutBuilderRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(ref c) = self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(ref mut c) = self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    // The child depends on the constraints, there is no child to ask without them
+    fn min_intrinsic_width(&self, _height: f32) -> f32 {
+        0.0
+    }
+
+    fn max_intrinsic_width(&self, _height: f32) -> f32 {
+        0.0
+    }
+
+    fn min_intrinsic_height(&self, _width: f32) -> f32 {
+        0.0
+    }
+
+    fn max_intrinsic_height(&self, _width: f32) -> f32 {
+        0.0
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        if let Some(ref c) = self.child {
+            c.paint(context, context_mut.update(self));
+        }
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        if self.child.is_none() || self.built_for.as_ref() != Some(&constraints) {
+            self.child = Some((self.builder)(&constraints).create_render_object());
+            self.built_for = Some(constraints.clone());
+        }
+        let c = self.child.as_mut().unwrap();
+        let size = constraints.constrain(c.layout(constraints.clone()));
+        c.set_render_pos(Position::default());
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::widgets::SizedBox;
+
+    #[test]
+    fn builds_again_only_for_new_constraints() {
+        let builds = Rc::new(Cell::new(0));
+        let counter = builds.clone();
+        let mut ro = LayoutBuilderRenderObject::new(Rc::new(move |constraints| {
+            counter.set(counter.get() + 1);
+            let width = if constraints.max_width > 100.0 {
+                80.0
+            } else {
+                20.0
+            };
+            Box::new(SizedBox::new(None, width, 10.0))
+        }));
+        let wide = BoxConstraints::loose(Size { w: 200.0, h: 50.0 });
+        assert_eq!(ro.layout(wide.clone()), Size { w: 80.0, h: 10.0 });
+        ro.mark_needs_layout();
+        ro.layout(wide);
+        assert_eq!(builds.get(), 1);
+        let narrow = BoxConstraints::loose(Size { w: 50.0, h: 50.0 });
+        assert_eq!(ro.layout(narrow), Size { w: 20.0, h: 10.0 });
+        assert_eq!(builds.get(), 2);
+    }
+}
@@ -0,0 +1,100 @@
+use std::cell::Cell;
+
+use crate::types::{BoxConstraints, Position, Rect, Size};
+
+// The constraints and size of the last layout of a render object, so it can be skipped
+// while nothing changed, and where it was last painted, so a change only repaints that area
+#[derive(Default)]
+pub struct LayoutCache {
+    constraints: Option<BoxConstraints>,
+    size: Size,
+    dirty: bool,
+    // the parent made the constraints from the intrinsic dimensions or size of the object
+    parent_uses_size: bool,
+    // the object or one below it has to be laid out again by `flush_layout`
+    needs_flush: bool,
+    needs_paint: bool,
+    // origin of the object and area covered with its children
+    painted: Cell<Option<(Position, Rect)>>,
+}
+
+impl LayoutCache {
+    pub fn get(&self, constraints: &BoxConstraints) -> Option<Size> {
+        if self.dirty || self.constraints.as_ref() != Some(constraints) {
+            return None;
+        }
+        Some(self.size)
+    }
+
+    pub fn store(&mut self, constraints: BoxConstraints, size: Size) {
+        self.constraints = Some(constraints);
+        self.size = size;
+        self.dirty = false;
+        self.needs_paint = true;
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn constraints(&self) -> Option<&BoxConstraints> {
+        self.constraints.as_ref()
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn set_parent_uses_size(&mut self, parent_uses_size: bool) {
+        self.parent_uses_size = parent_uses_size;
+    }
+
+    pub fn parent_uses_size(&self) -> bool {
+        self.parent_uses_size
+    }
+
+    // Tight constraints leave a single possible size, so a new layout of the subtree can't
+    // affect the parent, unless the parent made them from what the subtree looks like
+    pub fn is_relayout_boundary(&self) -> bool {
+        !self.parent_uses_size && self.constraints.as_ref().is_some_and(|c| c.is_tight())
+    }
+
+    pub fn set_needs_flush(&mut self, needs_flush: bool) {
+        self.needs_flush = needs_flush;
+    }
+
+    pub fn needs_flush(&self) -> bool {
+        self.needs_flush
+    }
+
+    pub fn mark_needs_paint(&mut self) {
+        self.needs_paint = true;
+    }
+
+    pub fn needs_paint(&self) -> bool {
+        self.needs_paint
+    }
+
+    pub fn clear_needs_paint(&mut self) {
+        self.needs_paint = false;
+    }
+
+    pub fn set_painted(&self, origin: Position, area: Rect) {
+        self.painted.set(Some((origin, area)));
+    }
+
+    pub fn painted_area(&self) -> Option<Rect> {
+        self.painted.get().map(|(_, area)| area)
+    }
+
+    // What a repaint has to cover: the area painted last time, and the current size at the
+    // same place in case it grew. None when the object wasn't painted yet.
+    pub fn damage(&self) -> Option<Rect> {
+        let (origin, area) = self.painted.get()?;
+        Some(area.union(&Rect::from_pos_size(origin, self.size)))
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     types::{BoxConstraints, Position, Size},
 };
 
-use super::{RenderObject, RenderObjectProp};
+use super::{LayoutCache, RenderObject, RenderObjectProp};
 
 pub struct LimitedRenderObject {
     child: Option<Box<dyn RenderObject>>,
@@ -14,6 +14,7 @@ pub struct LimitedRenderObject {
 
     props: HashMap<String, RenderObjectProp>,
     render_pos: Position,
+    layout_cache: LayoutCache,
 }
 
 impl LimitedRenderObject {
@@ -24,6 +25,7 @@ impl LimitedRenderObject {
             max_height,
             props: HashMap::new(),
             render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
         }
     }
 
@@ -47,19 +49,56 @@ impl LimitedRenderObject {
     fn compute_size(&mut self, constraints: BoxConstraints) -> Size {
         let cns = self.limit_constraints(constraints.clone());
         match self.child {
-            Some(ref mut c) => constraints.constrain(c.calculate_layout(cns)),
+            Some(ref mut c) => constraints.constrain(c.layout(cns)),
             None => cns.constrain(Size::default()),
         }
     }
 }
 
 impl RenderObject for LimitedRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(ref c) = self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(ref mut c) = self.child {
+            visitor(c.as_mut());
+        }
+    }
+
     fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
         if let Some(ref c) = self.child {
-            c.render(context, context_mut.update(self));
+            c.paint(context, context_mut.update(self));
         }
     }
 
+    // The limits only apply to unbounded constraints, intrinsic sizes are the child's
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_width(height))
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_width(height))
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_height(width))
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_height(width))
+    }
+
     fn set_render_pos(&mut self, render_pos: Position) {
         self.render_pos = render_pos;
     }
@@ -68,6 +107,14 @@ impl RenderObject for LimitedRenderObject {
         self.render_pos
     }
 
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
         self.props.get(prop)
     }
@@ -3,29 +3,53 @@ use crate::{
     types::{BoxConstraints, Position, Size},
 };
 
-use super::RenderObject;
+use super::{LayoutCache, RenderObject};
 
 pub struct ListRenderObject {
     children: Vec<Box<dyn RenderObject>>,
+    layout_cache: LayoutCache,
 }
 
 impl ListRenderObject {
     pub fn new(children: Vec<Box<dyn RenderObject>>) -> Self {
-        Self { children }
+        Self {
+            children,
+            layout_cache: LayoutCache::default(),
+        }
     }
 }
 
 impl RenderObject for ListRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in &self.children {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in &mut self.children {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
         for c in &mut self.children {
-            c.calculate_layout(constraints.clone());
+            c.layout(constraints.clone());
         }
         return constraints.smallest();
     }
 
     fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
         for c in &self.children {
-            c.render(context, context_mut.clone());
+            c.paint(context, context_mut.clone());
         }
     }
 }
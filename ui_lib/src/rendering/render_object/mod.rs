@@ -1,26 +1,278 @@
+mod animated;
+mod aspect_ratio;
 mod blob;
 mod constrained;
+mod container;
+mod custom_layout;
+mod custom_paint;
+mod decorated_box;
 mod flex;
+mod focus;
+mod fractionally_sized;
+mod grid;
+mod image;
+mod intrinsic;
+mod layout_builder;
+mod layout_cache;
 mod limited;
 mod list;
+mod nine_slice;
+mod opacity;
 mod position;
 mod props;
+mod repaint_boundary;
+mod shortcuts;
+mod table;
+mod text_field;
+mod transform;
+mod wrap;
+pub use animated::AnimatedRenderObject;
+pub use aspect_ratio::AspectRatioRenderObject;
 pub use blob::BlobRenderObject;
 pub use constrained::ConstrainedRenderObject;
+pub use container::{ContainerRenderObject, ContainerStyle};
+pub use custom_layout::{
+    CustomMultiChildLayoutRenderObject, FlowDelegate, FlowPainter, FlowRenderObject,
+    MultiChildLayout, MultiChildLayoutDelegate,
+};
+pub use custom_paint::{CustomPaintRenderObject, PaintMouseCallback, Painter};
+pub use decorated_box::{BoxDecoration, DecoratedBoxRenderObject};
 pub use flex::RenderFlex;
+pub use focus::{FocusRenderObject, FocusScopeRenderObject};
+pub use fractionally_sized::FractionallySizedRenderObject;
+pub use grid::RenderGrid;
+pub use image::ImageRenderObject;
+pub use intrinsic::{IntrinsicHeightRenderObject, IntrinsicWidthRenderObject};
+pub use layout_builder::{LayoutBuilderRenderObject, LayoutWidgetBuilder};
+pub use layout_cache::LayoutCache;
 pub use limited::LimitedRenderObject;
 pub use list::ListRenderObject;
+pub use nine_slice::NineSliceRenderObject;
+pub use opacity::OpacityRenderObject;
 pub use position::{MoveRenderObject, PositionRenderObject};
 pub use props::RenderObjectProp;
+pub use repaint_boundary::RepaintBoundaryRenderObject;
+pub use shortcuts::{
+    ActionCallback, ActionsRenderObject, KeyCallback, KeyboardListenerRenderObject,
+    ShortcutsRenderObject,
+};
+pub use table::RenderTable;
+pub use text_field::{TextCallback, TextFieldRenderObject, TextFieldStyle};
+pub use transform::TransformRenderObject;
+pub use wrap::RenderWrap;
 
-use super::{render_context::RenderContextMut, RenderContext};
-use crate::types::{BoxConstraints, Position, Size};
+use super::{render_context::RenderContextMut, BoxShadow, DamageRegion, RenderContext};
+use crate::{
+    inputs::{KeyEvent, KeyEventResult, MouseStatus},
+    types::{BoxConstraints, Color, Position, Rect, Size},
+};
 
 pub trait RenderObject {
     fn render(&self, _context: &mut RenderContext, _context_mut: RenderContextMut) {}
+    // What parents call on their children instead of `render`, remembers where the object
+    // and its children were drawn
+    fn paint(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let origin = context_mut.position + self.get_render_pos();
+        let rect = self
+            .layout_cache()
+            .map(|c| Rect::from_pos_size(origin, c.size()));
+        let shadow = self.elevation_shadow();
+        if let (Some(shadow), Some(rect)) = (&shadow, rect) {
+            // under the elevated object, at the depth of what it is raised from
+            let radius = self.get_prop_f32("shadow_radius").unwrap_or(0.0);
+            let shadow = BoxShadow {
+                color: shadow.color.with_opacity(context_mut.opacity),
+                ..*shadow
+            };
+            context
+                .display_list
+                .draw_shadow(rect, radius, &shadow, context_mut.elevation);
+        }
+        self.render(context, context_mut.clone());
+        let (Some(cache), Some(rect)) = (self.layout_cache(), rect) else {
+            return;
+        };
+        let mut area = self.paint_bounds(rect);
+        if let Some(shadow) = &shadow {
+            area = area.union(&shadow.bounds(rect));
+        }
+        self.visit_children(&mut |c| {
+            if let Some(painted) = c.layout_cache().and_then(|c| c.painted_area()) {
+                area = area.union(&painted);
+            }
+        });
+        cache.set_painted(origin, area);
+    }
+    // Where the object draws when laid out in `rect`, for what goes past its size like shadows
+    fn paint_bounds(&self, rect: Rect) -> Rect {
+        rect
+    }
+    // The shadow `Elevate` asks for, falling further the higher the elevation. The color is
+    // stored as 0xAARRGGBB.
+    fn elevation_shadow(&self) -> Option<BoxShadow> {
+        let color = self.get_prop_u32("shadow_color")?;
+        let elevation = self.get_prop_usize("elevation").unwrap_or(0);
+        Some(BoxShadow {
+            color: Color::from(color).with_alpha((color >> 24) as u8),
+            ..BoxShadow::from_elevation(elevation)
+        })
+    }
+    // For what is drawn from something else than the layout, like the focus
+    fn paint_changed(&self) -> bool {
+        false
+    }
+    fn mark_needs_paint(&mut self) {
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.mark_needs_paint();
+        }
+    }
+    // Adds where the objects to paint again were drawn last time. Objects that don't know
+    // where that was damage the whole window.
+    fn collect_damage(&mut self, damage: &mut DamageRegion) {
+        if self.paint_changed() || self.layout_cache().is_some_and(|c| c.needs_paint()) {
+            match self.layout_cache().and_then(|c| c.damage()) {
+                Some(rect) => damage.add(rect),
+                None => damage.add_everything(),
+            }
+        }
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.clear_needs_paint();
+        }
+        self.visit_children_mut(&mut |c| c.collect_damage(damage));
+    }
     fn calculate_layout(&mut self, _constraints: BoxConstraints) -> Size {
         Size::default()
     }
+    // Objects keeping a `LayoutCache` skip their layout when given the same constraints as
+    // last time. The others are laid out every time and always count as needing a layout.
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        None
+    }
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        None
+    }
+    // For layouts depending on something else than the constraints, like an animation
+    fn layout_changed(&self) -> bool {
+        false
+    }
+    fn needs_layout(&self) -> bool {
+        self.layout_changed() || self.layout_cache().is_none_or(|c| c.is_dirty())
+    }
+    fn mark_needs_layout(&mut self) {
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.mark_dirty();
+        }
+    }
+    fn is_relayout_boundary(&self) -> bool {
+        self.layout_cache()
+            .is_some_and(|c| c.is_relayout_boundary())
+    }
+    // What parents call on their children instead of `calculate_layout`
+    fn layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.layout_with(constraints, false)
+    }
+    // `layout` for parents making the constraints from the intrinsic dimensions or the size
+    // of the child: a change in the child changes them, so it can't be a relayout boundary
+    fn layout_with(&mut self, constraints: BoxConstraints, parent_uses_size: bool) -> Size {
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.set_parent_uses_size(parent_uses_size);
+        }
+        if !self.layout_changed() {
+            if let Some(size) = self.layout_cache().and_then(|c| c.get(&constraints)) {
+                return size;
+            }
+        }
+        let size = self.calculate_layout(constraints.clone());
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.store(constraints, size);
+        }
+        size
+    }
+    // Marks every object whose layout is out of date, going up from the changed ones until
+    // a relayout boundary, and the way down to them for `flush_layout`. The whole tree is
+    // visited since objects find out about their changes on their own. Returns whether the
+    // parent has to be laid out again.
+    fn propagate_needs_layout(&mut self) -> bool {
+        let mut child_changed = false;
+        let mut below = false;
+        self.visit_children_mut(&mut |c| {
+            child_changed |= c.propagate_needs_layout() && !c.is_relayout_boundary();
+            below |= c.layout_cache().is_none_or(|c| c.needs_flush());
+        });
+        let changed = child_changed || self.needs_layout();
+        if changed {
+            self.mark_needs_layout();
+        }
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.set_needs_flush(changed || below);
+        }
+        changed
+    }
+    // Lays out again, with their last constraints, the boundaries marked by
+    // `propagate_needs_layout` that no parent took care of, skipping the subtrees where
+    // nothing changed
+    fn flush_layout(&mut self) {
+        if self.layout_cache().is_some_and(|c| !c.needs_flush()) {
+            return;
+        }
+        if self.needs_layout() {
+            let last = self
+                .layout_cache()
+                .and_then(|c| Some((c.constraints().cloned()?, c.parent_uses_size())));
+            if let Some((constraints, parent_uses_size)) = last {
+                self.layout_with(constraints, parent_uses_size);
+            }
+        }
+        self.visit_children_mut(&mut |c| c.flush_layout());
+        if let Some(cache) = self.layout_cache_mut() {
+            cache.set_needs_flush(false);
+        }
+    }
+    // Intrinsic dimensions answer "how big would you like to be" without a layout: the
+    // smallest size the content fits in and the size past which it stops growing, along one
+    // axis when the other one is `height` or `width` (possibly infinite).
+    // Objects that don't know better answer for their largest child.
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        let mut width = 0f32;
+        self.visit_children(&mut |c| width = width.max(c.min_intrinsic_width(height)));
+        width
+    }
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        let mut width = 0f32;
+        self.visit_children(&mut |c| width = width.max(c.max_intrinsic_width(height)));
+        width
+    }
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        let mut height = 0f32;
+        self.visit_children(&mut |c| height = height.max(c.min_intrinsic_height(width)));
+        height
+    }
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        let mut height = 0f32;
+        self.visit_children(&mut |c| height = height.max(c.max_intrinsic_height(width)));
+        height
+    }
+    fn visit_children(&self, _visitor: &mut dyn FnMut(&dyn RenderObject)) {}
+    fn visit_children_mut(&mut self, _visitor: &mut dyn FnMut(&mut dyn RenderObject)) {}
+    fn contains_focus(&self) -> bool {
+        let mut focused = false;
+        self.visit_children(&mut |c| focused = focused || c.contains_focus());
+        focused
+    }
+    // Children get the event first, the first one not ignoring it wins
+    fn handle_key_event(&mut self, event: &KeyEvent) -> KeyEventResult {
+        let mut result = KeyEventResult::Ignored;
+        self.visit_children_mut(&mut |c| {
+            if result == KeyEventResult::Ignored {
+                result = c.handle_key_event(event);
+            }
+        });
+        result
+    }
+    // Every render object sees the mouse, the ones reacting to it check where they were drawn
+    fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+        self.visit_children_mut(&mut |c| c.handle_mouse_event(mouse));
+    }
     fn set_render_pos(&mut self, _render_pos: Position) {}
     fn get_render_pos(&self) -> Position {
         Position::default()
@@ -67,3 +319,139 @@ pub trait RenderObject {
         self.set_prop(prop, RenderObjectProp::F32(val));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    // Wants to be `size` big, which can change behind its back like with a notifier
+    #[derive(Default)]
+    struct Probe {
+        size: Rc<Cell<Size>>,
+        changed: Rc<Cell<bool>>,
+        layouts: Rc<Cell<usize>>,
+        layout_cache: LayoutCache,
+    }
+
+    impl Probe {
+        fn resize(&self, size: Size) {
+            self.size.set(size);
+            self.changed.set(true);
+        }
+    }
+
+    impl RenderObject for Probe {
+        fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+            self.changed.set(false);
+            self.layouts.set(self.layouts.get() + 1);
+            constraints.constrain(self.size.get())
+        }
+        fn layout_changed(&self) -> bool {
+            self.changed.get()
+        }
+        fn layout_cache(&self) -> Option<&LayoutCache> {
+            Some(&self.layout_cache)
+        }
+        fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+            Some(&mut self.layout_cache)
+        }
+        fn max_intrinsic_width(&self, _height: f32) -> f32 {
+            self.size.get().w
+        }
+    }
+
+    fn probe(w: f32, h: f32) -> (Probe, Probe) {
+        let probe = Probe {
+            size: Rc::new(Cell::new(Size { w, h })),
+            ..Probe::default()
+        };
+        let handle = Probe {
+            size: probe.size.clone(),
+            changed: probe.changed.clone(),
+            layouts: probe.layouts.clone(),
+            ..Probe::default()
+        };
+        (probe, handle)
+    }
+
+    // What `App` does every frame
+    fn frame(root: &mut dyn RenderObject, size: Size) -> Size {
+        root.propagate_needs_layout();
+        let size = root.layout(BoxConstraints::tight(size));
+        root.flush_layout();
+        size
+    }
+
+    fn child_is_boundary(ro: &dyn RenderObject) -> bool {
+        let mut boundary = false;
+        ro.visit_children(&mut |c| boundary = c.is_relayout_boundary());
+        boundary
+    }
+
+    fn child_size(ro: &dyn RenderObject) -> Size {
+        let mut size = Size::default();
+        ro.visit_children(&mut |c| size = c.layout_cache().unwrap().size());
+        size
+    }
+
+    #[test]
+    fn tight_constraints_stop_the_relayout() {
+        let (child, handle) = probe(10.0, 10.0);
+        let mut root = ConstrainedRenderObject::new(
+            Some(Box::new(child)),
+            BoxConstraints::tight_for(50.0, 50.0),
+        );
+        let window = Size { w: 100.0, h: 100.0 };
+        frame(&mut root, window);
+        assert_eq!(handle.layouts.get(), 1);
+        frame(&mut root, window);
+        assert_eq!(handle.layouts.get(), 1);
+
+        handle.resize(Size { w: 20.0, h: 20.0 });
+        assert!(child_is_boundary(&root));
+        // the child is laid out again on its own
+        assert!(!root.propagate_needs_layout());
+        assert!(!root.needs_layout());
+        root.flush_layout();
+        assert_eq!(handle.layouts.get(), 2);
+    }
+
+    #[test]
+    fn children_sized_from_their_intrinsics_are_not_boundaries() {
+        let (child, handle) = probe(30.0, 10.0);
+        let mut root = IntrinsicWidthRenderObject::new(Box::new(child), None, None);
+        let window = Size { w: 100.0, h: 100.0 };
+        frame(&mut root, window);
+        let constraints = BoxConstraints::loose(window);
+        assert_eq!(root.layout(constraints.clone()).w, 30.0);
+        assert_eq!(child_size(&root).w, 30.0);
+        assert!(!child_is_boundary(&root));
+
+        handle.resize(Size { w: 60.0, h: 10.0 });
+        assert!(root.propagate_needs_layout());
+        assert_eq!(root.layout(constraints).w, 60.0);
+        root.flush_layout();
+        assert_eq!(child_size(&root).w, 60.0);
+    }
+
+    #[test]
+    fn flush_skips_the_subtrees_where_nothing_changed() {
+        let (child, handle) = probe(10.0, 10.0);
+        let mut root = ConstrainedRenderObject::new(
+            Some(Box::new(child)),
+            BoxConstraints::tight_for(50.0, 50.0),
+        );
+        let window = Size { w: 100.0, h: 100.0 };
+        frame(&mut root, window);
+        root.propagate_needs_layout();
+        assert!(!root.layout_cache().unwrap().needs_flush());
+        handle.resize(Size { w: 20.0, h: 20.0 });
+        root.propagate_needs_layout();
+        assert!(root.layout_cache().unwrap().needs_flush());
+        root.flush_layout();
+        assert!(!root.layout_cache().unwrap().needs_flush());
+        assert_eq!(handle.layouts.get(), 2);
+    }
+}
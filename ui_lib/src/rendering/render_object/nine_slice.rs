@@ -0,0 +1,310 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    rendering::{FilterQuality, Image, RenderContext, RenderContextMut},
+    types::{BoxConstraints, Insets, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct NineSliceRenderObject {
+    // cut once, row after row from the top left corner
+    slices: Vec<Arc<Image>>,
+    // in pixels of the image
+    insets: Insets,
+    filter: FilterQuality,
+    child: Option<Box<dyn RenderObject>>,
+    // of the whole image, used without a child
+    image_size: Size,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl NineSliceRenderObject {
+    pub fn new(
+        image: &Image,
+        insets: Insets,
+        filter: FilterQuality,
+        child: Option<Box<dyn RenderObject>>,
+    ) -> Self {
+        let (w, h) = (image.width, image.height);
+        let left = (insets.left.max(0.0) as usize).min(w);
+        let right = (insets.right.max(0.0) as usize).min(w - left);
+        let top = (insets.top.max(0.0) as usize).min(h);
+        let bottom = (insets.bottom.max(0.0) as usize).min(h - top);
+        let columns = [(0, left), (left, w - left - right), (w - right, right)];
+        let rows = [(0, top), (top, h - top - bottom), (h - bottom, bottom)];
+        let slices = rows
+            .iter()
+            .flat_map(|&(y, sh)| {
+                columns
+                    .iter()
+                    .map(move |&(x, sw)| Arc::new(image.crop(x, y, sw, sh)))
+            })
+            .collect();
+        Self {
+            slices,
+            insets: Insets {
+                top: top as f32,
+                bottom: bottom as f32,
+                left: left as f32,
+                right: right as f32,
+            },
+            filter,
+            child,
+            image_size: Size {
+                w: w as f32,
+                h: h as f32,
+            },
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+// Where the three slices along a side of `size` pixels go, the fixed ones shrunk together
+// when they don't fit
+fn spans(start: f32, end: f32, size: f32) -> [(isize, usize); 3] {
+    let scale = if start + end > size && start + end > 0.0 {
+        size / (start + end)
+    } else {
+        1.0
+    };
+    let a = (start * scale).round() as isize;
+    let c = size.round() as isize;
+    let b = (c - (end * scale).round() as isize).max(a);
+    [
+        (0, a as usize),
+        (a, (b - a) as usize),
+        (b, (c - b).max(0) as usize),
+    ]
+}
+
+impl RenderObject for NineSliceRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        if let Some(c) = &self.child {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        if let Some(c) = &mut self.child {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        let inner = (height - self.insets.vertical()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_width(inner));
+        child + self.insets.horizontal()
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        let inner = (height - self.insets.vertical()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_width(inner));
+        child + self.insets.horizontal()
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        let inner = (width - self.insets.horizontal()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.min_intrinsic_height(inner));
+        child + self.insets.vertical()
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        let inner = (width - self.insets.horizontal()).max(0.0);
+        let child = self
+            .child
+            .as_ref()
+            .map_or(0.0, |c| c.max_intrinsic_height(inner));
+        child + self.insets.vertical()
+    }
+
+    // Corners keep their size, edges stretch along the side and the center both ways
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let size = self.layout_cache.size();
+        let list = &mut *context.display_list;
+        list.push_transform(context_mut.position);
+        let columns = spans(self.insets.left, self.insets.right, size.w);
+        let rows = spans(self.insets.top, self.insets.bottom, size.h);
+        for (j, &(y, h)) in rows.iter().enumerate() {
+            for (i, &(x, w)) in columns.iter().enumerate() {
+                let slice = &self.slices[j * 3 + i];
+                if w == 0 || h == 0 || slice.width == 0 || slice.height == 0 {
+                    continue;
+                }
+                list.draw_image(
+                    x,
+                    y,
+                    w,
+                    h,
+                    slice.clone(),
+                    self.filter,
+                    context_mut.elevation,
+                );
+            }
+        }
+        list.pop_transform();
+        if let Some(c) = &self.child {
+            c.paint(context, context_mut);
+        }
+    }
+
+    // The child goes inside of the insets, like with a padding. Without one the box is the
+    // size of the image.
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let insets = self.insets;
+        let Some(c) = &mut self.child else {
+            return constraints.constrain(self.image_size);
+        };
+        let child_size = c.layout(constraints.deflate(insets));
+        c.set_render_pos(Position {
+            x: insets.left,
+            y: insets.top,
+        });
+        constraints.constrain(Size {
+            w: child_size.w + insets.horizontal(),
+            h: child_size.h + insets.vertical(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rendering::{render_object::ConstrainedRenderObject, DisplayList},
+        types::Color,
+    };
+
+    // 6x4 pixels, each holding its own coordinates
+    fn image() -> Image {
+        let pixels = (0..4)
+            .flat_map(|y| (0..6).map(move |x| Color::rgb(x, y, 0)))
+            .collect();
+        Image::new(6, 4, pixels)
+    }
+
+    fn insets(left: f32, top: f32, right: f32, bottom: f32) -> Insets {
+        Insets {
+            top,
+            bottom,
+            left,
+            right,
+        }
+    }
+
+    #[test]
+    fn spans_keep_the_ends_and_stretch_the_middle() {
+        assert_eq!(spans(2.0, 3.0, 20.0), [(0, 2), (2, 15), (17, 3)]);
+        // shrunk together when they don't fit
+        assert_eq!(spans(6.0, 2.0, 4.0), [(0, 3), (3, 0), (3, 1)]);
+        assert_eq!(spans(0.0, 0.0, 5.0), [(0, 0), (0, 5), (5, 0)]);
+        assert_eq!(spans(2.0, 2.0, 0.0), [(0, 0), (0, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn slices_are_cut_inside_of_the_image() {
+        let ro = NineSliceRenderObject::new(
+            &image(),
+            insets(1.0, 1.0, 2.0, 10.0),
+            FilterQuality::Nearest,
+            None,
+        );
+        // the bottom inset is what is left under the top one
+        assert_eq!(ro.insets, insets(1.0, 1.0, 2.0, 3.0));
+        let sizes: Vec<(usize, usize)> = ro.slices.iter().map(|s| (s.width, s.height)).collect();
+        assert_eq!(
+            sizes,
+            vec![
+                (1, 1),
+                (3, 1),
+                (2, 1),
+                (1, 0),
+                (3, 0),
+                (2, 0),
+                (1, 3),
+                (3, 3),
+                (2, 3),
+            ]
+        );
+        // the bottom right corner starts at (4, 1)
+        assert_eq!(ro.slices[8].pixel(0, 0), Color::rgb(4, 1, 0));
+    }
+
+    #[test]
+    fn the_child_goes_inside_of_the_insets() {
+        let child = ConstrainedRenderObject::new(None, BoxConstraints::tight_for(10.0, 5.0));
+        let mut ro = NineSliceRenderObject::new(
+            &image(),
+            insets(1.0, 1.0, 2.0, 1.0),
+            FilterQuality::Nearest,
+            Some(Box::new(child)),
+        );
+        assert_eq!(ro.max_intrinsic_width(f32::INFINITY), 13.0);
+        assert_eq!(
+            ro.layout(BoxConstraints::default()),
+            Size { w: 13.0, h: 7.0 }
+        );
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        ro.paint(&mut context, RenderContextMut::default());
+        let images: Vec<String> = list
+            .to_text()
+            .lines()
+            .filter(|l| l.contains("image"))
+            .map(|l| l.trim().split(" from").next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            images,
+            vec![
+                "image 0 0 1x1",
+                "image 1 0 10x1",
+                "image 11 0 2x1",
+                "image 0 1 1x5",
+                "image 1 1 10x5",
+                "image 11 1 2x5",
+                "image 0 6 1x1",
+                "image 1 6 10x1",
+                "image 11 6 2x1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct OpacityRenderObject {
+    child: Box<dyn RenderObject>,
+    opacity: f32,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl OpacityRenderObject {
+    pub fn new(child: Box<dyn RenderObject>, opacity: f32) -> Self {
+        Self {
+            child,
+            opacity,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+}
+
+impl RenderObject for OpacityRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 || context_mut.opacity <= 0.0 {
+            return;
+        }
+        context.display_list.push_opacity(opacity);
+        self.child.paint(context, context_mut.update(self));
+        context.display_list.pop_opacity();
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     types::{Alignment, BoxConstraints, Position, Size},
 };
 
-use super::{RenderObject, RenderObjectProp};
+use super::{LayoutCache, RenderObject, RenderObjectProp};
 
 pub struct PositionRenderObject {
     child: Box<dyn RenderObject>,
@@ -15,6 +15,7 @@ pub struct PositionRenderObject {
     // text_direction
     props: HashMap<String, RenderObjectProp>,
     render_pos: Position,
+    layout_cache: LayoutCache,
 }
 
 impl PositionRenderObject {
@@ -31,13 +32,52 @@ impl PositionRenderObject {
             height_factor,
             props: HashMap::new(),
             render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
         }
     }
+
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment;
+    }
+}
+
+// An infinite factor makes the box as big as the constraints allow, there is no better
+// answer than the child's size then
+fn scale_intrinsic(size: f32, factor: f32) -> f32 {
+    if factor.is_finite() {
+        size * factor
+    } else {
+        size
+    }
 }
 
 impl RenderObject for PositionRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
     fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
-        self.child.render(context, context_mut.update(self));
+        self.child.paint(context, context_mut.update(self));
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        scale_intrinsic(self.child.min_intrinsic_width(height), self.width_factor)
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        scale_intrinsic(self.child.max_intrinsic_width(height), self.width_factor)
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        scale_intrinsic(self.child.min_intrinsic_height(width), self.height_factor)
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        scale_intrinsic(self.child.max_intrinsic_height(width), self.height_factor)
     }
 
     fn set_render_pos(&mut self, render_pos: Position) {
@@ -48,6 +88,14 @@ impl RenderObject for PositionRenderObject {
         self.render_pos
     }
 
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
         self.props.get(prop)
     }
@@ -58,7 +106,7 @@ impl RenderObject for PositionRenderObject {
 
     fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
         let (px, py) = self.alignment.loc();
-        let wanted_size = self.child.calculate_layout(constraints.loosen());
+        let wanted_size = self.child.layout(constraints.loosen());
         let container_width = constraints.constrain_width(wanted_size.w * self.width_factor);
         let container_height = constraints.constrain_height(wanted_size.h * self.height_factor);
         let x = (container_width - wanted_size.w) * (px + 1.0) / 2.0;
@@ -78,6 +126,7 @@ pub struct MoveRenderObject {
 
     props: HashMap<String, RenderObjectProp>,
     render_pos: Position,
+    layout_cache: LayoutCache,
 }
 
 impl MoveRenderObject {
@@ -88,11 +137,24 @@ impl MoveRenderObject {
             absolute,
             props: HashMap::new(),
             render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
         }
     }
+
+    pub fn set_pos(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 
 impl RenderObject for MoveRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
     fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
         let this_pos = if self.absolute {
             Position::default()
@@ -104,7 +166,24 @@ impl RenderObject for MoveRenderObject {
             elevation: self.get_prop_usize("elevation").unwrap_or(0),
             ..context_mut
         };
-        self.child.render(context, context_mut);
+        self.child.paint(context, context_mut);
+    }
+
+    // Moved children are drawn outside of the layout and take no room
+    fn min_intrinsic_width(&self, _height: f32) -> f32 {
+        0.0
+    }
+
+    fn max_intrinsic_width(&self, _height: f32) -> f32 {
+        0.0
+    }
+
+    fn min_intrinsic_height(&self, _width: f32) -> f32 {
+        0.0
+    }
+
+    fn max_intrinsic_height(&self, _width: f32) -> f32 {
+        0.0
     }
 
     fn set_render_pos(&mut self, render_pos: Position) {
@@ -115,6 +194,14 @@ impl RenderObject for MoveRenderObject {
         self.render_pos
     }
 
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
     fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
         self.props.get(prop)
     }
@@ -124,7 +211,7 @@ impl RenderObject for MoveRenderObject {
     }
 
     fn calculate_layout(&mut self, _constraints: BoxConstraints) -> Size {
-        self.child.calculate_layout(BoxConstraints::default());
+        self.child.layout(BoxConstraints::default());
         self.child.set_render_pos(self.pos);
         Size { w: 0.0, h: 0.0 }
     }
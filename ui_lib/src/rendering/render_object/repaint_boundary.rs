@@ -0,0 +1,322 @@
+use std::{cell::RefCell, collections::HashMap, sync::Arc};
+
+use crate::{
+    inputs::{FocusEntry, MouseStatus},
+    rendering::{AppBuffer, DamageRegion, DisplayList, RenderContext, RenderContextMut},
+    types::{BoxConstraints, Matrix, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+// Draws its child in an offscreen buffer, then only composites that buffer until something
+// in the subtree has to be painted again. What the child draws out of its bounds is cut.
+pub struct RepaintBoundaryRenderObject {
+    child: Box<dyn RenderObject>,
+    surface: RefCell<Option<Surface>>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+struct Surface {
+    buffer: Arc<AppBuffer>,
+    size: Size,
+    // where the child was when drawn, and where the buffer was last shown
+    drawn_at: Position,
+    shown_at: Position,
+    elevation: usize,
+    focus_scope: usize,
+    // the focusables are where the transform put them
+    transform: Matrix,
+    focusables: Vec<FocusEntry>,
+}
+
+impl Surface {
+    // whole pixels the buffer is moved by when compositing
+    fn offset(&self) -> (isize, isize) {
+        (
+            (self.shown_at.x - self.drawn_at.x).round() as isize,
+            (self.shown_at.y - self.drawn_at.y).round() as isize,
+        )
+    }
+}
+
+impl RepaintBoundaryRenderObject {
+    pub fn new(child: Box<dyn RenderObject>) -> Self {
+        Self {
+            child,
+            surface: RefCell::new(None),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    fn draw_surface(&self, context_mut: &RenderContextMut) -> Surface {
+        let origin = context_mut.position;
+        let size = self.layout_cache.size();
+        let (x, y) = (origin.x.floor(), origin.y.floor());
+        let mut buffer = AppBuffer::offscreen(
+            x as isize,
+            y as isize,
+            ((origin.x + size.w).ceil() - x).max(0.0) as usize,
+            ((origin.y + size.h).ceil() - y).max(0.0) as usize,
+        );
+        let mut display_list = DisplayList::new();
+        let mut context = RenderContext::new(&mut display_list);
+        // the opacity is applied when compositing
+        let child_context = RenderContextMut {
+            opacity: 1.0,
+            ..context_mut.clone()
+        };
+        self.child.paint(&mut context, child_context);
+        let focusables = context.focusables;
+        display_list.rasterize_parallel(&mut buffer);
+        Surface {
+            buffer: Arc::new(buffer),
+            size,
+            drawn_at: origin,
+            shown_at: origin,
+            elevation: context_mut.elevation,
+            focus_scope: context_mut.focus_scope,
+            transform: context_mut.transform,
+            focusables,
+        }
+    }
+}
+
+impl RenderObject for RepaintBoundaryRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    // The surface is dropped when anything inside changed, the damage is the whole boundary
+    fn collect_damage(&mut self, damage: &mut DamageRegion) {
+        let mut inside = DamageRegion::default();
+        self.child.collect_damage(&mut inside);
+        if !inside.is_empty() {
+            *self.surface.get_mut() = None;
+            self.layout_cache.mark_needs_paint();
+        }
+        if self.layout_cache.needs_paint() {
+            match self.layout_cache.damage() {
+                Some(rect) => damage.add(rect),
+                None => damage.add_everything(),
+            }
+            self.layout_cache.clear_needs_paint();
+        }
+    }
+
+    // The child handles the mouse where it was drawn, which isn't where it is shown once the
+    // boundary moved
+    fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+        let (dx, dy) = self
+            .surface
+            .get_mut()
+            .as_ref()
+            .map_or((0, 0), |s| s.offset());
+        if (dx, dy) == (0, 0) {
+            return self.child.handle_mouse_event(mouse);
+        }
+        let pos = mouse.pos.and_then(|(x, y, z)| {
+            let x = usize::try_from(x as isize - dx).ok()?;
+            let y = usize::try_from(y as isize - dy).ok()?;
+            Some((x, y, z))
+        });
+        self.child.handle_mouse_event(&MouseStatus {
+            pos,
+            ..mouse.clone()
+        });
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        if context_mut.opacity <= 0.0 {
+            return;
+        }
+        let mut surface = self.surface.borrow_mut();
+        let size = self.layout_cache.size();
+        let outdated = surface.as_ref().is_none_or(|s| {
+            s.elevation != context_mut.elevation
+                || s.focus_scope != context_mut.focus_scope
+                || s.transform != context_mut.transform
+                || s.size != size
+        });
+        if outdated {
+            *surface = Some(self.draw_surface(&context_mut));
+        }
+        let surface = surface.as_mut().unwrap();
+        surface.shown_at = context_mut.position;
+        let offset = surface.offset();
+        context
+            .display_list
+            .composite(surface.buffer.clone(), offset, context_mut.opacity);
+        // on the window, where the transform may turn the move
+        let transform = &context_mut.transform;
+        let moved = transform.apply(Position {
+            x: offset.0 as f32,
+            y: offset.1 as f32,
+        }) - transform.apply(Position::default());
+        for entry in &surface.focusables {
+            context.focusables.push(FocusEntry {
+                rect: entry.rect.translate(moved),
+                ..entry.clone()
+            });
+        }
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.child.layout(constraints);
+        self.child.set_render_pos(Position::default());
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::rendering::DisplayCommand;
+
+    #[derive(Default)]
+    struct Probe {
+        renders: Rc<Cell<usize>>,
+        mouse: Rc<Cell<Option<(usize, usize)>>>,
+        layout_cache: LayoutCache,
+    }
+
+    impl RenderObject for Probe {
+        fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+            constraints.constrain(Size { w: 20.0, h: 10.0 })
+        }
+        fn layout_cache(&self) -> Option<&LayoutCache> {
+            Some(&self.layout_cache)
+        }
+        fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+            Some(&mut self.layout_cache)
+        }
+        fn render(&self, _context: &mut RenderContext, _context_mut: RenderContextMut) {
+            self.renders.set(self.renders.get() + 1);
+        }
+        fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+            self.mouse.set(mouse.pos.map(|(x, y, _)| (x, y)));
+        }
+    }
+
+    fn boundary() -> (
+        RepaintBoundaryRenderObject,
+        Rc<Cell<usize>>,
+        Rc<Cell<Option<(usize, usize)>>>,
+    ) {
+        let probe = Probe::default();
+        let (renders, mouse) = (probe.renders.clone(), probe.mouse.clone());
+        let mut ro = RepaintBoundaryRenderObject::new(Box::new(probe));
+        ro.layout(BoxConstraints::default());
+        // what a new layout damaged
+        damage(&mut ro);
+        (ro, renders, mouse)
+    }
+
+    // the offsets of the surfaces composited
+    fn paint_at(ro: &dyn RenderObject, x: f32, y: f32) -> Vec<(isize, isize)> {
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        let context_mut = RenderContextMut {
+            position: Position { x, y },
+            ..RenderContextMut::default()
+        };
+        ro.paint(&mut context, context_mut);
+        list.commands()
+            .iter()
+            .filter_map(|c| match c {
+                DisplayCommand::Composite { offset, .. } => Some(*offset),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn damage(ro: &mut dyn RenderObject) -> Vec<crate::types::Rect> {
+        let mut damage = DamageRegion::default();
+        ro.collect_damage(&mut damage);
+        damage.take(Size { w: 100.0, h: 100.0 })
+    }
+
+    #[test]
+    fn the_surface_is_reused_until_the_child_changes() {
+        let (mut ro, renders, _) = boundary();
+        assert_eq!(paint_at(&ro, 0.0, 0.0), vec![(0, 0)]);
+        assert_eq!(renders.get(), 1);
+        assert!(damage(&mut ro).is_empty());
+
+        assert_eq!(paint_at(&ro, 0.0, 0.0), vec![(0, 0)]);
+        assert_eq!(renders.get(), 1);
+        assert!(damage(&mut ro).is_empty());
+
+        ro.visit_children_mut(&mut |c| c.layout_cache_mut().unwrap().mark_needs_paint());
+        assert!(!damage(&mut ro).is_empty());
+        paint_at(&ro, 0.0, 0.0);
+        assert_eq!(renders.get(), 2);
+    }
+
+    #[test]
+    fn a_moved_boundary_composites_the_same_surface() {
+        let (ro, renders, _) = boundary();
+        paint_at(&ro, 0.0, 0.0);
+        assert_eq!(paint_at(&ro, 5.0, 3.0), vec![(5, 3)]);
+        assert_eq!(renders.get(), 1);
+    }
+
+    #[test]
+    fn the_mouse_is_moved_back_to_where_the_child_was_drawn() {
+        let (mut ro, _, mouse) = boundary();
+        paint_at(&ro, 0.0, 0.0);
+        paint_at(&ro, 5.0, 3.0);
+        ro.handle_mouse_event(&MouseStatus::now(
+            Some((10, 10, 0)),
+            false,
+            false,
+            false,
+            0.0,
+        ));
+        assert_eq!(mouse.get(), Some((5, 7)));
+        // left of where the child was drawn
+        ro.handle_mouse_event(&MouseStatus::now(
+            Some((2, 10, 0)),
+            false,
+            false,
+            false,
+            0.0,
+        ));
+        assert_eq!(mouse.get(), None);
+    }
+}
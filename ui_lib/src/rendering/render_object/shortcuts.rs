@@ -0,0 +1,236 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    inputs::{focus, Intent, KeyChord, KeyEvent, KeyEventResult},
+    rendering::{RenderContext, RenderContextMut},
+    types::{BoxConstraints, Position, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub type KeyCallback = Rc<dyn Fn(&KeyEvent) -> bool>;
+pub type ActionCallback = Rc<dyn Fn()>;
+
+pub struct KeyboardListenerRenderObject {
+    child: Box<dyn RenderObject>,
+    on_key: KeyCallback,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl KeyboardListenerRenderObject {
+    pub fn new(child: Box<dyn RenderObject>, on_key: KeyCallback) -> Self {
+        Self {
+            child,
+            on_key,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for KeyboardListenerRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn handle_key_event(&mut self, event: &KeyEvent) -> KeyEventResult {
+        match self.child.handle_key_event(event) {
+            KeyEventResult::Ignored if (self.on_key)(event) => KeyEventResult::Handled,
+            result => result,
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.child.paint(context, context_mut.update(self));
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+}
+
+pub struct ShortcutsRenderObject {
+    child: Box<dyn RenderObject>,
+    bindings: HashMap<KeyChord, Intent>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl ShortcutsRenderObject {
+    pub fn new(child: Box<dyn RenderObject>, bindings: HashMap<KeyChord, Intent>) -> Self {
+        Self {
+            child,
+            bindings,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for ShortcutsRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn handle_key_event(&mut self, event: &KeyEvent) -> KeyEventResult {
+        // shortcuts only apply when the focus is below them, or when nothing is focused
+        let in_scope = self.child.contains_focus() || !focus::has_primary_focus();
+        match self.child.handle_key_event(event) {
+            KeyEventResult::Ignored if in_scope => self
+                .bindings
+                .iter()
+                .find(|(chord, _)| chord.accepts(event))
+                .map_or(KeyEventResult::Ignored, |(_, intent)| {
+                    KeyEventResult::Intent(*intent)
+                }),
+            result => result,
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.child.paint(context, context_mut.update(self));
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+}
+
+pub struct ActionsRenderObject {
+    child: Box<dyn RenderObject>,
+    actions: HashMap<Intent, ActionCallback>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl ActionsRenderObject {
+    pub fn new(child: Box<dyn RenderObject>, actions: HashMap<Intent, ActionCallback>) -> Self {
+        Self {
+            child,
+            actions,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+}
+
+impl RenderObject for ActionsRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn handle_key_event(&mut self, event: &KeyEvent) -> KeyEventResult {
+        match self.child.handle_key_event(event) {
+            KeyEventResult::Intent(intent) => match self.actions.get(&intent) {
+                Some(action) => {
+                    action();
+                    KeyEventResult::Handled
+                }
+                None => KeyEventResult::Intent(intent),
+            },
+            result => result,
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.child.paint(context, context_mut.update(self));
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.child.layout(constraints)
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{
+        BoxConstraints, Position, Rect, Size, TableBorder, TableCellVerticalAlignment,
+        TableColumnWidth,
+    },
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct RenderTable {
+    rows: Vec<Vec<Box<dyn RenderObject>>>,
+    column_widths: Vec<TableColumnWidth>,
+    default_column_width: TableColumnWidth,
+    default_vertical_alignment: TableCellVerticalAlignment,
+    border: Option<TableBorder>,
+
+    // edges of the columns and rows from the last layout
+    column_offsets: Vec<f32>,
+    row_offsets: Vec<f32>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+    render_size: Size,
+}
+
+impl RenderTable {
+    pub fn new(
+        rows: Vec<Vec<Box<dyn RenderObject>>>,
+        column_widths: Vec<TableColumnWidth>,
+        default_column_width: TableColumnWidth,
+        default_vertical_alignment: TableCellVerticalAlignment,
+        border: Option<TableBorder>,
+    ) -> Self {
+        Self {
+            rows,
+            column_widths,
+            default_column_width,
+            default_vertical_alignment,
+            border,
+            column_offsets: Vec::new(),
+            row_offsets: Vec::new(),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+            render_size: Size::default(),
+        }
+    }
+
+    fn column_count(&self) -> usize {
+        self.rows.iter().map(|r| r.len()).max().unwrap_or(0)
+    }
+
+    fn column_width(&self, column: usize) -> TableColumnWidth {
+        self.column_widths
+            .get(column)
+            .copied()
+            .unwrap_or(self.default_column_width)
+    }
+
+    fn vertical_alignment(&self, cell: &dyn RenderObject) -> TableCellVerticalAlignment {
+        cell.get_prop_i32("table_vertical_alignment")
+            .map(TableCellVerticalAlignment::from)
+            .unwrap_or(self.default_vertical_alignment)
+    }
+
+    fn intrinsic_column_width(&self, column: usize) -> f32 {
+        self.rows
+            .iter()
+            .filter_map(|r| r.get(column))
+            .map(|c| c.max_intrinsic_width(f32::INFINITY))
+            .fold(0.0, f32::max)
+    }
+
+    fn compute_column_widths(&self, max_width: f32) -> Vec<f32> {
+        let columns = self.column_count();
+        let mut widths: Vec<f32> = (0..columns)
+            .map(|i| match self.column_width(i) {
+                TableColumnWidth::Fixed(w) => w,
+                TableColumnWidth::Flex(_) if max_width.is_finite() => 0.0,
+                TableColumnWidth::Flex(_) | TableColumnWidth::Intrinsic => {
+                    self.intrinsic_column_width(i)
+                }
+            })
+            .collect();
+        let total_flex: f32 = (0..columns)
+            .map(|i| match self.column_width(i) {
+                TableColumnWidth::Flex(f) => f,
+                _ => 0.0,
+            })
+            .sum();
+        if max_width.is_finite() && total_flex > 0.0 {
+            let free = (max_width - widths.iter().sum::<f32>()).max(0.0);
+            for (i, w) in widths.iter_mut().enumerate() {
+                if let TableColumnWidth::Flex(f) = self.column_width(i) {
+                    *w = free * f / total_flex;
+                }
+            }
+        }
+        widths
+    }
+}
+
+fn offsets(sizes: &[f32]) -> Vec<f32> {
+    let mut offsets = vec![0f32];
+    for s in sizes {
+        offsets.push(offsets[offsets.len() - 1] + s);
+    }
+    offsets
+}
+
+impl RenderObject for RenderTable {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in self.rows.iter().flatten() {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in self.rows.iter_mut().flatten() {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, _height: f32) -> f32 {
+        (0..self.column_count())
+            .map(|i| match self.column_width(i) {
+                TableColumnWidth::Fixed(w) => w,
+                TableColumnWidth::Flex(_) => 0.0,
+                TableColumnWidth::Intrinsic => self.intrinsic_column_width(i),
+            })
+            .sum()
+    }
+
+    fn max_intrinsic_width(&self, _height: f32) -> f32 {
+        self.compute_column_widths(f32::INFINITY).iter().sum()
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        let widths = self.compute_column_widths(width);
+        self.rows
+            .iter()
+            .map(|r| {
+                // like in the layout, filling cells don't size their row
+                r.iter()
+                    .zip(&widths)
+                    .filter(|(c, _)| {
+                        self.vertical_alignment(c.as_ref()) != TableCellVerticalAlignment::Fill
+                    })
+                    .map(|(c, w)| c.max_intrinsic_height(*w))
+                    .fold(0.0, f32::max)
+            })
+            .sum()
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        self.min_intrinsic_height(width)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        for c in self.rows.iter().flatten() {
+            c.paint(context, context_mut.clone());
+        }
+
+        let Some(border) = self.border else {
+            return;
+        };
+        let origin = context_mut.position;
+        let rect = Rect::from_pos_size(origin, self.render_size);
+        let color = border.color.with_opacity(context_mut.opacity);
+        let depth = context_mut.elevation;
+        context
+            .display_list
+            .draw_outline(rect, border.width, color, depth);
+        if !border.inside {
+            return;
+        }
+        let t = border.width as usize;
+        let half = border.width / 2.0;
+        let inner_columns = self.column_offsets.iter().skip(1);
+        for x in inner_columns.take(self.column_offsets.len().saturating_sub(2)) {
+            context.display_list.blend_rectangle(
+                (origin.x + x - half) as isize,
+                origin.y as isize,
+                t,
+                self.render_size.h as usize,
+                color,
+                depth,
+            );
+        }
+        let inner_rows = self.row_offsets.iter().skip(1);
+        for y in inner_rows.take(self.row_offsets.len().saturating_sub(2)) {
+            context.display_list.blend_rectangle(
+                origin.x as isize,
+                (origin.y + y - half) as isize,
+                self.render_size.w as usize,
+                t,
+                color,
+                depth,
+            );
+        }
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let widths = self.compute_column_widths(constraints.max_width);
+        let column_offsets = offsets(&widths);
+
+        let mut heights = Vec::with_capacity(self.rows.len());
+        let mut sizes: Vec<Vec<Size>> = Vec::with_capacity(self.rows.len());
+        for r in 0..self.rows.len() {
+            let mut row_sizes = Vec::with_capacity(self.rows[r].len());
+            for (i, w) in widths.iter().enumerate().take(self.rows[r].len()) {
+                // filling cells are laid out once the row height is known
+                let size = match self.vertical_alignment(self.rows[r][i].as_ref()) {
+                    TableCellVerticalAlignment::Fill => Size::default(),
+                    _ => self.rows[r][i].layout(BoxConstraints {
+                        min_width: *w,
+                        max_width: *w,
+                        min_height: 0.0,
+                        max_height: f32::INFINITY,
+                    }),
+                };
+                row_sizes.push(size);
+            }
+            heights.push(row_sizes.iter().map(|s| s.h).fold(0.0, f32::max));
+            sizes.push(row_sizes);
+        }
+        let row_offsets = offsets(&heights);
+
+        for (r, height) in heights.iter().enumerate() {
+            for (i, w) in widths.iter().enumerate().take(self.rows[r].len()) {
+                let alignment = self.vertical_alignment(self.rows[r][i].as_ref());
+                let cell = &mut self.rows[r][i];
+                let free = height - sizes[r][i].h;
+                let y = match alignment {
+                    // the column may be as wide as the cell would like
+                    TableCellVerticalAlignment::Fill => {
+                        cell.layout_with(BoxConstraints::tight_for(*w, *height), true);
+                        0.0
+                    }
+                    TableCellVerticalAlignment::Top => 0.0,
+                    TableCellVerticalAlignment::Middle => free / 2.0,
+                    TableCellVerticalAlignment::Bottom => free,
+                };
+                cell.set_render_pos(Position {
+                    x: column_offsets[i],
+                    y: row_offsets[r] + y,
+                });
+            }
+        }
+
+        self.render_size = constraints.constrain(Size {
+            w: column_offsets[column_offsets.len() - 1],
+            h: row_offsets[row_offsets.len() - 1],
+        });
+        self.column_offsets = column_offsets;
+        self.row_offsets = row_offsets;
+        self.render_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_object::ConstrainedRenderObject;
+
+    fn cell(w: f32, h: f32) -> Box<dyn RenderObject> {
+        Box::new(ConstrainedRenderObject::new(
+            None,
+            BoxConstraints::tight_for(w, h),
+        ))
+    }
+
+    fn fill(h: f32) -> Box<dyn RenderObject> {
+        let mut cell = cell(0.0, h);
+        cell.set_prop_i32(
+            "table_vertical_alignment",
+            TableCellVerticalAlignment::Fill.into(),
+        );
+        cell
+    }
+
+    fn table(rows: Vec<Vec<Box<dyn RenderObject>>>, widths: Vec<TableColumnWidth>) -> RenderTable {
+        RenderTable::new(
+            rows,
+            widths,
+            TableColumnWidth::Intrinsic,
+            TableCellVerticalAlignment::Middle,
+            None,
+        )
+    }
+
+    #[test]
+    fn columns_are_fixed_intrinsic_or_share_the_rest() {
+        let mut table = table(
+            vec![
+                vec![cell(10.0, 10.0), cell(40.0, 10.0), cell(5.0, 10.0)],
+                vec![cell(10.0, 30.0), cell(60.0, 10.0)],
+            ],
+            vec![
+                TableColumnWidth::Fixed(20.0),
+                TableColumnWidth::Intrinsic,
+                TableColumnWidth::Flex(1.0),
+            ],
+        );
+        assert_eq!(table.min_intrinsic_width(f32::INFINITY), 80.0);
+        assert_eq!(table.max_intrinsic_width(f32::INFINITY), 85.0);
+        let size = table.layout(BoxConstraints::loose(Size { w: 200.0, h: 200.0 }));
+        assert_eq!(size, Size { w: 200.0, h: 40.0 });
+        assert_eq!(table.column_offsets, vec![0.0, 20.0, 80.0, 200.0]);
+        // the short cell of the second row is centered
+        let mut positions = Vec::new();
+        table.visit_children(&mut |c| positions.push(c.get_render_pos().y));
+        assert_eq!(positions, vec![0.0, 0.0, 0.0, 10.0, 20.0]);
+    }
+
+    #[test]
+    fn filling_cells_take_the_row_height_without_sizing_it() {
+        let mut table = table(
+            vec![vec![cell(10.0, 20.0), fill(100.0)]],
+            vec![TableColumnWidth::Fixed(10.0), TableColumnWidth::Fixed(10.0)],
+        );
+        assert_eq!(table.min_intrinsic_height(20.0), 20.0);
+        assert_eq!(table.max_intrinsic_height(20.0), 20.0);
+        assert_eq!(table.layout(BoxConstraints::default()).h, 20.0);
+        assert_eq!(table.rows[0][1].layout_cache().unwrap().size().h, 20.0);
+    }
+}
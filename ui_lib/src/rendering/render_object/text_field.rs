@@ -0,0 +1,521 @@
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use crate::{
+    inputs::{
+        clipboard::SharedClipboard, FocusEntry, FocusNode, KeyEvent, KeyEventResult, LogicalKey,
+        MouseStatus, NamedKey, TextEditor,
+    },
+    rendering::{font, RenderContext, RenderContextMut},
+    types::{BoxConstraints, Color, Position, Rect, Size, ValueNotifier},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub type TextCallback = Rc<dyn Fn(&str)>;
+
+#[derive(Clone, Copy)]
+pub struct TextFieldStyle {
+    pub text_scale: usize,
+    pub padding: f32,
+    pub text_color: Color,
+    pub placeholder_color: Color,
+    pub background: Color,
+    pub border: Color,
+    pub focused_border: Color,
+    pub selection: Color,
+}
+
+impl Default for TextFieldStyle {
+    fn default() -> Self {
+        Self {
+            text_scale: 2,
+            padding: 4.0,
+            text_color: Color::BLACK,
+            placeholder_color: Color::from(0x9CA3AF),
+            background: Color::WHITE,
+            border: Color::from(0x9CA3AF),
+            focused_border: Color::from(0x3B82F6),
+            selection: Color::from(0x93C5FD),
+        }
+    }
+}
+
+pub struct TextFieldRenderObject {
+    editor: TextEditor,
+    text: ValueNotifier<String>,
+    // version of `text` the editor is in sync with
+    text_version: u64,
+    node: FocusNode,
+    clipboard: SharedClipboard,
+    placeholder: String,
+    obscure: bool,
+    // visible lines when multiline
+    lines: usize,
+    style: TextFieldStyle,
+    on_changed: Option<TextCallback>,
+    on_submitted: Option<TextCallback>,
+
+    // first visible column and line
+    scroll: (usize, usize),
+    dragging: bool,
+    // where the field was last drawn, for mouse handling
+    drawn_at: Cell<(Rect, usize)>,
+    // whether the field had the focus when last drawn
+    painted_focus: Cell<bool>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+    render_size: Size,
+}
+
+impl TextFieldRenderObject {
+    pub fn new(
+        text: ValueNotifier<String>,
+        node: FocusNode,
+        clipboard: SharedClipboard,
+        multiline: Option<usize>,
+    ) -> Self {
+        Self {
+            editor: TextEditor::new(&text.get(), multiline.is_some()),
+            text_version: text.version(),
+            text,
+            node,
+            clipboard,
+            placeholder: String::new(),
+            obscure: false,
+            lines: multiline.unwrap_or(1).max(1),
+            style: TextFieldStyle::default(),
+            on_changed: None,
+            on_submitted: None,
+            scroll: (0, 0),
+            dragging: false,
+            drawn_at: Cell::new((Rect::default(), 0)),
+            painted_focus: Cell::new(false),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+            render_size: Size::default(),
+        }
+    }
+
+    pub fn set_placeholder(&mut self, placeholder: &str) {
+        self.placeholder = placeholder.to_string();
+    }
+
+    pub fn set_obscure(&mut self, obscure: bool) {
+        self.obscure = obscure;
+    }
+
+    pub fn set_style(&mut self, style: TextFieldStyle) {
+        self.style = style;
+    }
+
+    pub fn set_on_changed(&mut self, on_changed: Option<TextCallback>) {
+        self.on_changed = on_changed;
+    }
+
+    pub fn set_on_submitted(&mut self, on_submitted: Option<TextCallback>) {
+        self.on_submitted = on_submitted;
+    }
+
+    // Picks up the changes made to the text from outside of the field
+    fn sync_from_notifier(&mut self) {
+        if self.text.version() != self.text_version {
+            self.editor.set_text(&self.text.get());
+            self.text_version = self.text.version();
+        }
+    }
+
+    fn publish_text(&mut self) {
+        let text = self.editor.text();
+        if text == self.text.get() {
+            return;
+        }
+        self.text.set(text.clone());
+        self.text_version = self.text.version();
+        if let Some(ref on_changed) = self.on_changed {
+            on_changed(&text);
+        }
+    }
+
+    // room for 20 characters when nothing tells us how wide to be
+    fn default_width(&self) -> f32 {
+        20.0 * font::char_width(self.style.text_scale) + 2.0 * self.style.padding
+    }
+
+    fn height(&self) -> f32 {
+        self.lines as f32 * font::line_height(self.style.text_scale) + 2.0 * self.style.padding
+    }
+
+    fn visible_columns(&self) -> usize {
+        let w = self.render_size.w - 2.0 * self.style.padding;
+        (w / font::char_width(self.style.text_scale)).max(1.0) as usize
+    }
+
+    fn ensure_caret_visible(&mut self) {
+        let (line, col) = self.editor.line_col(self.editor.caret());
+        let columns = self.visible_columns();
+        // keep one extra column so the caret isn't drawn on the border
+        if col < self.scroll.0 {
+            self.scroll.0 = col;
+        } else if col + 1 > self.scroll.0 + columns {
+            self.scroll.0 = col + 1 - columns;
+        }
+        if line < self.scroll.1 {
+            self.scroll.1 = line;
+        } else if line >= self.scroll.1 + self.lines {
+            self.scroll.1 = line + 1 - self.lines;
+        }
+    }
+
+    fn position_under(&self, x: f32, y: f32) -> usize {
+        let (rect, _) = self.drawn_at.get();
+        let cw = font::char_width(self.style.text_scale);
+        let lh = font::line_height(self.style.text_scale);
+        let col = ((x - rect.x - self.style.padding) / cw + 0.5).max(0.0) as usize;
+        let line = ((y - rect.y - self.style.padding) / lh).max(0.0) as usize;
+        self.editor
+            .position_at(line + self.scroll.1, col + self.scroll.0)
+    }
+
+    fn copy(&self) {
+        // copying a password would defeat masking it
+        if self.obscure {
+            return;
+        }
+        if let Some(text) = self.editor.selected_text() {
+            self.clipboard.borrow_mut().set_text(text);
+        }
+    }
+
+    fn handle_shortcut(&mut self, c: char, shift: bool) -> bool {
+        match c.to_ascii_lowercase() {
+            'a' => self.editor.select_all(),
+            'c' => self.copy(),
+            'x' => {
+                self.copy();
+                if !self.obscure {
+                    self.editor.cut();
+                }
+            }
+            'v' => {
+                let text = self.clipboard.borrow().get_text();
+                if let Some(text) = text {
+                    self.editor.insert(&text);
+                }
+            }
+            'z' if shift => {
+                self.editor.redo();
+            }
+            'z' => {
+                self.editor.undo();
+            }
+            'y' => {
+                self.editor.redo();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_named_key(&mut self, key: NamedKey, word: bool, extend: bool) -> bool {
+        let multiline = self.editor.is_multiline();
+        match key {
+            NamedKey::Enter if multiline => self.editor.insert("\n"),
+            NamedKey::Enter => {
+                if let Some(ref on_submitted) = self.on_submitted {
+                    on_submitted(&self.editor.text());
+                }
+            }
+            NamedKey::Backspace => self.editor.delete_backward(word),
+            NamedKey::Delete => self.editor.delete_forward(word),
+            NamedKey::ArrowLeft => self.editor.move_left(word, extend),
+            NamedKey::ArrowRight => self.editor.move_right(word, extend),
+            NamedKey::ArrowUp if multiline => self.editor.move_up(extend),
+            NamedKey::ArrowDown if multiline => self.editor.move_down(extend),
+            NamedKey::Home => self.editor.move_home(word, extend),
+            NamedKey::End => self.editor.move_end(word, extend),
+            _ => return false,
+        }
+        true
+    }
+
+    fn display_line(&self, line: &[char]) -> String {
+        if self.obscure {
+            "*".repeat(line.len())
+        } else {
+            line.iter().collect()
+        }
+    }
+}
+
+impl RenderObject for TextFieldRenderObject {
+    fn contains_focus(&self) -> bool {
+        self.node.has_focus()
+    }
+
+    fn handle_key_event(&mut self, event: &KeyEvent) -> KeyEventResult {
+        if !self.node.has_focus() || !event.is_down() {
+            return KeyEventResult::Ignored;
+        }
+        self.sync_from_notifier();
+        let m = event.modifiers;
+        let handled = if let Some(c) = event.character() {
+            self.editor.insert(&c.to_string());
+            true
+        } else {
+            match event.logical_key {
+                LogicalKey::Character(c) if m.ctrl && !m.alt => self.handle_shortcut(c, m.shift),
+                LogicalKey::Named(key) if !m.alt => self.handle_named_key(key, m.ctrl, m.shift),
+                _ => false,
+            }
+        };
+        if !handled {
+            return KeyEventResult::Ignored;
+        }
+        self.publish_text();
+        self.ensure_caret_visible();
+        self.mark_needs_paint();
+        KeyEventResult::Handled
+    }
+
+    fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+        let (rect, elevation) = self.drawn_at.get();
+        if mouse.lclick.released {
+            self.dragging = false;
+        }
+        let Some((x, y, _)) = mouse.pos else {
+            return;
+        };
+        let (x, y) = (x as f32, y as f32);
+        if mouse.lclick.pressed {
+            if !mouse.is_hovering(rect.pos(), rect.size(), elevation) {
+                if self.node.has_focus() {
+                    self.node.unfocus();
+                }
+                return;
+            }
+            self.sync_from_notifier();
+            self.node.request_focus();
+            let pos = self.position_under(x, y);
+            self.editor.set_caret(pos, false);
+            self.dragging = true;
+            self.mark_needs_paint();
+        } else if self.dragging && mouse.lclick.held && mouse.motion.is_some() {
+            let pos = self.position_under(x, y);
+            self.editor.set_caret(pos, true);
+            self.ensure_caret_visible();
+            self.mark_needs_paint();
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let rect = Rect::from_pos_size(context_mut.position, self.render_size);
+        let depth = context_mut.elevation;
+        let opacity = context_mut.opacity;
+        self.drawn_at.set((rect, depth));
+        if self.node.can_request_focus() {
+            context.focusables.push(FocusEntry {
+                node: self.node.clone(),
+                scope: context_mut.focus_scope,
+                rect: context_mut.transform.apply_rect(rect),
+            });
+        }
+
+        let style = &self.style;
+        let focused = self.node.has_focus();
+        self.painted_focus.set(focused);
+        let list = &mut *context.display_list;
+        list.blend_rectangle(
+            rect.x as isize,
+            rect.y as isize,
+            rect.w as usize,
+            rect.h as usize,
+            style.background.with_opacity(opacity),
+            depth,
+        );
+        let border = if focused {
+            style.focused_border
+        } else {
+            style.border
+        };
+        list.draw_outline(rect, 1.0, border.with_opacity(opacity), depth);
+
+        let scale = style.text_scale;
+        let cw = font::char_width(scale);
+        let lh = font::line_height(scale);
+        let origin = rect.pos()
+            + Position {
+                x: style.padding,
+                y: style.padding,
+            };
+        let columns = self.visible_columns();
+        let (scroll_x, scroll_y) = self.scroll;
+
+        if self.editor.chars().is_empty() && !self.placeholder.is_empty() {
+            let placeholder: String = self.placeholder.chars().take(columns).collect();
+            list.draw_text(
+                origin.x as isize,
+                origin.y as isize,
+                &placeholder,
+                scale,
+                style.placeholder_color.with_opacity(opacity),
+                depth,
+            );
+        }
+
+        let selection = self.editor.selection();
+        let chars = self.editor.chars();
+        let mut start = 0;
+        for (l, line) in chars.split(|c| *c == '\n').enumerate() {
+            let end = start + line.len();
+            if l >= scroll_y && l < scroll_y + self.lines {
+                let y = origin.y + (l - scroll_y) as f32 * lh;
+                let first = scroll_x.min(line.len());
+                let last = (scroll_x + columns).min(line.len());
+                if let Some((s, e)) = selection {
+                    let s = s.max(start + first).min(start + last);
+                    let e = e.min(start + last).max(s);
+                    if e > s {
+                        list.blend_rectangle(
+                            (origin.x + (s - start - scroll_x) as f32 * cw) as isize,
+                            y as isize,
+                            ((e - s) as f32 * cw) as usize,
+                            lh as usize,
+                            style.selection.with_opacity(opacity),
+                            depth,
+                        );
+                    }
+                }
+                list.draw_text(
+                    origin.x as isize,
+                    y as isize,
+                    &self.display_line(&line[first..last]),
+                    scale,
+                    style.text_color.with_opacity(opacity),
+                    depth,
+                );
+            }
+            start = end + 1;
+        }
+
+        if focused {
+            let (line, col) = self.editor.line_col(self.editor.caret());
+            if line >= scroll_y && line < scroll_y + self.lines && col >= scroll_x {
+                list.blend_rectangle(
+                    (origin.x + (col - scroll_x) as f32 * cw) as isize - 1,
+                    (origin.y + (line - scroll_y) as f32 * lh) as isize,
+                    scale,
+                    lh as usize,
+                    style.text_color.with_opacity(opacity),
+                    depth,
+                );
+            }
+        }
+    }
+
+    fn min_intrinsic_width(&self, _height: f32) -> f32 {
+        self.default_width()
+    }
+
+    fn max_intrinsic_width(&self, _height: f32) -> f32 {
+        self.default_width()
+    }
+
+    fn min_intrinsic_height(&self, _width: f32) -> f32 {
+        self.height()
+    }
+
+    fn max_intrinsic_height(&self, _width: f32) -> f32 {
+        self.height()
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn paint_changed(&self) -> bool {
+        self.node.has_focus() != self.painted_focus.get()
+    }
+
+    // the text was changed from outside of the field
+    fn layout_changed(&self) -> bool {
+        self.text.version() != self.text_version
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        self.sync_from_notifier();
+        let width = if constraints.has_bounded_width() {
+            constraints.max_width
+        } else {
+            self.default_width()
+        };
+        self.render_size = constraints.constrain_dimensions(width, self.height());
+        self.ensure_caret_visible();
+        self.render_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{
+        inputs::InMemoryClipboard,
+        rendering::{render_object::TransformRenderObject, DisplayList, FilterQuality},
+        types::Matrix,
+    };
+
+    #[test]
+    fn the_focus_rect_goes_through_the_transforms() {
+        let field = TextFieldRenderObject::new(
+            ValueNotifier::new("text".to_string()),
+            FocusNode::new(),
+            Rc::new(RefCell::new(InMemoryClipboard::new())),
+            None,
+        );
+        let mut ro = TransformRenderObject::new(
+            Box::new(field),
+            Matrix::scale(2.0, 2.0),
+            Position::default(),
+            None,
+            FilterQuality::Nearest,
+        );
+        let size = ro.layout(BoxConstraints::loose(Size { w: 100.0, h: 100.0 }));
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        let context_mut = RenderContextMut {
+            position: Position { x: 10.0, y: 10.0 },
+            ..RenderContextMut::default()
+        };
+        ro.paint(&mut context, context_mut);
+        assert_eq!(context.focusables.len(), 1);
+        assert_eq!(
+            context.focusables[0].rect,
+            Rect::new(10.0, 10.0, 2.0 * size.w, 2.0 * size.h)
+        );
+    }
+}
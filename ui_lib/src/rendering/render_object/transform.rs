@@ -0,0 +1,315 @@
+use std::{cell::Cell, collections::HashMap, sync::Arc};
+
+use crate::{
+    inputs::MouseStatus,
+    rendering::{
+        AppBuffer, DamageRegion, DisplayList, FilterQuality, RenderContext, RenderContextMut,
+    },
+    types::{Alignment, BoxConstraints, Matrix, Position, Rect, Size},
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+// Draws its child moved by a matrix, without changing its layout. Unless the matrix only
+// translates, the child is drawn in an offscreen buffer that is then resampled.
+pub struct TransformRenderObject {
+    child: Box<dyn RenderObject>,
+    matrix: Matrix,
+    // what the matrix turns around, from the top left corner and then the alignment
+    origin: Position,
+    alignment: Option<Alignment>,
+    filter: FilterQuality,
+    // the matrix on the window when last painted, to bring the mouse back to the child
+    shown: Cell<Matrix>,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+impl TransformRenderObject {
+    pub fn new(
+        child: Box<dyn RenderObject>,
+        matrix: Matrix,
+        origin: Position,
+        alignment: Option<Alignment>,
+        filter: FilterQuality,
+    ) -> Self {
+        Self {
+            child,
+            matrix,
+            origin,
+            alignment,
+            filter,
+            shown: Cell::new(Matrix::IDENTITY),
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    pub fn set_transform(&mut self, matrix: Matrix) {
+        if matrix != self.matrix {
+            self.matrix = matrix;
+            self.mark_needs_paint();
+        }
+    }
+
+    // The matrix for the object drawn at `position`
+    fn placed(&self, position: Position) -> Matrix {
+        let size = self.layout_cache.size();
+        let mut pivot = position + self.origin;
+        if let Some(alignment) = self.alignment {
+            let (px, py) = alignment.loc();
+            pivot = pivot
+                + Position {
+                    x: size.w * (px + 1.0) / 2.0,
+                    y: size.h * (py + 1.0) / 2.0,
+                };
+        }
+        self.matrix.around(pivot)
+    }
+
+    // Where the child drew before being transformed
+    fn child_area(&self, position: Position) -> Rect {
+        self.child
+            .layout_cache()
+            .and_then(|c| c.painted_area())
+            .unwrap_or(Rect::from_pos_size(position, self.layout_cache.size()))
+    }
+}
+
+impl RenderObject for TransformRenderObject {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        visitor(self.child.as_ref());
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        visitor(self.child.as_mut());
+    }
+
+    // The child only knows where it was before the transform, the damage is the whole
+    // transformed area
+    fn collect_damage(&mut self, damage: &mut DamageRegion) {
+        let mut inside = DamageRegion::default();
+        self.child.collect_damage(&mut inside);
+        if !inside.is_empty() {
+            self.layout_cache.mark_needs_paint();
+        }
+        if self.layout_cache.needs_paint() {
+            match self.layout_cache.damage() {
+                Some(rect) => damage.add(rect),
+                None => damage.add_everything(),
+            }
+            self.layout_cache.clear_needs_paint();
+        }
+    }
+
+    // The child gets the mouse where it would be without the transform
+    fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+        let shown = self.shown.get();
+        if shown == Matrix::IDENTITY {
+            return self.child.handle_mouse_event(mouse);
+        }
+        // flattened, nothing of the child is under the mouse but it still sees the buttons
+        let Some(inverse) = shown.inverse() else {
+            return self.child.handle_mouse_event(&MouseStatus {
+                pos: None,
+                ..mouse.clone()
+            });
+        };
+        let pos = mouse.pos.and_then(|(x, y, z)| {
+            let p = inverse.apply(Position {
+                x: x as f32 + 0.5,
+                y: y as f32 + 0.5,
+            });
+            (p.x >= 0.0 && p.y >= 0.0).then_some((p.x as usize, p.y as usize, z))
+        });
+        let motion = mouse.motion.map(|(dx, dy)| {
+            let d = inverse.apply(Position {
+                x: dx as f32,
+                y: dy as f32,
+            }) - inverse.apply(Position::default());
+            (d.x.round() as isize, d.y.round() as isize)
+        });
+        self.child.handle_mouse_event(&MouseStatus {
+            pos,
+            motion,
+            ..mouse.clone()
+        });
+    }
+
+    fn paint(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        self.render(context, context_mut.clone());
+        let origin = context_mut.position + self.render_pos;
+        let area = self.shown.get().apply_rect(self.child_area(origin));
+        self.layout_cache.set_painted(origin, area);
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        let context_mut = context_mut.update(self);
+        let matrix = self.placed(context_mut.position);
+        self.shown.set(matrix);
+        if context_mut.opacity <= 0.0 {
+            return;
+        }
+        let child_context = RenderContextMut {
+            transform: matrix.then(&context_mut.transform),
+            ..context_mut.clone()
+        };
+        if matrix.is_translation() {
+            let list = &mut *context.display_list;
+            list.push_transform(Position {
+                x: matrix.e,
+                y: matrix.f,
+            });
+            self.child.paint(context, child_context);
+            context.display_list.pop_transform();
+            return;
+        }
+
+        let mut display_list = DisplayList::new();
+        let mut inner = RenderContext::new(&mut display_list);
+        // the opacity is applied when compositing
+        self.child.paint(
+            &mut inner,
+            RenderContextMut {
+                opacity: 1.0,
+                ..child_context
+            },
+        );
+        context.focusables.append(&mut inner.focusables);
+        let area = self.child_area(context_mut.position);
+        let (x, y) = (area.x.floor(), area.y.floor());
+        let mut buffer = AppBuffer::offscreen(
+            x as isize,
+            y as isize,
+            (area.right().ceil() - x).max(0.0) as usize,
+            (area.bottom().ceil() - y).max(0.0) as usize,
+        );
+        display_list.rasterize_parallel(&mut buffer);
+        context.display_list.composite_transformed(
+            Arc::new(buffer),
+            matrix,
+            self.filter,
+            context_mut.opacity,
+        );
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    // Transforms don't change the layout
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let size = self.child.layout(constraints);
+        self.child.set_render_pos(Position::default());
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    // Remembers the last mouse it was given
+    #[derive(Default)]
+    struct Probe {
+        mouse: Rc<Cell<Option<MouseStatus>>>,
+        layout_cache: LayoutCache,
+    }
+
+    impl RenderObject for Probe {
+        fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+            constraints.constrain(Size { w: 20.0, h: 10.0 })
+        }
+        fn layout_cache(&self) -> Option<&LayoutCache> {
+            Some(&self.layout_cache)
+        }
+        fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+            Some(&mut self.layout_cache)
+        }
+        fn handle_mouse_event(&mut self, mouse: &MouseStatus) {
+            self.mouse.set(Some(mouse.clone()));
+        }
+    }
+
+    fn transformed(matrix: Matrix) -> (TransformRenderObject, Rc<Cell<Option<MouseStatus>>>) {
+        let probe = Probe::default();
+        let mouse = probe.mouse.clone();
+        let mut ro = TransformRenderObject::new(
+            Box::new(probe),
+            matrix,
+            Position::default(),
+            None,
+            FilterQuality::Nearest,
+        );
+        ro.layout(BoxConstraints::default());
+        let mut list = DisplayList::new();
+        let mut context = RenderContext::new(&mut list);
+        let context_mut = RenderContextMut {
+            position: Position { x: 10.0, y: 10.0 },
+            ..RenderContextMut::default()
+        };
+        ro.paint(&mut context, context_mut);
+        (ro, mouse)
+    }
+
+    #[test]
+    fn the_mouse_is_brought_back_to_the_child() {
+        let (mut ro, mouse) = transformed(Matrix::scale(2.0, 2.0));
+        ro.handle_mouse_event(&MouseStatus::now(
+            Some((21, 15, 0)),
+            true,
+            false,
+            false,
+            0.0,
+        ));
+        let seen = mouse.take().unwrap();
+        assert_eq!(seen.pos, Some((15, 12, 0)));
+        // moves are scaled back too
+        let moved = MouseStatus {
+            motion: Some((4, -2)),
+            ..MouseStatus::now(Some((21, 15, 0)), false, false, false, 0.0)
+        };
+        ro.handle_mouse_event(&moved);
+        assert_eq!(mouse.take().unwrap().motion, Some((2, -1)));
+    }
+
+    #[test]
+    fn flattened_children_still_get_the_buttons() {
+        let (mut ro, mouse) = transformed(Matrix::scale(0.0, 1.0));
+        ro.handle_mouse_event(&MouseStatus::now(
+            Some((10, 15, 0)),
+            true,
+            false,
+            false,
+            0.0,
+        ));
+        let seen = mouse.take().expect("the child was left out");
+        assert_eq!(seen.pos, None);
+        assert!(seen.lclick.held);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use crate::{
+    rendering::{RenderContext, RenderContextMut},
+    types::{
+        Axis, BoxConstraints, MainAxisAlignment, Position, Size, TextDirection, VerticalDirection,
+        WrapCrossAlignment,
+    },
+};
+
+use super::{LayoutCache, RenderObject, RenderObjectProp};
+
+pub struct RenderWrap {
+    children: Vec<Box<dyn RenderObject>>,
+    direction: Axis,
+    alignment: MainAxisAlignment,
+    spacing: f32,
+    run_alignment: MainAxisAlignment,
+    run_spacing: f32,
+    cross_alignment: WrapCrossAlignment,
+    text_direction: TextDirection,
+    vertical_direction: VerticalDirection,
+
+    props: HashMap<String, RenderObjectProp>,
+    render_pos: Position,
+    layout_cache: LayoutCache,
+}
+
+// Children laid on the same line, `main_size` includes the spacing between them
+struct Run {
+    first: usize,
+    count: usize,
+    main_size: f32,
+    cross_size: f32,
+}
+
+impl RenderWrap {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        children: Vec<Box<dyn RenderObject>>,
+        direction: Axis,
+        alignment: MainAxisAlignment,
+        spacing: f32,
+        run_alignment: MainAxisAlignment,
+        run_spacing: f32,
+        cross_alignment: WrapCrossAlignment,
+        text_direction: TextDirection,
+        vertical_direction: VerticalDirection,
+    ) -> Self {
+        Self {
+            children,
+            direction,
+            alignment,
+            spacing,
+            run_alignment,
+            run_spacing,
+            cross_alignment,
+            text_direction,
+            vertical_direction,
+            props: HashMap::new(),
+            render_pos: Position::default(),
+            layout_cache: LayoutCache::default(),
+        }
+    }
+
+    fn main_cross(&self, size: Size) -> (f32, f32) {
+        match self.direction {
+            Axis::Horizontal => (size.w, size.h),
+            Axis::Vertical => (size.h, size.w),
+        }
+    }
+
+    fn to_xy(&self, main: f32, cross: f32) -> (f32, f32) {
+        match self.direction {
+            Axis::Horizontal => (main, cross),
+            Axis::Vertical => (cross, main),
+        }
+    }
+
+    fn compute_runs(&mut self, constraints: &BoxConstraints) -> (Vec<Run>, Vec<Size>) {
+        let (child_constraints, main_limit) = match self.direction {
+            Axis::Horizontal => (
+                BoxConstraints {
+                    min_width: 0.0,
+                    max_width: constraints.max_width,
+                    min_height: 0.0,
+                    max_height: f32::INFINITY,
+                },
+                constraints.max_width,
+            ),
+            Axis::Vertical => (
+                BoxConstraints {
+                    min_width: 0.0,
+                    max_width: f32::INFINITY,
+                    min_height: 0.0,
+                    max_height: constraints.max_height,
+                },
+                constraints.max_height,
+            ),
+        };
+        let sizes: Vec<Size> = self
+            .children
+            .iter_mut()
+            .map(|c| c.layout(child_constraints.clone()))
+            .collect();
+
+        let mut runs: Vec<Run> = Vec::new();
+        for (i, size) in sizes.iter().enumerate() {
+            let (main, cross) = self.main_cross(*size);
+            match runs.last_mut() {
+                Some(run) if run.main_size + self.spacing + main <= main_limit => {
+                    run.count += 1;
+                    run.main_size += self.spacing + main;
+                    run.cross_size = run.cross_size.max(cross);
+                }
+                _ => runs.push(Run {
+                    first: i,
+                    count: 1,
+                    main_size: main,
+                    cross_size: cross,
+                }),
+            }
+        }
+        (runs, sizes)
+    }
+
+    // Extent across the runs when the main axis is `main_limit` long, the children being as
+    // long as their max intrinsic size along it
+    fn intrinsic_cross_size(
+        &self,
+        main_limit: f32,
+        cross: fn(&dyn RenderObject, f32) -> f32,
+    ) -> f32 {
+        let mut runs: Vec<(f32, f32)> = Vec::new();
+        for c in &self.children {
+            let main = match self.direction {
+                Axis::Horizontal => c.max_intrinsic_width(f32::INFINITY),
+                Axis::Vertical => c.max_intrinsic_height(f32::INFINITY),
+            }
+            .min(main_limit);
+            let child_cross = cross(c.as_ref(), main);
+            match runs.last_mut() {
+                Some(run) if run.0 + self.spacing + main <= main_limit => {
+                    run.0 += self.spacing + main;
+                    run.1 = run.1.max(child_cross);
+                }
+                _ => runs.push((main, child_cross)),
+            }
+        }
+        runs.iter().map(|r| r.1).sum::<f32>()
+            + self.run_spacing * runs.len().saturating_sub(1) as f32
+    }
+
+    // Along the main axis, the longest child at least and every child on a single run at most
+    fn intrinsic_main_size(&self, main: fn(&dyn RenderObject, f32) -> f32, max: bool) -> f32 {
+        let sizes = self
+            .children
+            .iter()
+            .map(|c| main(c.as_ref(), f32::INFINITY));
+        if max {
+            sizes.sum::<f32>() + self.spacing * self.children.len().saturating_sub(1) as f32
+        } else {
+            sizes.fold(0.0, f32::max)
+        }
+    }
+
+    fn position_children(&mut self, runs: &[Run], sizes: &[Size], main_size: f32, cross_size: f32) {
+        let flip_main = (self.direction == Axis::Horizontal
+            && self.text_direction == TextDirection::Rtl)
+            || (self.direction == Axis::Vertical
+                && self.vertical_direction == VerticalDirection::Up);
+        let flip_cross = (self.direction == Axis::Horizontal
+            && self.vertical_direction == VerticalDirection::Up)
+            || (self.direction == Axis::Vertical && self.text_direction == TextDirection::Rtl);
+
+        let runs_extent: f32 = runs.iter().map(|r| r.cross_size).sum();
+        let (run_before, run_between, _) = self.run_alignment.distribute_free_space(
+            cross_size - runs_extent,
+            runs.len(),
+            self.run_spacing,
+        );
+
+        let mut run_offset = run_before;
+        for run in runs {
+            let children_size = run.main_size - self.spacing * (run.count - 1) as f32;
+            let (before, between, _) = self.alignment.distribute_free_space(
+                main_size - children_size,
+                run.count,
+                self.spacing,
+            );
+            let run_cross = if flip_cross {
+                cross_size - run_offset - run.cross_size
+            } else {
+                run_offset
+            };
+
+            let mut child_offset = before;
+            for (i, size) in sizes.iter().enumerate().skip(run.first).take(run.count) {
+                let (child_main, child_cross) = self.main_cross(*size);
+                let mut cross = self
+                    .cross_alignment
+                    .get_cross_offset(run.cross_size, child_cross);
+                if flip_cross {
+                    cross = run.cross_size - cross - child_cross;
+                }
+                let main = if flip_main {
+                    main_size - child_offset - child_main
+                } else {
+                    child_offset
+                };
+                let (x, y) = self.to_xy(main, run_cross + cross);
+                self.children[i].set_render_pos(Position { x, y });
+                child_offset += child_main + between;
+            }
+            run_offset += run.cross_size + run_between;
+        }
+    }
+}
+
+impl RenderObject for RenderWrap {
+    fn visit_children(&self, visitor: &mut dyn FnMut(&dyn RenderObject)) {
+        for c in &self.children {
+            visitor(c.as_ref());
+        }
+    }
+
+    fn visit_children_mut(&mut self, visitor: &mut dyn FnMut(&mut dyn RenderObject)) {
+        for c in &mut self.children {
+            visitor(c.as_mut());
+        }
+    }
+
+    fn min_intrinsic_width(&self, height: f32) -> f32 {
+        match self.direction {
+            Axis::Horizontal => self.intrinsic_main_size(|c, h| c.min_intrinsic_width(h), false),
+            Axis::Vertical => self.intrinsic_cross_size(height, |c, h| c.min_intrinsic_width(h)),
+        }
+    }
+
+    fn max_intrinsic_width(&self, height: f32) -> f32 {
+        match self.direction {
+            Axis::Horizontal => self.intrinsic_main_size(|c, h| c.max_intrinsic_width(h), true),
+            Axis::Vertical => self.intrinsic_cross_size(height, |c, h| c.max_intrinsic_width(h)),
+        }
+    }
+
+    fn min_intrinsic_height(&self, width: f32) -> f32 {
+        match self.direction {
+            Axis::Horizontal => self.intrinsic_cross_size(width, |c, w| c.min_intrinsic_height(w)),
+            Axis::Vertical => self.intrinsic_main_size(|c, w| c.min_intrinsic_height(w), false),
+        }
+    }
+
+    fn max_intrinsic_height(&self, width: f32) -> f32 {
+        match self.direction {
+            Axis::Horizontal => self.intrinsic_cross_size(width, |c, w| c.max_intrinsic_height(w)),
+            Axis::Vertical => self.intrinsic_main_size(|c, w| c.max_intrinsic_height(w), true),
+        }
+    }
+
+    fn set_prop(&mut self, prop: &str, val: RenderObjectProp) {
+        self.props.insert(prop.to_string(), val);
+    }
+
+    fn get_prop(&self, prop: &str) -> Option<&RenderObjectProp> {
+        self.props.get(prop)
+    }
+
+    fn set_render_pos(&mut self, render_pos: Position) {
+        self.render_pos = render_pos;
+    }
+
+    fn get_render_pos(&self) -> Position {
+        self.render_pos
+    }
+
+    fn layout_cache(&self) -> Option<&LayoutCache> {
+        Some(&self.layout_cache)
+    }
+
+    fn layout_cache_mut(&mut self) -> Option<&mut LayoutCache> {
+        Some(&mut self.layout_cache)
+    }
+
+    fn render(&self, context: &mut RenderContext, context_mut: RenderContextMut) {
+        for c in &self.children {
+            c.paint(context, context_mut.update(self));
+        }
+    }
+
+    fn calculate_layout(&mut self, constraints: BoxConstraints) -> Size {
+        let (runs, sizes) = self.compute_runs(&constraints);
+        let main_extent = runs.iter().map(|r| r.main_size).fold(0.0, f32::max);
+        let cross_extent = runs.iter().map(|r| r.cross_size).sum::<f32>()
+            + self.run_spacing * runs.len().saturating_sub(1) as f32;
+        let (w, h) = self.to_xy(main_extent, cross_extent);
+        let size = constraints.constrain(Size { w, h });
+        let (main_size, cross_size) = self.main_cross(size);
+        self.position_children(&runs, &sizes, main_size, cross_size);
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_object::ConstrainedRenderObject;
+
+    fn boxes(sizes: &[(f32, f32)]) -> Vec<Box<dyn RenderObject>> {
+        sizes
+            .iter()
+            .map(|&(w, h)| {
+                Box::new(ConstrainedRenderObject::new(
+                    None,
+                    BoxConstraints::tight_for(w, h),
+                )) as Box<dyn RenderObject>
+            })
+            .collect()
+    }
+
+    fn wrap(children: Vec<Box<dyn RenderObject>>, text_direction: TextDirection) -> RenderWrap {
+        RenderWrap::new(
+            children,
+            Axis::Horizontal,
+            MainAxisAlignment::Start,
+            10.0,
+            MainAxisAlignment::Start,
+            5.0,
+            WrapCrossAlignment::Center,
+            text_direction,
+            VerticalDirection::Down,
+        )
+    }
+
+    fn positions(wrap: &RenderWrap) -> Vec<(f32, f32)> {
+        let mut positions = Vec::new();
+        wrap.visit_children(&mut |c| {
+            let pos = c.get_render_pos();
+            positions.push((pos.x, pos.y));
+        });
+        positions
+    }
+
+    #[test]
+    fn children_flow_onto_new_runs() {
+        let mut wrap = wrap(
+            boxes(&[(40.0, 20.0), (40.0, 10.0), (40.0, 20.0)]),
+            TextDirection::Ltr,
+        );
+        let size = wrap.layout(BoxConstraints::loose(Size { w: 100.0, h: 100.0 }));
+        assert_eq!(size, Size { w: 90.0, h: 45.0 });
+        assert_eq!(positions(&wrap), vec![(0.0, 0.0), (50.0, 5.0), (0.0, 25.0)]);
+    }
+
+    #[test]
+    fn right_to_left_runs_start_on_the_right() {
+        let mut wrap = wrap(boxes(&[(40.0, 20.0), (40.0, 20.0)]), TextDirection::Rtl);
+        wrap.layout(BoxConstraints::tight_for(100.0, 50.0));
+        assert_eq!(positions(&wrap), vec![(60.0, 0.0), (10.0, 0.0)]);
+    }
+
+    #[test]
+    fn an_oversized_child_gets_a_run_of_its_own() {
+        let mut wrap = wrap(
+            boxes(&[(30.0, 10.0), (150.0, 10.0), (30.0, 10.0)]),
+            TextDirection::Ltr,
+        );
+        let size = wrap.layout(BoxConstraints::loose(Size { w: 100.0, h: 100.0 }));
+        assert_eq!(size.h, 40.0);
+        assert_eq!(positions(&wrap)[2], (0.0, 30.0));
+    }
+}
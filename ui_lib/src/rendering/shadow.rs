@@ -0,0 +1,258 @@
+use std::{cell::RefCell, collections::HashMap, sync::Arc};
+
+use crate::types::{Color, Position, Rect};
+
+// Masks kept for the shadows drawn again and again, the cache is emptied past it
+const CACHED_MASKS: usize = 64;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BoxShadow {
+    pub color: Color,
+    pub offset: Position,
+    // how far the shadow fades out past its edges
+    pub blur_radius: f32,
+    // how much bigger than the box the shadow is before the blur
+    pub spread_radius: f32,
+}
+
+impl BoxShadow {
+    pub const DEFAULT_COLOR: Color = Color::argb(0x60, 0, 0, 0);
+
+    pub fn new(color: impl Into<Color>, offset: Position, blur_radius: f32) -> Self {
+        Self {
+            color: color.into(),
+            offset,
+            blur_radius,
+            spread_radius: 0.0,
+        }
+    }
+
+    // A shadow falling further and softer the higher the elevation
+    pub fn from_elevation(elevation: usize) -> Self {
+        let e = elevation.min(24) as f32;
+        Self::new(Self::DEFAULT_COLOR, Position { x: 0.0, y: e / 2.0 }, e)
+    }
+
+    pub fn spread(mut self, spread_radius: f32) -> Self {
+        self.spread_radius = spread_radius;
+        self
+    }
+
+    // Half-width of the box blur, applied three times in a row so that it looks gaussian
+    fn box_radius(&self) -> usize {
+        if self.blur_radius <= 0.0 {
+            return 0;
+        }
+        let sigma = self.blur_radius * 0.57735 + 0.5;
+        ((sigma * sigma + 0.25).sqrt() - 0.5).round().max(0.0) as usize
+    }
+
+    // Everything the shadow of `rect` covers
+    pub fn bounds(&self, rect: Rect) -> Rect {
+        // and a pixel for the rounding
+        let margin = self.spread_radius + (3 * self.box_radius()) as f32 + 1.0;
+        rect.translate(self.offset).inflate(margin)
+    }
+
+    // The shadow of a rectangle with rounded corners. Shadows of the same shape only
+    // moved by whole pixels share their mask.
+    pub fn mask(&self, rect: Rect, border_radius: f32) -> ShadowMask {
+        let shape = rect
+            .translate(self.offset)
+            .inflate(self.spread_radius.max(-rect.w.min(rect.h) / 2.0));
+        let radius = (border_radius + self.spread_radius).max(0.0);
+        let (dx, dy) = (shape.x.floor(), shape.y.floor());
+        let key = MaskKey {
+            size: (shape.w.to_bits(), shape.h.to_bits()),
+            fraction: ((shape.x - dx).to_bits(), (shape.y - dy).to_bits()),
+            radius: radius.to_bits(),
+            blur: self.box_radius(),
+        };
+        let mask = MASKS.with_borrow_mut(|masks| {
+            if let Some(mask) = masks.get(&key) {
+                return mask.clone();
+            }
+            let shape = Rect {
+                x: shape.x - dx,
+                y: shape.y - dy,
+                ..shape
+            };
+            let mask = draw_mask(shape, radius, key.blur);
+            if masks.len() >= CACHED_MASKS {
+                masks.clear();
+            }
+            masks.insert(key, mask.clone());
+            mask
+        });
+        ShadowMask {
+            x: mask.x + dx as isize,
+            y: mask.y + dy as isize,
+            ..mask
+        }
+    }
+}
+
+// What a mask depends on, the shape being moved to the first pixel
+#[derive(PartialEq, Eq, Hash)]
+struct MaskKey {
+    size: (u32, u32),
+    // where the shape starts in its first pixel
+    fraction: (u32, u32),
+    radius: u32,
+    blur: usize,
+}
+
+thread_local! {
+    static MASKS: RefCell<HashMap<MaskKey, ShadowMask>> = RefCell::new(HashMap::new());
+}
+
+// `shape` with rounded corners of `radius`, blurred by boxes of half-width `k`
+fn draw_mask(shape: Rect, radius: f32, k: usize) -> ShadowMask {
+    let (x, y) = (
+        shape.x.floor() as isize - 3 * k as isize,
+        shape.y.floor() as isize - 3 * k as isize,
+    );
+    let width = (shape.right().ceil() as isize - x) as usize + 3 * k;
+    let height = (shape.bottom().ceil() as isize - y) as usize + 3 * k;
+
+    let mut alpha = vec![0.0f32; width * height];
+    let (left, top) = (shape.x - x as f32, shape.y - y as f32);
+    let (right, bottom) = (left + shape.w, top + shape.h);
+    let radius = radius.min(shape.w.min(shape.h) / 2.0);
+    let corner = |p: f32, low: f32, high: f32| (p.max(low + radius)).min(high - radius);
+    for j in 0..height {
+        let py = j as f32 + 0.5;
+        let cy = corner(py, top, bottom);
+        for i in 0..width {
+            let px = i as f32 + 0.5;
+            let cx = corner(px, left, right);
+            let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+            // partly covered pixels on the straight edges too, the rectangle isn't aligned
+            let edge_x = (px - left + 0.5).min(right - px + 0.5).clamp(0.0, 1.0);
+            let edge_y = (py - top + 0.5).min(bottom - py + 0.5).clamp(0.0, 1.0);
+            alpha[j * width + i] = if cx == px || cy == py {
+                edge_x * edge_y
+            } else {
+                (radius + 0.5 - distance).clamp(0.0, 1.0)
+            };
+        }
+    }
+    for _ in 0..3 {
+        box_blur(&mut alpha, width, height, 1, width, k);
+        box_blur(&mut alpha, height, width, width, 1, k);
+    }
+
+    ShadowMask {
+        x,
+        y,
+        width,
+        height,
+        alpha: alpha
+            .iter()
+            .map(|a| (a * 255.0).round().clamp(0.0, 255.0) as u8)
+            .collect(),
+    }
+}
+
+// How much of the shadow color each pixel of a rectangle gets
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShadowMask {
+    pub x: isize,
+    pub y: isize,
+    pub width: usize,
+    pub height: usize,
+    pub alpha: Arc<[u8]>,
+}
+
+// Averages each value with the `k` before and after it along lines of `length` values,
+// `step` apart, the lines starting `stride` apart. Outside of the mask counts as empty.
+fn box_blur(values: &mut [f32], length: usize, lines: usize, step: usize, stride: usize, k: usize) {
+    if k == 0 {
+        return;
+    }
+    let size = (2 * k + 1) as f32;
+    let mut line = vec![0.0f32; length];
+    for l in 0..lines {
+        let start = l * stride;
+        for (i, v) in line.iter_mut().enumerate() {
+            *v = values[start + i * step];
+        }
+        let mut sum: f32 = line.iter().take(k).sum();
+        for i in 0..length {
+            if i + k < length {
+                sum += line[i + k];
+            }
+            values[start + i * step] = sum / size;
+            if i >= k {
+                sum -= line[i - k];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shadow(blur: f32) -> BoxShadow {
+        BoxShadow::new(Color::BLACK, Position { x: 1.0, y: 2.0 }, blur)
+    }
+
+    #[test]
+    fn moved_shadows_share_their_mask() {
+        let mask = shadow(4.0).mask(Rect::new(10.25, 20.0, 30.0, 20.0), 5.0);
+        let moved = shadow(4.0).mask(Rect::new(15.25, 27.0, 30.0, 20.0), 5.0);
+        assert!(Arc::ptr_eq(&mask.alpha, &moved.alpha));
+        assert_eq!((moved.x - mask.x, moved.y - mask.y), (5, 7));
+        assert_eq!((moved.width, moved.height), (mask.width, mask.height));
+        // another place in the pixel, another blur or another spread
+        for other in [
+            shadow(4.0).mask(Rect::new(10.5, 20.0, 30.0, 20.0), 5.0),
+            shadow(8.0).mask(Rect::new(10.25, 20.0, 30.0, 20.0), 5.0),
+            shadow(4.0)
+                .spread(2.0)
+                .mask(Rect::new(10.25, 20.0, 30.0, 20.0), 5.0),
+        ] {
+            assert!(!Arc::ptr_eq(&mask.alpha, &other.alpha));
+        }
+    }
+
+    #[test]
+    fn unblurred_masks_cover_the_moved_box() {
+        let mask = shadow(0.0).mask(Rect::new(2.0, 3.0, 4.0, 5.0), 0.0);
+        assert_eq!((mask.x, mask.y, mask.width, mask.height), (3, 5, 4, 5));
+        assert!(mask.alpha.iter().all(|a| *a == 255));
+        let spread = shadow(0.0)
+            .spread(1.0)
+            .mask(Rect::new(2.0, 3.0, 4.0, 5.0), 0.0);
+        assert_eq!(
+            (spread.x, spread.y, spread.width, spread.height),
+            (2, 4, 6, 7)
+        );
+    }
+
+    #[test]
+    fn blurring_keeps_the_amount_of_shadow() {
+        let shadow = shadow(4.0);
+        let rect = Rect::new(20.0, 20.0, 10.0, 10.0);
+        let mask = shadow.mask(rect, 0.0);
+        let total: f32 = mask.alpha.iter().map(|a| *a as f32 / 255.0).sum();
+        assert!((total - 100.0).abs() < 2.0, "{total}");
+        // and stays in the bounds
+        let bounds = shadow.bounds(rect);
+        assert!(bounds.x <= mask.x as f32 && bounds.y <= mask.y as f32);
+        assert!(bounds.right() >= (mask.x + mask.width as isize) as f32);
+        assert!(bounds.bottom() >= (mask.y + mask.height as isize) as f32);
+    }
+
+    #[test]
+    fn box_blur_averages_with_the_neighbours() {
+        let mut values = [0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 3.0];
+        box_blur(&mut values, 7, 1, 1, 7, 1);
+        assert_eq!(values, [0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        // columns of a 2x3 grid
+        let mut grid = [3.0, 0.0, 0.0, 0.0, 0.0, 6.0];
+        box_blur(&mut grid, 3, 2, 2, 1, 1);
+        assert_eq!(grid, [1.0, 0.0, 1.0, 2.0, 0.0, 2.0]);
+    }
+}
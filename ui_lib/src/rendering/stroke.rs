@@ -0,0 +1,402 @@
+use std::f32::consts::PI;
+
+use crate::types::Position;
+
+use super::{path::TOLERANCE, Contour, Path};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LineCap {
+    // stops at the end of the line
+    #[default]
+    Butt,
+    Round,
+    // goes on for half the width after the end
+    Square,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LineJoin {
+    // outer edges extended until they meet, or beveled past the miter limit
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Stroke {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    // longest miter, as a multiple of the width
+    pub miter_limit: f32,
+    // alternating lengths drawn and skipped, repeated along each subpath
+    pub dashes: Vec<f32>,
+    pub dash_offset: f32,
+}
+
+impl Stroke {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn dashes(mut self, dashes: Vec<f32>, offset: f32) -> Self {
+        self.dashes = dashes;
+        self.dash_offset = offset;
+        self
+    }
+
+    // The area covered by the stroke, as pieces turning the same way so that filling them
+    // with the non-zero rule gives their union
+    pub fn outline(&self, path: &Path) -> Vec<Contour> {
+        let mut pieces = Vec::new();
+        if self.width <= 0.0 {
+            return pieces;
+        }
+        for contour in path.flatten() {
+            for part in self.dash(contour) {
+                self.outline_contour(&without_repeated_points(part), &mut pieces);
+            }
+        }
+        for piece in &mut pieces {
+            if signed_area(&piece.points) < 0.0 {
+                piece.points.reverse();
+            }
+        }
+        pieces
+    }
+
+    fn outline_contour(&self, contour: &Contour, pieces: &mut Vec<Contour>) {
+        let half = self.width / 2.0;
+        let points = &contour.points;
+        if points.len() == 1 {
+            // a dot, only seen with caps
+            let p = points[0];
+            match self.cap {
+                LineCap::Butt => {}
+                LineCap::Round => pieces.push(circle(p, half)),
+                LineCap::Square => pieces.push(polygon(vec![
+                    p + Position { x: -half, y: -half },
+                    p + Position { x: half, y: -half },
+                    p + Position { x: half, y: half },
+                    p + Position { x: -half, y: half },
+                ])),
+            }
+            return;
+        }
+
+        let segments = if contour.closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        for i in 0..segments {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let n = normal(a, b) * half;
+            pieces.push(polygon(vec![a + n, b + n, b - n, a - n]));
+        }
+
+        let joins = if contour.closed {
+            0..points.len()
+        } else {
+            1..points.len() - 1
+        };
+        for i in joins {
+            let before = points[(i + points.len() - 1) % points.len()];
+            let (at, after) = (points[i], points[(i + 1) % points.len()]);
+            self.join_piece(before, at, after, pieces);
+        }
+
+        if !contour.closed {
+            let last = points.len() - 1;
+            self.cap_piece(points[1], points[0], pieces);
+            self.cap_piece(points[last - 1], points[last], pieces);
+        }
+    }
+
+    fn join_piece(
+        &self,
+        before: Position,
+        at: Position,
+        after: Position,
+        pieces: &mut Vec<Contour>,
+    ) {
+        let half = self.width / 2.0;
+        let (d0, d1) = (direction(before, at), direction(at, after));
+        let cross = d0.x * d1.y - d0.y * d1.x;
+        if cross.abs() < 1e-6 && d0.x * d1.x + d0.y * d1.y > 0.0 {
+            // straight on, the segments already meet
+            return;
+        }
+        if self.join == LineJoin::Round {
+            pieces.push(circle(at, half));
+            return;
+        }
+        // the gap to fill is on the outside of the turn
+        let side = if cross > 0.0 { half } else { -half };
+        let (n0, n1) = (normal(before, at) * side, normal(at, after) * side);
+        let (a, b) = (at + n0, at + n1);
+        let cos = (n0.x * n1.x + n0.y * n1.y) / (half * half);
+        let miter_ratio = (2.0 / (1.0 + cos)).sqrt();
+        if self.join == LineJoin::Miter && cos > -1.0 + 1e-6 && miter_ratio <= self.miter_limit {
+            let tip = at + (n0 + n1) * (1.0 / (1.0 + cos));
+            pieces.push(polygon(vec![at, a, tip, b]));
+        } else {
+            pieces.push(polygon(vec![at, a, b]));
+        }
+    }
+
+    // The cap at `end`, for the line coming from `from`
+    fn cap_piece(&self, from: Position, end: Position, pieces: &mut Vec<Contour>) {
+        let half = self.width / 2.0;
+        match self.cap {
+            LineCap::Butt => {}
+            LineCap::Round => pieces.push(circle(end, half)),
+            LineCap::Square => {
+                let n = normal(from, end) * half;
+                let d = direction(from, end) * half;
+                pieces.push(polygon(vec![end + n, end + n + d, end - n + d, end - n]));
+            }
+        }
+    }
+
+    // The parts of the contour left drawn by the dashes, all open
+    fn dash(&self, contour: Contour) -> Vec<Contour> {
+        let mut pattern = self.dashes.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+        let period: f32 = pattern.iter().sum();
+        if pattern.is_empty() || period <= 0.0 || pattern.iter().any(|d| *d < 0.0) {
+            return vec![contour];
+        }
+
+        // where in the pattern the contour starts
+        let mut index = 0;
+        let mut left = pattern[0];
+        let mut offset = self.dash_offset.rem_euclid(period);
+        while offset > 0.0 {
+            if offset < left {
+                left -= offset;
+                break;
+            }
+            offset -= left;
+            index = (index + 1) % pattern.len();
+            left = pattern[index];
+        }
+
+        let mut points = contour.points;
+        if contour.closed {
+            points.push(points[0]);
+        }
+        let mut parts = Vec::new();
+        let mut part: Vec<Position> = if index % 2 == 0 {
+            vec![points[0]]
+        } else {
+            Vec::new()
+        };
+        for w in points.windows(2) {
+            let (mut a, b) = (w[0], w[1]);
+            let mut length = (b - a).length();
+            while length >= left {
+                let p = Position::lerp(&a, &b, left / length.max(f32::EPSILON));
+                // ends the dash, or starts the next one
+                part.push(p);
+                if index % 2 == 0 {
+                    parts.push(Contour {
+                        points: std::mem::take(&mut part),
+                        closed: false,
+                    });
+                }
+                length -= left;
+                a = p;
+                index = (index + 1) % pattern.len();
+                left = pattern[index];
+            }
+            left -= length;
+            if index % 2 == 0 {
+                part.push(b);
+            }
+        }
+        if index % 2 == 0 && part.len() > 1 {
+            parts.push(Contour {
+                points: part,
+                closed: false,
+            });
+        }
+        parts
+    }
+}
+
+fn without_repeated_points(mut contour: Contour) -> Contour {
+    contour.points.dedup_by(|a, b| (*a - *b).length() < 1e-4);
+    if contour.closed && contour.points.len() > 1 {
+        let (first, last) = (contour.points[0], contour.points[contour.points.len() - 1]);
+        if (first - last).length() < 1e-4 {
+            contour.points.pop();
+        }
+    }
+    contour
+}
+
+fn direction(a: Position, b: Position) -> Position {
+    let d = b - a;
+    d * (1.0 / d.length().max(f32::EPSILON))
+}
+
+// Perpendicular of the direction, to its left on screen
+fn normal(a: Position, b: Position) -> Position {
+    let d = direction(a, b);
+    Position { x: d.y, y: -d.x }
+}
+
+fn polygon(points: Vec<Position>) -> Contour {
+    Contour {
+        points,
+        closed: true,
+    }
+}
+
+fn circle(center: Position, radius: f32) -> Contour {
+    let steps = if radius <= TOLERANCE {
+        8
+    } else {
+        ((PI / (1.0 - TOLERANCE / radius).acos()).ceil() as usize).clamp(8, 256)
+    };
+    polygon(
+        (0..steps)
+            .map(|i| center + Position::from_angle(2.0 * PI * i as f32 / steps as f32) * radius)
+            .collect(),
+    )
+}
+
+fn signed_area(points: &[Position]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rendering::{AppBuffer, DisplayList},
+        types::Color,
+    };
+
+    const RED: u32 = 0xFF0000;
+
+    fn stroke(path: &Path, stroke: &Stroke) -> AppBuffer {
+        let mut list = DisplayList::new();
+        list.stroke_path(path, stroke, Color::from(RED), 0);
+        let mut buffer = AppBuffer::new(20, 20);
+        list.rasterize(&mut buffer);
+        buffer
+    }
+
+    fn covered(buffer: &AppBuffer) -> usize {
+        buffer.color.iter().filter(|&&c| c == RED).count()
+    }
+
+    fn line() -> Path {
+        Path::new().move_to(2.0, 5.0).line_to(12.0, 5.0)
+    }
+
+    #[test]
+    fn caps_decide_how_far_lines_go() {
+        let butt = stroke(&line(), &Stroke::new(2.0));
+        assert_eq!(covered(&butt), 20);
+        assert_eq!(butt.color[4 * 20 + 1], 0);
+        let square = stroke(&line(), &Stroke::new(2.0).cap(LineCap::Square));
+        assert_eq!(covered(&square), 24);
+        assert_eq!(square.color[4 * 20 + 1], RED);
+        let round = stroke(&line(), &Stroke::new(2.0).cap(LineCap::Round));
+        assert_ne!(round.color[4 * 20 + 1], 0);
+        assert_ne!(round.color[4 * 20 + 1], RED);
+    }
+
+    #[test]
+    fn miters_fill_the_outer_corner_and_bevels_cut_it() {
+        let corner = Path::new()
+            .move_to(2.0, 10.0)
+            .line_to(10.0, 10.0)
+            .line_to(10.0, 2.0);
+        let miter = stroke(&corner, &Stroke::new(4.0));
+        assert_eq!(miter.color[11 * 20 + 11], RED);
+        let bevel = stroke(&corner, &Stroke::new(4.0).join(LineJoin::Bevel));
+        assert_eq!(bevel.color[11 * 20 + 11], 0);
+        // past the limit, miters are beveled
+        let limited = stroke(&corner, &Stroke::new(4.0).miter_limit(1.0));
+        assert!(limited.color == bevel.color);
+    }
+
+    #[test]
+    fn dashes_cut_the_contours() {
+        let contour = Contour {
+            points: vec![Position { x: 0.0, y: 0.0 }, Position { x: 20.0, y: 0.0 }],
+            closed: false,
+        };
+        let ends = |stroke: Stroke| -> Vec<(f32, f32)> {
+            stroke
+                .dash(contour.clone())
+                .iter()
+                .map(|c| (c.points[0].x, c.points.last().unwrap().x))
+                .collect()
+        };
+        let dashed = Stroke::new(1.0).dashes(vec![4.0, 2.0], 0.0);
+        assert_eq!(
+            ends(dashed),
+            vec![(0.0, 4.0), (6.0, 10.0), (12.0, 16.0), (18.0, 20.0)]
+        );
+        let shifted = Stroke::new(1.0).dashes(vec![4.0, 2.0], 1.0);
+        assert_eq!(
+            ends(shifted),
+            vec![(0.0, 3.0), (5.0, 9.0), (11.0, 15.0), (17.0, 20.0)]
+        );
+        // patterns that can't be followed leave the line whole
+        assert_eq!(
+            ends(Stroke::new(1.0).dashes(vec![0.0], 0.0)),
+            vec![(0.0, 20.0)]
+        );
+    }
+
+    #[test]
+    fn pieces_all_turn_the_same_way() {
+        let path = Path::new()
+            .move_to(2.0, 2.0)
+            .line_to(15.0, 4.0)
+            .line_to(3.0, 15.0)
+            .close();
+        let pieces = Stroke::new(3.0).join(LineJoin::Round).outline(&path);
+        assert!(!pieces.is_empty());
+        assert!(pieces.iter().all(|p| signed_area(&p.points) >= 0.0));
+        assert!(Stroke::new(0.0).outline(&path).is_empty());
+    }
+}
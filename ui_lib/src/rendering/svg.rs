@@ -0,0 +1,438 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use crate::types::Color;
+
+use super::{
+    font, Contour, DisplayCommand, DisplayList, FillRule, FilterQuality, GradientShape, Paint,
+    Shader, TileMode,
+};
+
+// Vector export of a display list. Commands are drawn in order, the elevation isn't kept,
+// and group opacity applies to the group as a whole rather than to each command.
+impl DisplayList {
+    pub fn to_svg(&self, width: usize, height: usize) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\">\n"
+        );
+        let mut clips = 0;
+        // groups opened by the pushes and not closed yet
+        let mut groups = 0;
+        // gradients used by the next element
+        let mut defs = Vec::new();
+        let mut gradients = 0;
+        for command in self.commands() {
+            if matches!(
+                command,
+                DisplayCommand::PopClip | DisplayCommand::PopTransform | DisplayCommand::PopOpacity
+            ) {
+                // a pop without its push closes nothing
+                if groups == 0 {
+                    continue;
+                }
+                groups -= 1;
+            }
+            let indent = "  ".repeat(groups + 1);
+            let element = match command {
+                DisplayCommand::FillRect {
+                    x, y, w, h, color, ..
+                } => format!(
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\"{}/>",
+                    fill(color)
+                ),
+                DisplayCommand::RoundedRect {
+                    x,
+                    y,
+                    w,
+                    h,
+                    radius,
+                    paint,
+                    ..
+                } => {
+                    let r = radius.min(*w.min(h) as f32 / 2.0);
+                    format!(
+                        "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" rx=\"{r}\" \
+                         ry=\"{r}\"{}/>",
+                        fill_paint(paint, &mut defs, &mut gradients)
+                    )
+                }
+                DisplayCommand::Text {
+                    x,
+                    y,
+                    text,
+                    scale,
+                    paint,
+                    ..
+                } => text_element(
+                    *x,
+                    *y,
+                    text,
+                    *scale,
+                    &fill_paint(paint, &mut defs, &mut gradients),
+                ),
+                DisplayCommand::Image {
+                    x,
+                    y,
+                    w,
+                    h,
+                    image,
+                    filter,
+                    ..
+                } => format!(
+                    "<image x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" \
+                     preserveAspectRatio=\"none\" image-rendering=\"{}\" \
+                     href=\"data:image/png;base64,{}\"/>",
+                    match filter {
+                        FilterQuality::Nearest => "pixelated",
+                        FilterQuality::Bilinear => "smooth",
+                    },
+                    base64(&png(image.width, image.height, &image.pixels))
+                ),
+                DisplayCommand::Path {
+                    contours,
+                    rule,
+                    paint,
+                    ..
+                } => {
+                    let rule = match rule {
+                        FillRule::NonZero => "nonzero",
+                        FillRule::EvenOdd => "evenodd",
+                    };
+                    format!(
+                        "<path d=\"{}\" fill-rule=\"{rule}\"{}/>",
+                        path_data(contours),
+                        fill_paint(paint, &mut defs, &mut gradients)
+                    )
+                }
+                DisplayCommand::Shadow { mask, color, .. } => {
+                    let pixels: Vec<Color> = mask
+                        .alpha
+                        .iter()
+                        .map(|a| color.with_opacity(*a as f32 / 255.0))
+                        .collect();
+                    format!(
+                        "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+                         href=\"data:image/png;base64,{}\"/>",
+                        mask.x,
+                        mask.y,
+                        mask.width,
+                        mask.height,
+                        base64(&png(mask.width, mask.height, &pixels))
+                    )
+                }
+                DisplayCommand::Composite {
+                    surface,
+                    offset,
+                    opacity,
+                } => {
+                    let (x, y) = surface.origin();
+                    format!(
+                        "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" opacity=\"{opacity}\" \
+                         href=\"data:image/png;base64,{}\"/>",
+                        x + offset.0,
+                        y + offset.1,
+                        surface.width,
+                        surface.height,
+                        base64(&png(surface.width, surface.height, &surface.to_image().pixels))
+                    )
+                }
+                DisplayCommand::CompositeTransformed {
+                    surface,
+                    matrix: m,
+                    filter,
+                    opacity,
+                } => {
+                    let (x, y) = surface.origin();
+                    format!(
+                        "<image x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" \
+                         opacity=\"{opacity}\" transform=\"matrix({} {} {} {} {} {})\" \
+                         image-rendering=\"{}\" href=\"data:image/png;base64,{}\"/>",
+                        surface.width,
+                        surface.height,
+                        m.a,
+                        m.b,
+                        m.c,
+                        m.d,
+                        m.e,
+                        m.f,
+                        match filter {
+                            FilterQuality::Nearest => "pixelated",
+                            FilterQuality::Bilinear => "smooth",
+                        },
+                        base64(&png(
+                            surface.width,
+                            surface.height,
+                            &surface.to_image().pixels
+                        ))
+                    )
+                }
+                DisplayCommand::PushClip(r) => {
+                    clips += 1;
+                    format!(
+                        "<clipPath id=\"clip{clips}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" \
+                         height=\"{}\"/></clipPath>\n{indent}<g clip-path=\"url(#clip{clips})\">",
+                        r.x, r.y, r.w, r.h
+                    )
+                }
+                DisplayCommand::PushTransform(p) => {
+                    format!("<g transform=\"translate({} {})\">", p.x, p.y)
+                }
+                DisplayCommand::PushOpacity(o) => format!("<g opacity=\"{o}\">"),
+                DisplayCommand::PopClip
+                | DisplayCommand::PopTransform
+                | DisplayCommand::PopOpacity => "</g>".to_string(),
+            };
+            if !defs.is_empty() {
+                let _ = writeln!(svg, "{indent}<defs>{}</defs>", defs.concat());
+                defs.clear();
+            }
+            let _ = writeln!(svg, "{indent}{element}");
+            if matches!(
+                command,
+                DisplayCommand::PushClip(_)
+                    | DisplayCommand::PushTransform(_)
+                    | DisplayCommand::PushOpacity(_)
+            ) {
+                groups += 1;
+            }
+        }
+        for open in (0..groups).rev() {
+            let _ = writeln!(svg, "{}</g>", "  ".repeat(open + 1));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save_svg(&self, path: impl AsRef<Path>, width: usize, height: usize) -> io::Result<()> {
+        fs::write(path, self.to_svg(width, height))
+    }
+}
+
+fn fill(color: &Color) -> String {
+    let mut attributes = format!(" fill=\"#{:02x}{:02x}{:02x}\"", color.r, color.g, color.b);
+    if color.a != 0xFF {
+        let _ = write!(attributes, " fill-opacity=\"{:.3}\"", color.opacity());
+    }
+    attributes
+}
+
+// Gradients are defined in `defs`, numbered in the order they come in the document
+fn fill_paint(paint: &Paint, defs: &mut Vec<String>, gradients: &mut usize) -> String {
+    let gradient = match &paint.shader {
+        Shader::Solid(color) => return fill(color),
+        Shader::Gradient(g) => g,
+    };
+    *gradients += 1;
+    let id = format!("gradient{gradients}");
+    let spread = match gradient.tile_mode {
+        TileMode::Clamp => "pad",
+        TileMode::Repeat => "repeat",
+        TileMode::Mirror => "reflect",
+    };
+    let stops: String = gradient
+        .stops
+        .iter()
+        .map(|(t, c)| {
+            format!(
+                "<stop offset=\"{t}\" stop-color=\"#{:02x}{:02x}{:02x}\" stop-opacity=\"{:.3}\"/>",
+                c.r,
+                c.g,
+                c.b,
+                c.opacity()
+            )
+        })
+        .collect();
+    let definition = match gradient.shape {
+        GradientShape::Linear { start, end } => format!(
+            "<linearGradient id=\"{id}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" \
+             x2=\"{}\" y2=\"{}\" spreadMethod=\"{spread}\">{stops}</linearGradient>",
+            start.x, start.y, end.x, end.y
+        ),
+        GradientShape::Radial { center, radius } => format!(
+            "<radialGradient id=\"{id}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" \
+             r=\"{radius}\" spreadMethod=\"{spread}\">{stops}</radialGradient>",
+            center.x, center.y
+        ),
+        // SVG has no sweep gradient, the color halfway through stands for it
+        GradientShape::Sweep { .. } => {
+            let middle = gradient.stops.len() / 2;
+            return gradient
+                .stops
+                .get(middle)
+                .map_or_else(String::new, |(_, c)| fill(c));
+        }
+    };
+    defs.push(definition);
+    format!(" fill=\"url(#{id})\"")
+}
+
+fn path_data(contours: &[Contour]) -> String {
+    let mut data = String::new();
+    for contour in contours {
+        for (i, p) in contour.points.iter().enumerate() {
+            let command = if i == 0 { 'M' } else { 'L' };
+            let _ = write!(data, "{command}{} {} ", p.x, p.y);
+        }
+        data.push('Z');
+    }
+    data
+}
+
+// Each line in a `tspan`, stretched to the advance of the built-in font
+fn text_element(x: isize, y: isize, text: &str, scale: usize, fill: &str) -> String {
+    let scale = scale.max(1);
+    let mut element = format!(
+        "<text font-family=\"monospace\" font-size=\"{}\"{}>",
+        font::GLYPH_HEIGHT * scale,
+        fill
+    );
+    for (l, line) in text.split('\n').enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        // the baseline is at the bottom of the glyphs
+        let baseline = y + ((l * font::LINE_HEIGHT + font::GLYPH_HEIGHT) * scale) as isize;
+        let length = line.chars().count() * font::ADVANCE * scale;
+        let _ = write!(
+            element,
+            "<tspan x=\"{x}\" y=\"{baseline}\" textLength=\"{length}\" \
+             lengthAdjust=\"spacingAndGlyphs\">{}</tspan>",
+            escape(line)
+        );
+    }
+    element.push_str("</text>");
+    element
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Uncompressed PNG: the deflate stream is made of stored blocks
+fn png(width: usize, height: usize, pixels: &[Color]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(height * (1 + width * 4));
+    for row in pixels.chunks(width.max(1)).take(height) {
+        raw.push(0);
+        for c in row {
+            raw.extend_from_slice(&[c.r, c.g, c.b, c.a]);
+        }
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bits per channel, RGBA, no interlacing
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    for (kind, data) in [(b"IHDR", header), (b"IDAT", zlib), (b"IEND", Vec::new())] {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(&data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+    png
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Position, Rect};
+
+    fn groups(svg: &str) -> (usize, usize) {
+        (svg.matches("<g ").count(), svg.matches("</g>").count())
+    }
+
+    #[test]
+    fn groups_stay_balanced() {
+        let mut list = DisplayList::new();
+        list.pop_clip();
+        list.push_transform(Position { x: 2.0, y: 3.0 });
+        list.push_clip(Rect::new(0.0, 0.0, 10.0, 10.0));
+        list.draw_rectangle(0, 0, 4, 4, 0xFF0000FF, 0);
+        list.pop_clip();
+        list.pop_transform();
+        list.pop_opacity();
+        list.push_opacity(0.5);
+        list.push_transform(Position { x: 1.0, y: 1.0 });
+        let svg = list.to_svg(10, 10);
+        assert_eq!(groups(&svg), (4, 4));
+        assert_eq!(
+            svg.lines().skip(1).collect::<Vec<_>>(),
+            vec![
+                "  <g transform=\"translate(2 3)\">",
+                "    <clipPath id=\"clip1\"><rect x=\"0\" y=\"0\" width=\"10\" height=\"10\"/></clipPath>",
+                "    <g clip-path=\"url(#clip1)\">",
+                "      <rect x=\"0\" y=\"0\" width=\"4\" height=\"4\" fill=\"#0000ff\"/>",
+                "    </g>",
+                "  </g>",
+                "  <g opacity=\"0.5\">",
+                "    <g transform=\"translate(1 1)\">",
+                "    </g>",
+                "  </g>",
+                "</svg>",
+            ]
+        );
+    }
+}
@@ -27,4 +27,9 @@ impl Alignment {
             Self::Value(x, y) => (*x, *y),
         }
     }
+
+    pub fn lerp(a: &Alignment, b: &Alignment, t: f32) -> Self {
+        let ((ax, ay), (bx, by)) = (a.loc(), b.loc());
+        Self::Value(ax + (bx - ax) * t, ay + (by - ay) * t)
+    }
 }
@@ -318,23 +318,35 @@ impl BoxConstraints {
         }
     }
 
-    // pub fn lerp(a: &BoxConstraints, b: &BoxConstraints, t: f32) -> Self {
-    //     if a == b { return a.clone(); }
-    // return BoxConstraints(
-    //   minWidth: a.minWidth.isFinite ? ui.lerpDouble(a.minWidth, b.minWidth, t)! : double.infinity,
-    //   maxWidth: a.maxWidth.isFinite ? ui.lerpDouble(a.maxWidth, b.maxWidth, t)! : double.infinity,
-    //   minHeight: a.minHeight.isFinite ? ui.lerpDouble(a.minHeight, b.minHeight, t)! : double.infinity,
-    //   maxHeight: a.maxHeight.isFinite ? ui.lerpDouble(a.maxHeight, b.maxHeight, t)! : double.infinity,
-    // );
-    // }
-
-    // pub fn lerp_to_unconstrained(a: &BoxConstraints, t: f32) -> Self {
-    //     a.mul(1.0 - t)
-    // }
-
-    // pub fn lerp_from_unconstrained(a: &BoxConstraints, t: f32) -> Self {
-    //     a.mul(t)
-    // }
+    pub fn lerp(a: &BoxConstraints, b: &BoxConstraints, t: f32) -> Self {
+        if a == b {
+            return a.clone();
+        }
+        // an infinite bound can't be interpolated, it jumps halfway
+        fn lerp_double(a: f32, b: f32, t: f32) -> f32 {
+            if a.is_finite() && b.is_finite() {
+                a + (b - a) * t
+            } else if t < 0.5 {
+                a
+            } else {
+                b
+            }
+        }
+        Self {
+            min_width: lerp_double(a.min_width, b.min_width, t),
+            max_width: lerp_double(a.max_width, b.max_width, t),
+            min_height: lerp_double(a.min_height, b.min_height, t),
+            max_height: lerp_double(a.max_height, b.max_height, t),
+        }
+    }
+
+    pub fn lerp_to_unconstrained(a: &BoxConstraints, t: f32) -> Self {
+        a.mul(1.0 - t)
+    }
+
+    pub fn lerp_from_unconstrained(a: &BoxConstraints, t: f32) -> Self {
+        a.mul(t)
+    }
 }
 
 impl Display for BoxConstraints {
@@ -0,0 +1,77 @@
+use super::Size;
+
+// How an image is sized to go in a box
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BoxFit {
+    // stretched to the box, losing its aspect ratio
+    Fill,
+    // as big as possible while staying inside of the box
+    #[default]
+    Contain,
+    // as small as possible while covering the whole box, cut where it goes past it
+    Cover,
+    // the width of the box, even if it goes past it vertically
+    FitWidth,
+    // the height of the box, even if it goes past it horizontally
+    FitHeight,
+    // its own size
+    None,
+    // like None, or like Contain if that is smaller
+    ScaleDown,
+}
+
+impl BoxFit {
+    // The size an image of size `input` is drawn at in a box of size `output`
+    pub fn apply(&self, input: Size, output: Size) -> Size {
+        if input.w <= 0.0 || input.h <= 0.0 {
+            return Size::default();
+        }
+        let (sx, sy) = (output.w / input.w, output.h / input.h);
+        match self {
+            Self::Fill => output,
+            Self::Contain => input.mul(sx.min(sy)),
+            Self::Cover => input.mul(sx.max(sy)),
+            Self::FitWidth => input.mul(sx),
+            Self::FitHeight => input.mul(sy),
+            Self::None => input,
+            Self::ScaleDown => input.mul(sx.min(sy).min(1.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(w: f32, h: f32) -> Size {
+        Size { w, h }
+    }
+
+    #[test]
+    fn fits_a_wide_image_in_a_square() {
+        let (image, square) = (size(200.0, 100.0), size(100.0, 100.0));
+        let fitted = |fit: BoxFit| fit.apply(image, square);
+        assert_eq!(fitted(BoxFit::Fill), size(100.0, 100.0));
+        assert_eq!(fitted(BoxFit::Contain), size(100.0, 50.0));
+        assert_eq!(fitted(BoxFit::Cover), size(200.0, 100.0));
+        assert_eq!(fitted(BoxFit::FitWidth), size(100.0, 50.0));
+        assert_eq!(fitted(BoxFit::FitHeight), size(200.0, 100.0));
+        assert_eq!(fitted(BoxFit::None), image);
+        assert_eq!(fitted(BoxFit::ScaleDown), size(100.0, 50.0));
+    }
+
+    #[test]
+    fn small_images_are_only_scaled_down() {
+        let image = size(20.0, 10.0);
+        assert_eq!(BoxFit::ScaleDown.apply(image, size(100.0, 100.0)), image);
+        assert_eq!(
+            BoxFit::Contain.apply(image, size(100.0, 100.0)),
+            size(100.0, 50.0)
+        );
+        // nothing to draw
+        assert_eq!(
+            BoxFit::Cover.apply(size(0.0, 10.0), size(100.0, 100.0)),
+            Size::default()
+        );
+    }
+}
@@ -0,0 +1,66 @@
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Color {
+    pub a: u8,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const TRANSPARENT: Color = Color::argb(0, 0, 0, 0);
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(0xFF, 0xFF, 0xFF);
+
+    pub const fn argb(a: u8, r: u8, g: u8, b: u8) -> Self {
+        Self { a, r, g, b }
+    }
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { a: 0xFF, r, g, b }
+    }
+
+    pub fn with_alpha(&self, a: u8) -> Self {
+        Self { a, ..*self }
+    }
+
+    pub fn with_opacity(&self, opacity: f32) -> Self {
+        self.with_alpha((self.a as f32 * opacity.clamp(0.0, 1.0)).round() as u8)
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.a as f32 / 255.0
+    }
+
+    // 0RGB, the layout of the AppBuffer
+    pub fn to_u32(&self) -> u32 {
+        ((self.r as u32) << 16) | ((self.g as u32) << 8) | self.b as u32
+    }
+
+    pub fn lerp(a: &Color, b: &Color, t: f32) -> Self {
+        fn channel(a: u8, b: u8, t: f32) -> u8 {
+            (a as f32 + (b as f32 - a as f32) * t)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        }
+        Self {
+            a: channel(a.a, b.a, t),
+            r: channel(a.r, b.r, t),
+            g: channel(a.g, b.g, t),
+            b: channel(a.b, b.b, t),
+        }
+    }
+}
+
+// Colors are written as 0xRRGGBB everywhere in the library, so the top byte is ignored
+// and the color is opaque.
+impl From<u32> for Color {
+    fn from(value: u32) -> Self {
+        Self::rgb((value >> 16) as u8, (value >> 8) as u8, value as u8)
+    }
+}
+
+impl From<Color> for u32 {
+    fn from(value: Color) -> Self {
+        value.to_u32()
+    }
+}
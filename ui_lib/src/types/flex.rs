@@ -40,11 +40,15 @@ pub enum MainAxisAlignment {
 }
 
 impl MainAxisAlignment {
-    pub fn distribute_free_space(&self, free: f32, n: usize) -> (f32, f32, f32) {
-        match self {
+    // Returns the room before the first child, between two children and after the last one.
+    // `free` is the room the `n` children leave, `spacing` the smallest gap between two of them.
+    pub fn distribute_free_space(&self, free: f32, n: usize, spacing: f32) -> (f32, f32, f32) {
+        let free = free - spacing * n.saturating_sub(1) as f32;
+        let (before, between, after) = match self {
             Self::Start => (0.0, 0.0, free),
             Self::End => (free, 0.0, 0.0),
             Self::Center => (free / 2.0, 0.0, free / 2.0),
+            Self::SpaceBetween if n < 2 => (0.0, 0.0, free),
             Self::SpaceBetween => (0.0, free / (n - 1) as f32, 0.0),
             Self::SpaceAround => (
                 free / (2.0 * n as f32),
@@ -56,7 +60,8 @@ impl MainAxisAlignment {
                 free / (n + 1) as f32,
                 free / (n + 1) as f32,
             ),
-        }
+        };
+        (before, between + spacing, after)
     }
 }
 
@@ -79,3 +84,21 @@ impl CrossAxisAlignment {
         }
     }
 }
+
+// How the children of a run of a `Wrap` are placed across it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WrapCrossAlignment {
+    Start,
+    End,
+    Center,
+}
+
+impl WrapCrossAlignment {
+    pub fn get_cross_offset(&self, s_max: f32, s: f32) -> f32 {
+        match self {
+            Self::Start => 0.0,
+            Self::End => s_max - s,
+            Self::Center => (s_max - s) / 2.0,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Size of a row or a column of a `Grid`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TrackSize {
+    // fixed number of pixels
+    Px(f32),
+    // share of the space left by the other tracks, sized like `Auto` when that space is infinite
+    Fr(f32),
+    // as big as the largest child in the track
+    Auto,
+    // like `Auto`, clamped between the two values
+    MinMax(f32, f32),
+}
+
+impl TrackSize {
+    pub fn is_flexible(&self) -> bool {
+        matches!(self, Self::Fr(_))
+    }
+
+    // Size of the track when its biggest child is `content` big
+    pub fn resolve(&self, content: f32) -> f32 {
+        match self {
+            Self::Px(px) => *px,
+            Self::Fr(_) | Self::Auto => content,
+            Self::MinMax(min, max) => content.clamp(*min, max.max(*min)),
+        }
+    }
+}
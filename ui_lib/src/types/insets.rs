@@ -5,3 +5,40 @@ pub struct Insets {
     pub left: f32,
     pub right: f32,
 }
+
+impl Insets {
+    pub fn all(value: f32) -> Self {
+        Self {
+            top: value,
+            bottom: value,
+            left: value,
+            right: value,
+        }
+    }
+
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Self {
+            top: vertical,
+            bottom: vertical,
+            left: horizontal,
+            right: horizontal,
+        }
+    }
+
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
+    pub fn lerp(a: &Insets, b: &Insets, t: f32) -> Self {
+        Self {
+            top: a.top + (b.top - a.top) * t,
+            bottom: a.bottom + (b.bottom - a.bottom) * t,
+            left: a.left + (b.left - a.left) * t,
+            right: a.right + (b.right - a.right) * t,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use super::{Position, Rect};
+
+// A 2D affine transform, moving (x, y) to (a x + c y + e, b x + d y + f)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Matrix {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            e: x,
+            f: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self {
+            a: sx,
+            d: sy,
+            ..Self::IDENTITY
+        }
+    }
+
+    // Clockwise on the screen, y going down
+    pub fn rotation(radians: f32) -> Self {
+        // quarter turns stay exact, and keep the pixels aligned
+        let snap = |v: f32| if v.abs() < 1e-6 { 0.0 } else { v };
+        let (sin, cos) = radians.sin_cos();
+        let (sin, cos) = (snap(sin), snap(cos));
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    // Angles in radians by which vertical and horizontal lines lean
+    pub fn skew(x: f32, y: f32) -> Self {
+        Self {
+            b: y.tan(),
+            c: x.tan(),
+            ..Self::IDENTITY
+        }
+    }
+
+    // `self` first, then `next`
+    pub fn then(&self, next: &Matrix) -> Self {
+        Self {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    // The same transform with `origin` staying in place instead of (0, 0)
+    pub fn around(&self, origin: Position) -> Self {
+        Self::translation(-origin.x, -origin.y)
+            .then(self)
+            .then(&Self::translation(origin.x, origin.y))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.a * self.d - self.b * self.c
+    }
+
+    // None when the transform flattens everything on a line or a point
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det.abs() < 1e-9 {
+            return None;
+        }
+        Some(Self {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    pub fn is_translation(&self) -> bool {
+        self.a == 1.0 && self.b == 0.0 && self.c == 0.0 && self.d == 1.0
+    }
+
+    pub fn apply(&self, p: Position) -> Position {
+        Position {
+            x: self.a * p.x + self.c * p.y + self.e,
+            y: self.b * p.x + self.d * p.y + self.f,
+        }
+    }
+
+    // The smallest rectangle holding the transformed one
+    pub fn apply_rect(&self, rect: Rect) -> Rect {
+        let corners = [
+            Position {
+                x: rect.x,
+                y: rect.y,
+            },
+            Position {
+                x: rect.right(),
+                y: rect.y,
+            },
+            Position {
+                x: rect.x,
+                y: rect.bottom(),
+            },
+            Position {
+                x: rect.right(),
+                y: rect.bottom(),
+            },
+        ]
+        .map(|p| self.apply(p));
+        let (mut left, mut top) = (f32::INFINITY, f32::INFINITY);
+        let (mut right, mut bottom) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for p in corners {
+            left = left.min(p.x);
+            top = top.min(p.y);
+            right = right.max(p.x);
+            bottom = bottom.max(p.y);
+        }
+        Rect::new(left, top, right - left, bottom - top)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    fn close(a: Position, b: Position) -> bool {
+        (a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4
+    }
+
+    #[test]
+    fn then_applies_the_matrices_in_order() {
+        let p = Position { x: 1.0, y: 2.0 };
+        let moved_then_scaled = Matrix::translation(3.0, 0.0).then(&Matrix::scale(2.0, 2.0));
+        assert_eq!(moved_then_scaled.apply(p), Position { x: 8.0, y: 4.0 });
+        let scaled_then_moved = Matrix::scale(2.0, 2.0).then(&Matrix::translation(3.0, 0.0));
+        assert_eq!(scaled_then_moved.apply(p), Position { x: 5.0, y: 4.0 });
+        // quarter turns are exact, clockwise with y going down
+        assert_eq!(
+            Matrix::rotation(PI / 2.0).apply(Position { x: 1.0, y: 0.0 }),
+            Position { x: 0.0, y: 1.0 }
+        );
+        let origin = Position { x: 5.0, y: 5.0 };
+        assert_eq!(Matrix::rotation(1.0).around(origin).apply(origin), origin);
+    }
+
+    #[test]
+    fn inverses_undo_the_transform() {
+        let m = Matrix::rotation(0.7)
+            .then(&Matrix::skew(0.2, -0.1))
+            .then(&Matrix::scale(3.0, 0.5))
+            .then(&Matrix::translation(-4.0, 9.0));
+        let inverse = m.inverse().unwrap();
+        for p in [Position { x: 0.0, y: 0.0 }, Position { x: 12.5, y: -3.0 }] {
+            assert!(close(inverse.apply(m.apply(p)), p));
+        }
+        assert!(close(
+            m.then(&inverse).apply(Position { x: 7.0, y: 1.0 }),
+            Position { x: 7.0, y: 1.0 }
+        ));
+        // flattened on a line
+        assert_eq!(Matrix::scale(0.0, 2.0).inverse(), None);
+        assert_eq!(
+            Matrix {
+                a: 1.0,
+                b: 2.0,
+                c: 2.0,
+                d: 4.0,
+                e: 0.0,
+                f: 0.0
+            }
+            .inverse(),
+            None
+        );
+    }
+
+    #[test]
+    fn transformed_rects_are_bounded() {
+        let rect = Rect::new(0.0, 0.0, 4.0, 2.0);
+        assert_eq!(
+            Matrix::rotation(PI / 2.0).apply_rect(rect),
+            Rect::new(-2.0, 0.0, 2.0, 4.0)
+        );
+        let turned = Matrix::rotation(PI / 4.0).apply_rect(Rect::new(-1.0, -1.0, 2.0, 2.0));
+        let half_diagonal = 2f32.sqrt();
+        assert!(close(
+            turned.pos(),
+            Position {
+                x: -half_diagonal,
+                y: -half_diagonal
+            }
+        ));
+        assert!((turned.w - 2.0 * half_diagonal).abs() < 1e-4);
+        assert_eq!(
+            Matrix::translation(3.0, 1.0).apply_rect(rect),
+            Rect::new(3.0, 1.0, 4.0, 2.0)
+        );
+    }
+}
@@ -1,17 +1,32 @@
 mod alignment;
 mod box_constraints;
+mod box_fit;
+mod color;
 mod constraints;
 mod direction;
 mod flex;
+mod grid;
 mod insets;
+mod matrix;
+mod notifier;
 mod position;
+mod rect;
 mod size;
+mod table;
 
 pub use alignment::Alignment;
 pub use box_constraints::BoxConstraints;
+pub use box_fit::BoxFit;
+pub use color::Color;
 pub use constraints::Constraints;
 pub use direction::{Axis, TextDirection, VerticalDirection};
-pub use flex::{CrossAxisAlignment, FlexFit, MainAxisAlignment, MainAxisSize};
+pub use flex::{CrossAxisAlignment, FlexFit, MainAxisAlignment, MainAxisSize, WrapCrossAlignment};
+pub use grid::TrackSize;
 pub use insets::Insets;
+pub use matrix::Matrix;
+pub use notifier::ValueNotifier;
+pub(crate) use notifier::{mark_pending_changes, take_pending_changes};
 pub use position::Position;
+pub use rect::Rect;
 pub use size::{Size, SizeFlex};
+pub use table::{TableBorder, TableCellVerticalAlignment, TableColumnWidth};
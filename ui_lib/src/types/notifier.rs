@@ -0,0 +1,70 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+thread_local! {
+    static PENDING_CHANGES: Cell<bool> = const { Cell::new(false) };
+}
+
+pub(crate) fn mark_pending_changes() {
+    PENDING_CHANGES.with(|c| c.set(true));
+}
+
+// Returns whether any notifier was set since the last call
+pub(crate) fn take_pending_changes() -> bool {
+    PENDING_CHANGES.with(|c| c.replace(false))
+}
+
+// Shared, observable value: widgets keep a clone and pick up the changes made through
+// any other clone on the next frame.
+pub struct ValueNotifier<T> {
+    inner: Rc<RefCell<(T, u64)>>,
+}
+
+impl<T> Clone for ValueNotifier<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> From<T> for ValueNotifier<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> ValueNotifier<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new((value, 0))),
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        let mut inner = self.inner.borrow_mut();
+        inner.0 = value;
+        inner.1 += 1;
+        mark_pending_changes();
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut inner = self.inner.borrow_mut();
+        f(&mut inner.0);
+        inner.1 += 1;
+        mark_pending_changes();
+    }
+
+    // Incremented on every change
+    pub fn version(&self) -> u64 {
+        self.inner.borrow().1
+    }
+}
+
+impl<T: Clone> ValueNotifier<T> {
+    pub fn get(&self) -> T {
+        self.inner.borrow().0.clone()
+    }
+}
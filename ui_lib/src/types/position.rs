@@ -4,6 +4,27 @@ pub struct Position {
     pub y: f32,
 }
 
+impl Position {
+    pub fn lerp(a: &Position, b: &Position, t: f32) -> Self {
+        Self {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+        }
+    }
+
+    // Unit vector at `angle` radians from the x axis, clockwise on screen
+    pub fn from_angle(angle: f32) -> Self {
+        Self {
+            x: angle.cos(),
+            y: angle.sin(),
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.x.hypot(self.y)
+    }
+}
+
 impl std::ops::Add for Position {
     type Output = Position;
     fn add(self, rhs: Self) -> Self::Output {
@@ -40,3 +61,12 @@ impl std::ops::Sub for &Position {
         }
     }
 }
+impl std::ops::Mul<f32> for Position {
+    type Output = Position;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Position {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use super::{Position, Size};
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    pub fn from_pos_size(pos: Position, size: Size) -> Self {
+        Self {
+            x: pos.x,
+            y: pos.y,
+            w: size.w,
+            h: size.h,
+        }
+    }
+
+    pub fn pos(&self) -> Position {
+        Position {
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        Size {
+            w: self.w,
+            h: self.h,
+        }
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+
+    pub fn center(&self) -> Position {
+        Position {
+            x: self.x + self.w / 2.0,
+            y: self.y + self.h / 2.0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0.0 || self.h <= 0.0
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.x >= self.x && pos.y >= self.y && pos.x < self.right() && pos.y < self.bottom()
+    }
+
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        !self.intersect(other).is_empty()
+    }
+
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Rect {
+            x,
+            y,
+            w: (self.right().min(other.right()) - x).max(0.0),
+            h: (self.bottom().min(other.bottom()) - y).max(0.0),
+        }
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect {
+            x,
+            y,
+            w: self.right().max(other.right()) - x,
+            h: self.bottom().max(other.bottom()) - y,
+        }
+    }
+
+    pub fn inflate(&self, delta: f32) -> Rect {
+        Rect {
+            x: self.x - delta,
+            y: self.y - delta,
+            w: self.w + 2.0 * delta,
+            h: self.h + 2.0 * delta,
+        }
+    }
+
+    pub fn translate(&self, offset: Position) -> Rect {
+        Rect {
+            x: self.x + offset.x,
+            y: self.y + offset.y,
+            ..*self
+        }
+    }
+}
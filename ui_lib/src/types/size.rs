@@ -21,6 +21,13 @@ impl Size {
         }
     }
 
+    pub fn lerp(a: &Size, b: &Size, t: f32) -> Self {
+        Self {
+            w: a.w + (b.w - a.w) * t,
+            h: a.h + (b.h - a.h) * t,
+        }
+    }
+
     pub fn mul_d(&self, factor_w: f32, factor_h: f32) -> Self {
         Self {
             w: self.w * factor_w,
@@ -0,0 +1,70 @@
+use super::Color;
+
+// Width of a column of a `Table`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TableColumnWidth {
+    Fixed(f32),
+    // share of the width left by the other columns, intrinsic when that width is infinite
+    Flex(f32),
+    // the largest max intrinsic width of the cells of the column
+    Intrinsic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableCellVerticalAlignment {
+    Top,
+    Middle,
+    Bottom,
+    // the cell is as tall as its row, it doesn't count when sizing the row
+    Fill,
+}
+
+impl From<i32> for TableCellVerticalAlignment {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::Top,
+            1 => Self::Middle,
+            2 => Self::Bottom,
+            3 => Self::Fill,
+            _ => panic!("unknown TableCellVerticalAlignment value {value}"),
+        }
+    }
+}
+
+impl From<TableCellVerticalAlignment> for i32 {
+    fn from(value: TableCellVerticalAlignment) -> Self {
+        match value {
+            TableCellVerticalAlignment::Top => 0,
+            TableCellVerticalAlignment::Middle => 1,
+            TableCellVerticalAlignment::Bottom => 2,
+            TableCellVerticalAlignment::Fill => 3,
+        }
+    }
+}
+
+// Lines drawn over the edges of the cells, they don't take any room
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TableBorder {
+    pub color: Color,
+    pub width: f32,
+    // also draw the lines between the cells
+    pub inside: bool,
+}
+
+impl TableBorder {
+    pub fn all(color: Color, width: f32) -> Self {
+        Self {
+            color,
+            width,
+            inside: true,
+        }
+    }
+
+    pub fn outside(color: Color, width: f32) -> Self {
+        Self {
+            color,
+            width,
+            inside: false,
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use crate::{
+    animation::{Curve, ImplicitAnimation},
+    rendering::render_object::{
+        AnimatedRenderObject, ConstrainedRenderObject, ContainerRenderObject, ContainerStyle,
+        MoveRenderObject, OpacityRenderObject, PositionRenderObject, RenderObject,
+    },
+    types::{Alignment, BoxConstraints, Position, ValueNotifier},
+};
+
+use super::Widget;
+
+// The widgets below animate towards the value of their notifier every time it is set.
+
+pub struct AnimatedAlign {
+    child: Box<dyn Widget>,
+    alignment: ValueNotifier<Alignment>,
+    width_factor: f32,
+    height_factor: f32,
+    duration: Duration,
+    curve: Curve,
+}
+
+impl AnimatedAlign {
+    pub fn new(
+        alignment: impl Into<ValueNotifier<Alignment>>,
+        child: Box<dyn Widget>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            child,
+            alignment: alignment.into(),
+            width_factor: 1.0,
+            height_factor: 1.0,
+            duration,
+            curve: Curve::Linear,
+        }
+    }
+
+    pub fn width_factor(mut self, width_factor: f32) -> Self {
+        self.width_factor = width_factor;
+        self
+    }
+
+    pub fn height_factor(mut self, height_factor: f32) -> Self {
+        self.height_factor = height_factor;
+        self
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for AnimatedAlign {
+    fn create_render_object(&self) -> Box<dyn RenderObject> {
+        Box::new(AnimatedRenderObject::new(
+            PositionRenderObject::new(
+                self.child.create_render_object(),
+                self.alignment.get(),
+                self.width_factor,
+                self.height_factor,
+            ),
+            ImplicitAnimation::new(self.alignment.clone(), self.duration, self.curve),
+            PositionRenderObject::set_alignment,
+        ))
+    }
+}
+
+pub struct AnimatedSize {
+    child: Option<Box<dyn Widget>>,
+    constraints: ValueNotifier<BoxConstraints>,
+    duration: Duration,
+    curve: Curve,
+}
+
+impl AnimatedSize {
+    pub fn new(
+        child: Option<Box<dyn Widget>>,
+        constraints: impl Into<ValueNotifier<BoxConstraints>>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            child,
+            constraints: constraints.into(),
+            duration,
+            curve: Curve::Linear,
+        }
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for AnimatedSize {
+    fn create_render_object(&self) -> Box<dyn RenderObject> {
+        Box::new(AnimatedRenderObject::new(
+            ConstrainedRenderObject::new(
+                self.child.as_ref().map(|c| c.create_render_object()),
+                self.constraints.get(),
+            ),
+            ImplicitAnimation::new(self.constraints.clone(), self.duration, self.curve),
+            ConstrainedRenderObject::set_constraints,
+        ))
+    }
+}
+
+pub struct AnimatedPositioned {
+    child: Box<dyn Widget>,
+    pos: ValueNotifier<Position>,
+    absolute: bool,
+    duration: Duration,
+    curve: Curve,
+}
+
+impl AnimatedPositioned {
+    pub fn new(
+        child: Box<dyn Widget>,
+        pos: impl Into<ValueNotifier<Position>>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            child,
+            pos: pos.into(),
+            absolute: false,
+            duration,
+            curve: Curve::Linear,
+        }
+    }
+
+    pub fn absolute(mut self) -> Self {
+        self.absolute = true;
+        self
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for AnimatedPositioned {
+    fn create_render_object(&self) -> Box<dyn RenderObject> {
+        Box::new(AnimatedRenderObject::new(
+            MoveRenderObject::new(
+                self.child.create_render_object(),
+                self.pos.get(),
+                self.absolute,
+            ),
+            ImplicitAnimation::new(self.pos.clone(), self.duration, self.curve),
+            MoveRenderObject::set_pos,
+        ))
+    }
+}
+
+pub struct AnimatedOpacity {
+    child: Box<dyn Widget>,
+    opacity: ValueNotifier<f32>,
+    duration: Duration,
+    curve: Curve,
+}
+
+impl AnimatedOpacity {
+    pub fn new(
+        child: Box<dyn Widget>,
+        opacity: impl Into<ValueNotifier<f32>>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            child,
+            opacity: opacity.into(),
+            duration,
+            curve: Curve::Linear,
+        }
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for AnimatedOpacity {
+    fn create_render_object(&self) -> Box<dyn RenderObject> {
+        Box::new(AnimatedRenderObject::new(
+            OpacityRenderObject::new(self.child.create_render_object(), self.opacity.get()),
+            ImplicitAnimation::new(self.opacity.clone(), self.duration, self.curve),
+            OpacityRenderObject::set_opacity,
+        ))
+    }
+}
+
+pub struct AnimatedContainer {
+    child: Option<Box<dyn Widget>>,
+    style: ValueNotifier<ContainerStyle>,
+    duration: Duration,
+    curve: Curve,
+}
+
+impl AnimatedContainer {
+    // The style can be taken from a `Container` with `Container::style`
+    pub fn new(
+        child: Option<Box<dyn Widget>>,
+        style: impl Into<ValueNotifier<ContainerStyle>>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            child,
+            style: style.into(),
+            duration,
+            curve: Curve::Linear,
+        }
+    }
+
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for AnimatedContainer {
+    fn create_render_object(&self) -> Box<dyn RenderObject> {
+        Box::new(AnimatedRenderObject::new(
+            ContainerRenderObject::new(
+                self.child.as_ref().map(|c| c.create_render_object()),
+                self.style.get(),
+            ),
+            ImplicitAnimation::new(self.style.clone(), self.duration, self.curve),
+            ContainerRenderObject::set_style,
+        ))
+    }
+}
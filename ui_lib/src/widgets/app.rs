@@ -1,12 +1,24 @@
-use minifb::{Scale, Window, WindowOptions};
+use std::{thread, time::Duration};
+
+use minifb::{KeyRepeat, Scale, Window, WindowOptions};
 
 use crate::{
-    inputs::MouseStatus,
-    rendering::{render_object::RenderObject, AppBuffer, RenderContext, RenderContextMut},
-    types::{BoxConstraints, Position, Size},
+    animation::{scheduler, FrameClock},
+    inputs::{
+        focus, FocusEntry, KeyEvent, KeyEventKind, KeyEventResult, LogicalKey, Modifiers,
+        MouseStatus, NamedKey, PhysicalKey,
+    },
+    rendering::{
+        render_object::RenderObject, AppBuffer, DamageRegion, DisplayList, RenderContext,
+        RenderContextMut,
+    },
+    types::{take_pending_changes, BoxConstraints, Size},
 };
 
-use super::Widget;
+use super::{MediaQuery, MediaQueryData, Widget};
+
+// How often the window is looked at for input while nothing changes
+const IDLE_POLL: Duration = Duration::from_millis(16);
 
 pub struct App {
     pub size: Size,
@@ -15,6 +27,11 @@ pub struct App {
     pub buffer: AppBuffer,
     mouse: MouseStatus,
     window: Window,
+    clock: FrameClock,
+    focusables: Vec<FocusEntry>,
+    damage: DamageRegion,
+    // what the last frame was drawn from
+    display_list: DisplayList,
 }
 
 #[derive(Clone)]
@@ -25,8 +42,11 @@ pub struct UpdateContext<'a> {
 impl App {
     pub fn new(name: &str, w: f32, h: f32, root: Box<dyn Widget>) -> Self {
         let root = root;
+        MediaQuery::set(MediaQueryData {
+            size: Size { w, h },
+        });
         let mut render_root = root.create_render_object();
-        render_root.calculate_layout(BoxConstraints::tight_for(w, h));
+        render_root.layout(BoxConstraints::tight_for(w, h));
         // Create a buffer to store pixel data (RGB)
 
         let window = Window::new(
@@ -41,6 +61,9 @@ impl App {
         )
         .expect("Unable to create window");
 
+        let mut damage = DamageRegion::default();
+        damage.add_everything();
+
         Self {
             size: Size { w, h },
             root,
@@ -48,37 +71,96 @@ impl App {
             render_root,
             buffer: AppBuffer::new(w as usize, h as usize),
             mouse: MouseStatus::now(None, false, false, false, 0.0),
+            clock: FrameClock::new(),
+            focusables: Vec::new(),
+            damage,
+            display_list: DisplayList::new(),
         }
     }
-    fn on_draw(&mut self) {
-        self.render_root.render(
-            &mut RenderContext {
-                appbuffer: &mut self.buffer,
-            },
-            RenderContextMut {
-                position: Position::default(),
-                elevation: 0,
-            },
-        );
+    // The commands of the last frame drawn, e.g. to export it with `DisplayList::save_svg`
+    pub fn display_list(&self) -> &DisplayList {
+        &self.display_list
+    }
+
+    // Paints again the damaged parts of the window, returns false when there were none
+    fn on_draw(&mut self) -> bool {
+        self.render_root.collect_damage(&mut self.damage);
+        if self.damage.is_empty() {
+            return false;
+        }
+        let mut display_list = DisplayList::new();
+        let mut context = RenderContext::new(&mut display_list);
+        self.render_root
+            .paint(&mut context, RenderContextMut::default());
+        self.focusables = context.focusables;
+        let damage = self.damage.take(self.size);
+        display_list.rasterize_damage(&mut self.buffer, &damage, 0xFFFFFFFF);
+        self.display_list = display_list;
+        focus::settle(&self.focusables);
+        true
     }
 
     pub fn run(&mut self) {
         while self.window.is_open() {
-            self.on_tick();
-            self.on_draw();
-
-            // Update the window with the buffer data
-            self.window
-                .update_with_buffer(
-                    &self.buffer.color,
-                    self.size.w as usize,
-                    self.size.h as usize,
-                )
-                .expect("Unable to update window");
+            let changed = self.on_tick();
+            if self.on_draw() {
+                // Update the window with the buffer data
+                self.window
+                    .update_with_buffer(
+                        &self.buffer.color,
+                        self.size.w as usize,
+                        self.size.h as usize,
+                    )
+                    .expect("Unable to update window");
+            } else {
+                // Nothing to show, only look for input
+                self.window.update();
+                if !changed && !scheduler::has_scheduled_frame() {
+                    thread::sleep(IDLE_POLL);
+                }
+            }
         }
     }
 
-    fn on_tick(&mut self) {
+    fn dispatch_key_events(&mut self) {
+        let w = &self.window;
+        let held: Vec<PhysicalKey> = w.get_keys().into_iter().map(PhysicalKey::from).collect();
+        let modifiers = Modifiers::from_held_keys(&held);
+        let pressed = w.get_keys_pressed(KeyRepeat::No);
+        let repeated = w
+            .get_keys_pressed(KeyRepeat::Yes)
+            .into_iter()
+            .filter(|k| !pressed.contains(k));
+        let mut events: Vec<KeyEvent> = pressed
+            .iter()
+            .map(|k| KeyEvent::new(KeyEventKind::Down, (*k).into(), modifiers))
+            .collect();
+        events.extend(repeated.map(|k| KeyEvent::new(KeyEventKind::Repeat, k.into(), modifiers)));
+        events.extend(
+            w.get_keys_released()
+                .into_iter()
+                .map(|k| KeyEvent::new(KeyEventKind::Up, k.into(), modifiers)),
+        );
+        for event in events {
+            let result = self.render_root.handle_key_event(&event);
+            let m = event.modifiers;
+            if result != KeyEventResult::Handled
+                && event.is_down()
+                && event.logical_key == LogicalKey::Named(NamedKey::Tab)
+                && !(m.ctrl || m.alt || m.meta)
+            {
+                focus::traverse(&self.focusables, !m.shift);
+            }
+        }
+    }
+
+    // Returns whether an animation or a notifier changed something during the tick
+    fn on_tick(&mut self) -> bool {
+        self.clock.tick();
+        self.dispatch_key_events();
+        // the render objects depending on what changed pick it up on their own
+        let changed = scheduler::tick(&self.clock) | take_pending_changes();
+
         let w = &mut self.window;
         let (new_width, new_height) = w.get_size();
         if new_width != self.buffer.width || new_height != self.buffer.height {
@@ -86,11 +168,18 @@ impl App {
             self.buffer.resize(new_width, new_height);
             self.size.w = new_width as f32;
             self.size.h = new_height as f32;
-
-            self.render_root
-                .calculate_layout(BoxConstraints::tight_for(self.size.w, self.size.h));
+            MediaQuery::set(MediaQueryData { size: self.size });
+            self.damage.add_everything();
         }
 
+        // Every object is visited to find what changed, but only the parts of the tree that
+        // changed, or got new constraints, are laid out again
+        self.render_root.propagate_needs_layout();
+        self.render_root
+            .layout(BoxConstraints::tight_for(self.size.w, self.size.h));
+        self.render_root.flush_layout();
+
+        let w = &mut self.window;
         let mpos = if let Some((mx, my)) = w.get_mouse_pos(minifb::MouseMode::Clamp) {
             let (mx, my) = (mx as usize, my as usize);
             let mz = self.buffer.depth[mx + my * self.buffer.width];
@@ -102,8 +191,8 @@ impl App {
         let mright = w.get_mouse_down(minifb::MouseButton::Right);
         let mmiddle = w.get_mouse_down(minifb::MouseButton::Middle);
         let mscroll = w.get_scroll_wheel().unwrap_or((0.0, 0.0)).1;
-        self.mouse.update(mpos, mleft, mright, mmiddle, mscroll);
-
-        self.buffer.clear(0xFFFFFFFF, 0);
+        self.mouse = self.mouse.update(mpos, mleft, mright, mmiddle, mscroll);
+        self.render_root.handle_mouse_event(&self.mouse);
+        changed
     }
 }
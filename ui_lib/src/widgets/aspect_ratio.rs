@@ -0,0 +1,31 @@
+use crate::rendering::render_object::AspectRatioRenderObject;
+
+use super::Widget;
+
+// Takes the largest size with the given width / height ratio that the constraints allow
+pub struct AspectRatio {
+    child: Option<Box<dyn Widget>>,
+    aspect_ratio: f32,
+}
+
+impl AspectRatio {
+    pub fn new(aspect_ratio: f32, child: Option<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            aspect_ratio,
+        }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for AspectRatio {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(AspectRatioRenderObject::new(
+            self.child.as_ref().map(|c| c.create_render_object()),
+            self.aspect_ratio,
+        ))
+    }
+}
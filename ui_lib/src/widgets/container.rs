@@ -0,0 +1,57 @@
+use crate::{
+    rendering::render_object::{ContainerRenderObject, ContainerStyle},
+    types::{Alignment, BoxConstraints, Color, Insets},
+};
+
+use super::Widget;
+
+pub struct Container {
+    child: Option<Box<dyn Widget>>,
+    style: ContainerStyle,
+}
+
+impl Container {
+    pub fn new(child: Option<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            style: ContainerStyle::default(),
+        }
+    }
+
+    pub fn color(mut self, color: impl Into<Color>) -> Self {
+        self.style.color = color.into();
+        self
+    }
+
+    pub fn constraints(mut self, constraints: BoxConstraints) -> Self {
+        self.style.constraints = Some(constraints);
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.style.alignment = Some(alignment);
+        self
+    }
+
+    pub fn padding(mut self, padding: Insets) -> Self {
+        self.style.padding = padding;
+        self
+    }
+
+    pub fn style(&self) -> ContainerStyle {
+        self.style.clone()
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Container {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(ContainerRenderObject::new(
+            self.child.as_ref().map(|c| c.create_render_object()),
+            self.style.clone(),
+        ))
+    }
+}
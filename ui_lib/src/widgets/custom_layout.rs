@@ -0,0 +1,95 @@
+use std::rc::Rc;
+
+use crate::rendering::render_object::{
+    CustomMultiChildLayoutRenderObject, FlowDelegate, FlowRenderObject, MultiChildLayoutDelegate,
+};
+
+use super::Widget;
+
+// Lets a delegate size and place the children, which are given ids with `LayoutId`
+pub struct CustomMultiChildLayout {
+    delegate: Rc<dyn MultiChildLayoutDelegate>,
+    children: Vec<Box<dyn Widget>>,
+}
+
+impl CustomMultiChildLayout {
+    pub fn new(
+        delegate: impl MultiChildLayoutDelegate + 'static,
+        children: Vec<Box<dyn Widget>>,
+    ) -> Self {
+        Self {
+            delegate: Rc::new(delegate),
+            children,
+        }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for CustomMultiChildLayout {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(CustomMultiChildLayoutRenderObject::new(
+            self.children
+                .iter()
+                .map(|c| c.create_render_object())
+                .collect(),
+            self.delegate.clone(),
+        ))
+    }
+}
+
+pub struct LayoutId {
+    child: Box<dyn Widget>,
+    id: usize,
+}
+
+impl LayoutId {
+    pub fn new(id: usize, child: Box<dyn Widget>) -> Self {
+        Self { child, id }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for LayoutId {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        let mut ro = self.child.create_render_object();
+        ro.set_prop_usize("layout_id", self.id);
+        ro
+    }
+}
+
+// Lets a delegate place the children when painting, for layouts that move a lot
+pub struct Flow {
+    delegate: Rc<dyn FlowDelegate>,
+    children: Vec<Box<dyn Widget>>,
+}
+
+impl Flow {
+    pub fn new(delegate: impl FlowDelegate + 'static, children: Vec<Box<dyn Widget>>) -> Self {
+        Self {
+            delegate: Rc::new(delegate),
+            children,
+        }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Flow {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(FlowRenderObject::new(
+            self.children
+                .iter()
+                .map(|c| c.create_render_object())
+                .collect(),
+            self.delegate.clone(),
+        ))
+    }
+}
@@ -0,0 +1,64 @@
+use std::rc::Rc;
+
+use crate::{
+    inputs::MouseStatus,
+    rendering::render_object::{CustomPaintRenderObject, PaintMouseCallback, Painter},
+    types::{Position, Size},
+};
+
+use super::Widget;
+
+// Lets a painter draw behind the child, or in a box of the given size without one
+pub struct CustomPaint {
+    painter: Rc<dyn Painter>,
+    child: Option<Box<dyn Widget>>,
+    size: Size,
+    on_mouse: Option<PaintMouseCallback>,
+}
+
+impl CustomPaint {
+    pub fn new(painter: impl Painter + 'static) -> Self {
+        Self::shared(Rc::new(painter))
+    }
+
+    // To share a painter whose data changes with the code changing it
+    pub fn shared(painter: Rc<dyn Painter>) -> Self {
+        Self {
+            painter,
+            child: None,
+            size: Size::default(),
+            on_mouse: None,
+        }
+    }
+
+    pub fn child(mut self, child: Box<dyn Widget>) -> Self {
+        self.child = Some(child);
+        self
+    }
+
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    // Called while the mouse is over what the painter hit tests
+    pub fn on_mouse(mut self, on_mouse: impl Fn(Position, &MouseStatus) + 'static) -> Self {
+        self.on_mouse = Some(Rc::new(on_mouse));
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for CustomPaint {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(CustomPaintRenderObject::new(
+            self.painter.clone(),
+            self.child.as_ref().map(|c| c.create_render_object()),
+            self.size,
+            self.on_mouse.clone(),
+        ))
+    }
+}
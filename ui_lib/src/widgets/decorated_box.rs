@@ -0,0 +1,68 @@
+use crate::{
+    rendering::{
+        render_object::{BoxDecoration, DecoratedBoxRenderObject},
+        BoxShadow, Gradient,
+    },
+    types::Color,
+};
+
+use super::Widget;
+
+// Paints a decoration behind its child, the size of the child
+pub struct DecoratedBox {
+    child: Option<Box<dyn Widget>>,
+    decoration: BoxDecoration,
+}
+
+impl DecoratedBox {
+    pub fn new(child: Option<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            decoration: BoxDecoration::default(),
+        }
+    }
+
+    pub fn decoration(mut self, decoration: BoxDecoration) -> Self {
+        self.decoration = decoration;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<Color>) -> Self {
+        self.decoration.color = color.into();
+        self
+    }
+
+    // Positions of the gradient are fractions of the box
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.decoration.gradient = Some(gradient);
+        self
+    }
+
+    pub fn border_radius(mut self, radius: f32) -> Self {
+        self.decoration.border_radius = radius;
+        self
+    }
+
+    pub fn shadow(mut self, shadow: BoxShadow) -> Self {
+        self.decoration.shadows.push(shadow);
+        self
+    }
+
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.decoration.dither = dither;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for DecoratedBox {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(DecoratedBoxRenderObject::new(
+            self.child.as_ref().map(|c| c.create_render_object()),
+            self.decoration.clone(),
+        ))
+    }
+}
@@ -1,8 +1,12 @@
+use crate::{rendering::BoxShadow, types::Color};
+
 use super::Widget;
 
 pub struct Elevate {
     child: Box<dyn Widget>,
     elevation: usize,
+    shadow: Option<Color>,
+    shadow_radius: f32,
 }
 
 impl Elevate {
@@ -10,9 +14,27 @@ impl Elevate {
         Self {
             child: c,
             elevation: e,
+            shadow: None,
+            shadow_radius: 0.0,
         }
     }
 
+    // Casts a shadow under the child, softer and further the higher the elevation
+    pub fn shadow(self) -> Self {
+        self.shadow_color(BoxShadow::DEFAULT_COLOR)
+    }
+
+    pub fn shadow_color(mut self, color: impl Into<Color>) -> Self {
+        self.shadow = Some(color.into());
+        self
+    }
+
+    // Rounds the corners of the shadow, to match a rounded child
+    pub fn shadow_radius(mut self, radius: f32) -> Self {
+        self.shadow_radius = radius;
+        self
+    }
+
     pub fn b(self) -> Box<Self> {
         Box::new(self)
     }
@@ -22,6 +44,10 @@ impl Widget for Elevate {
     fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
         let mut ro = self.child.create_render_object();
         ro.set_prop_usize("elevation", self.elevation);
+        if let Some(color) = self.shadow {
+            ro.set_prop_u32("shadow_color", ((color.a as u32) << 24) | color.to_u32());
+            ro.set_prop_f32("shadow_radius", self.shadow_radius);
+        }
         ro
     }
 }
@@ -16,6 +16,7 @@ pub struct Flex {
     vertical_direction: VerticalDirection,
     text_baseline: Option<()>,
     clip_behavior: Option<()>,
+    spacing: f32,
     children: Vec<Box<dyn Widget>>,
 }
 
@@ -31,6 +32,7 @@ impl Flex {
             vertical_direction: VerticalDirection::Down,
             text_baseline: None,
             clip_behavior: None,
+            spacing: 0.0,
         }
     }
 
@@ -59,6 +61,12 @@ impl Flex {
         self
     }
 
+    // Gap between two children, on top of the one from the alignment
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
     pub fn b(self) -> Box<Self> {
         Box::new(self)
     }
@@ -66,20 +74,23 @@ impl Flex {
 
 impl Widget for Flex {
     fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
-        Box::new(RenderFlex::new(
-            self.children
-                .iter()
-                .map(|c| c.create_render_object())
-                .collect(),
-            self.direction,
-            self.main_axis_size,
-            self.main_axis_alignment,
-            self.cross_axis_alignment,
-            self.text_direction,
-            self.vertical_direction,
-            self.text_baseline,
-            self.clip_behavior,
-        ))
+        Box::new(
+            RenderFlex::new(
+                self.children
+                    .iter()
+                    .map(|c| c.create_render_object())
+                    .collect(),
+                self.direction,
+            )
+            .main_axis_size(self.main_axis_size)
+            .main_axis_alignment(self.main_axis_alignment)
+            .cross_axis_alignment(self.cross_axis_alignment)
+            .text_direction(self.text_direction)
+            .vertical_direction(self.vertical_direction)
+            .text_baseline(self.text_baseline)
+            .clip_behavior(self.clip_behavior)
+            .spacing(self.spacing),
+        )
     }
 }
 
@@ -5,6 +5,8 @@ pub struct Flexible {
     child: Box<dyn Widget>,
     flex: i32,
     fit: FlexFit,
+    min_extent: Option<f32>,
+    max_extent: Option<f32>,
 }
 
 impl Flexible {
@@ -13,6 +15,8 @@ impl Flexible {
             child,
             flex: 1,
             fit,
+            min_extent: None,
+            max_extent: None,
         }
     }
 
@@ -21,6 +25,18 @@ impl Flexible {
         self
     }
 
+    // Bounds of the child's size along the main axis of the `Flex`, the space it can't take
+    // goes to the other flexible children
+    pub fn min_extent(mut self, min: f32) -> Self {
+        self.min_extent = Some(min);
+        self
+    }
+
+    pub fn max_extent(mut self, max: f32) -> Self {
+        self.max_extent = Some(max);
+        self
+    }
+
     pub fn b(self) -> Box<Self> {
         Box::new(self)
     }
@@ -31,6 +47,12 @@ impl Widget for Flexible {
         let mut ro = self.child.create_render_object();
         ro.set_prop_i32("flex", self.flex);
         ro.set_prop_i32("fit", self.fit.into());
+        if let Some(min) = self.min_extent {
+            ro.set_prop_f32("flex_min", min);
+        }
+        if let Some(max) = self.max_extent {
+            ro.set_prop_f32("flex_max", max);
+        }
         ro
     }
 }
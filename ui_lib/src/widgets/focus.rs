@@ -0,0 +1,102 @@
+use std::rc::Rc;
+
+use crate::{
+    inputs::{FocusNode, KeyEvent},
+    rendering::render_object::{FocusRenderObject, FocusScopeRenderObject, KeyCallback},
+    types::Color,
+};
+
+use super::Widget;
+
+pub struct Focus {
+    child: Box<dyn Widget>,
+    node: FocusNode,
+    on_key: Option<KeyCallback>,
+    focus_ring: Option<Color>,
+    autofocus: bool,
+}
+
+impl Focus {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            child,
+            node: FocusNode::new(),
+            on_key: None,
+            focus_ring: Some(Color::from(0x3B82F6)),
+            autofocus: false,
+        }
+    }
+
+    pub fn focus_node(mut self, node: FocusNode) -> Self {
+        self.node = node;
+        self
+    }
+
+    // Called with the key events that reach this widget while it has the focus
+    pub fn on_key(mut self, on_key: impl Fn(&KeyEvent) -> bool + 'static) -> Self {
+        self.on_key = Some(Rc::new(on_key));
+        self
+    }
+
+    pub fn focus_ring(mut self, color: Option<Color>) -> Self {
+        self.focus_ring = color;
+        self
+    }
+
+    pub fn autofocus(mut self) -> Self {
+        self.autofocus = true;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Focus {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        if self.autofocus {
+            self.node.request_focus();
+        }
+        Box::new(FocusRenderObject::new(
+            self.child.create_render_object(),
+            self.node.clone(),
+            self.on_key.clone(),
+            self.focus_ring,
+        ))
+    }
+}
+
+pub struct FocusScope {
+    child: Box<dyn Widget>,
+    node: FocusNode,
+}
+
+impl FocusScope {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            child,
+            node: FocusNode::scope(),
+        }
+    }
+
+    // `node` must have been created with `FocusNode::scope`
+    pub fn node(mut self, node: FocusNode) -> Self {
+        assert!(node.is_scope());
+        self.node = node;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for FocusScope {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(FocusScopeRenderObject::new(
+            self.child.create_render_object(),
+            self.node.clone(),
+        ))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{rendering::render_object::FractionallySizedRenderObject, types::Alignment};
+
+use super::Widget;
+
+// Sizes its child to a fraction of the maximum size it is given
+pub struct FractionallySizedBox {
+    child: Option<Box<dyn Widget>>,
+    width_factor: Option<f32>,
+    height_factor: Option<f32>,
+    alignment: Alignment,
+}
+
+impl FractionallySizedBox {
+    pub fn new(child: Option<Box<dyn Widget>>) -> Self {
+        Self {
+            child,
+            width_factor: None,
+            height_factor: None,
+            alignment: Alignment::Center,
+        }
+    }
+
+    pub fn width_factor(mut self, width_factor: f32) -> Self {
+        self.width_factor = Some(width_factor);
+        self
+    }
+
+    pub fn height_factor(mut self, height_factor: f32) -> Self {
+        self.height_factor = Some(height_factor);
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for FractionallySizedBox {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(FractionallySizedRenderObject::new(
+            self.child.as_ref().map(|c| c.create_render_object()),
+            self.width_factor,
+            self.height_factor,
+            self.alignment,
+        ))
+    }
+}
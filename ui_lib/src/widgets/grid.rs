@@ -0,0 +1,142 @@
+use crate::{
+    rendering::render_object::RenderGrid,
+    types::{Alignment, TrackSize},
+};
+
+use super::Widget;
+
+// Lays its children on rows and columns shared by all of them. Children are placed with
+// `GridCell`, the others fill the free cells in reading order.
+pub struct Grid {
+    columns: Vec<TrackSize>,
+    rows: Vec<TrackSize>,
+    column_gap: f32,
+    row_gap: f32,
+    children: Vec<Box<dyn Widget>>,
+}
+
+impl Grid {
+    pub fn new(children: Vec<Box<dyn Widget>>) -> Self {
+        Self {
+            columns: vec![TrackSize::Fr(1.0)],
+            rows: Vec::new(),
+            column_gap: 0.0,
+            row_gap: 0.0,
+            children,
+        }
+    }
+
+    pub fn columns(mut self, columns: Vec<TrackSize>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    // Rows past these ones are `TrackSize::Auto`
+    pub fn rows(mut self, rows: Vec<TrackSize>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    pub fn column_gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    pub fn row_gap(mut self, gap: f32) -> Self {
+        self.row_gap = gap;
+        self
+    }
+
+    pub fn gap(self, gap: f32) -> Self {
+        self.column_gap(gap).row_gap(gap)
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Grid {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(RenderGrid::new(
+            self.children
+                .iter()
+                .map(|c| c.create_render_object())
+                .collect(),
+            self.columns.clone(),
+            self.rows.clone(),
+            self.column_gap,
+            self.row_gap,
+        ))
+    }
+}
+
+// Where a child of a `Grid` goes. Without an alignment, the child is stretched over its cells.
+// Given only a column or a row, the child takes the first free cell along it.
+pub struct GridCell {
+    child: Box<dyn Widget>,
+    column: Option<usize>,
+    row: Option<usize>,
+    span: (usize, usize),
+    alignment: Option<Alignment>,
+}
+
+impl GridCell {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            child,
+            column: None,
+            row: None,
+            span: (1, 1),
+            alignment: None,
+        }
+    }
+
+    pub fn at(self, column: usize, row: usize) -> Self {
+        self.column(column).row(row)
+    }
+
+    pub fn column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn row(mut self, row: usize) -> Self {
+        self.row = Some(row);
+        self
+    }
+
+    pub fn span(mut self, columns: usize, rows: usize) -> Self {
+        self.span = (columns, rows);
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for GridCell {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        let mut ro = self.child.create_render_object();
+        if let Some(column) = self.column {
+            ro.set_prop_usize("grid_column", column);
+        }
+        if let Some(row) = self.row {
+            ro.set_prop_usize("grid_row", row);
+        }
+        ro.set_prop_usize("grid_column_span", self.span.0);
+        ro.set_prop_usize("grid_row_span", self.span.1);
+        if let Some(alignment) = self.alignment {
+            let (x, y) = alignment.loc();
+            ro.set_prop_f32("grid_align_x", x);
+            ro.set_prop_f32("grid_align_y", y);
+        }
+        ro
+    }
+}
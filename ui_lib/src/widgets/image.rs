@@ -0,0 +1,86 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    rendering::{image_cache, render_object::ImageRenderObject, FilterQuality, ImageError},
+    types::{Alignment, BoxFit},
+};
+
+use super::Widget;
+
+// Shows decoded pixels, sized like the image unless given a width or height
+pub struct Image {
+    image: Option<Arc<crate::rendering::Image>>,
+    width: Option<f32>,
+    height: Option<f32>,
+    fit: BoxFit,
+    alignment: Alignment,
+    filter: FilterQuality,
+}
+
+impl Image {
+    pub fn new(image: Arc<crate::rendering::Image>) -> Self {
+        Self::from_option(Some(image))
+    }
+
+    // Read through the image cache. Draws nothing when the file can't be decoded.
+    pub fn file(path: impl AsRef<Path>) -> Self {
+        Self::from_option(image_cache().borrow_mut().load(path).ok())
+    }
+
+    pub fn try_file(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        Ok(Self::new(image_cache().borrow_mut().load(path)?))
+    }
+
+    fn from_option(image: Option<Arc<crate::rendering::Image>>) -> Self {
+        Self {
+            image,
+            width: None,
+            height: None,
+            fit: BoxFit::Contain,
+            alignment: Alignment::Center,
+            filter: FilterQuality::Nearest,
+        }
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn fit(mut self, fit: BoxFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn filter(mut self, filter: FilterQuality) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Image {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(ImageRenderObject::new(
+            self.image.clone(),
+            self.width,
+            self.height,
+            self.fit,
+            self.alignment,
+            self.filter,
+        ))
+    }
+}
@@ -0,0 +1,71 @@
+use crate::rendering::render_object::{IntrinsicHeightRenderObject, IntrinsicWidthRenderObject};
+
+use super::Widget;
+
+// Makes its child as wide as it would like to be, e.g. to give the children of a `Column`
+// the width of the widest one. Costly: the child's subtree is walked an extra time.
+pub struct IntrinsicWidth {
+    child: Box<dyn Widget>,
+    step_width: Option<f32>,
+    step_height: Option<f32>,
+}
+
+impl IntrinsicWidth {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            child,
+            step_width: None,
+            step_height: None,
+        }
+    }
+
+    // Rounds the width up to a multiple of `step`
+    pub fn step_width(mut self, step: f32) -> Self {
+        self.step_width = Some(step);
+        self
+    }
+
+    // Also sizes the height to the intrinsic one, rounded up to a multiple of `step`
+    pub fn step_height(mut self, step: f32) -> Self {
+        self.step_height = Some(step);
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for IntrinsicWidth {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(IntrinsicWidthRenderObject::new(
+            self.child.create_render_object(),
+            self.step_width,
+            self.step_height,
+        ))
+    }
+}
+
+// Makes its child as tall as it would like to be, e.g. to give the children of a `Row` the
+// height of the tallest one
+pub struct IntrinsicHeight {
+    child: Box<dyn Widget>,
+}
+
+impl IntrinsicHeight {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self { child }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for IntrinsicHeight {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(IntrinsicHeightRenderObject::new(
+            self.child.create_render_object(),
+        ))
+    }
+}
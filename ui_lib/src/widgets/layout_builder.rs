@@ -0,0 +1,32 @@
+use std::rc::Rc;
+
+use crate::{
+    rendering::render_object::{LayoutBuilderRenderObject, LayoutWidgetBuilder},
+    types::BoxConstraints,
+};
+
+use super::Widget;
+
+// Builds its child during layout, from the constraints it is given. The child is built again,
+// losing its state, each time the constraints change.
+pub struct LayoutBuilder {
+    builder: LayoutWidgetBuilder,
+}
+
+impl LayoutBuilder {
+    pub fn new(builder: impl Fn(&BoxConstraints) -> Box<dyn Widget> + 'static) -> Self {
+        Self {
+            builder: Rc::new(builder),
+        }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for LayoutBuilder {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(LayoutBuilderRenderObject::new(self.builder.clone()))
+    }
+}
@@ -0,0 +1,43 @@
+use std::cell::Cell;
+
+use crate::types::Size;
+
+// What the app knows about the window, kept up to date by `App`
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct MediaQueryData {
+    pub size: Size,
+}
+
+thread_local! {
+    static DATA: Cell<MediaQueryData> = Cell::new(MediaQueryData::default());
+}
+
+pub struct MediaQuery;
+
+impl MediaQuery {
+    pub fn of() -> MediaQueryData {
+        DATA.with(|d| d.get())
+    }
+
+    // Size of the window
+    pub fn size() -> Size {
+        Self::of().size
+    }
+
+    pub(crate) fn set(data: MediaQueryData) {
+        DATA.with(|d| d.set(data));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_what_the_app_set() {
+        assert_eq!(MediaQuery::size(), Size::default());
+        let size = Size { w: 640.0, h: 480.0 };
+        MediaQuery::set(MediaQueryData { size });
+        assert_eq!(MediaQuery::size(), size);
+    }
+}
@@ -1,25 +1,69 @@
 use crate::rendering::render_object::RenderObject;
 
 pub mod align;
+pub mod animated;
 pub mod app;
+pub mod aspect_ratio;
 pub mod blob;
 pub mod constrained;
+pub mod container;
+pub mod custom_layout;
+pub mod custom_paint;
+pub mod decorated_box;
 pub mod elevate;
 pub mod flex;
 pub mod flexible;
+pub mod focus;
+pub mod fractionally_sized;
+pub mod grid;
+pub mod image;
+pub mod intrinsic;
+pub mod layout_builder;
 pub mod list;
+pub mod media_query;
+pub mod nine_slice;
+pub mod opacity;
 pub mod position;
+pub mod repaint_boundary;
+pub mod shortcuts;
 pub mod spacer;
+pub mod table;
+pub mod text_field;
+pub mod transform;
+pub mod wrap;
 
 pub use align::{Align, Center};
+pub use animated::{
+    AnimatedAlign, AnimatedContainer, AnimatedOpacity, AnimatedPositioned, AnimatedSize,
+};
 pub use app::App;
+pub use aspect_ratio::AspectRatio;
 pub use blob::Blob;
 pub use constrained::{ConstrainedBox, LimitedBox, SizedBox};
+pub use container::Container;
+pub use custom_layout::{CustomMultiChildLayout, Flow, LayoutId};
+pub use custom_paint::CustomPaint;
+pub use decorated_box::DecoratedBox;
 pub use elevate::Elevate;
 pub use flex::{Column, Flex, Row};
 pub use flexible::{Expanded, Flexible};
+pub use focus::{Focus, FocusScope};
+pub use fractionally_sized::FractionallySizedBox;
+pub use grid::{Grid, GridCell};
+pub use image::Image;
+pub use intrinsic::{IntrinsicHeight, IntrinsicWidth};
+pub use layout_builder::LayoutBuilder;
 pub use list::WidgetList;
+pub use media_query::{MediaQuery, MediaQueryData};
+pub use nine_slice::NineSlice;
+pub use opacity::Opacity;
 pub use position::PositionBox;
+pub use repaint_boundary::RepaintBoundary;
+pub use shortcuts::{Actions, KeyboardListener, Shortcuts};
+pub use table::{Table, TableCell};
+pub use text_field::TextField;
+pub use transform::Transform;
+pub use wrap::Wrap;
 
 pub trait Widget {
     fn create_render_object(&self) -> Box<dyn RenderObject>;
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use crate::{
+    rendering::{render_object::NineSliceRenderObject, FilterQuality, Image},
+    types::Insets,
+};
+
+use super::Widget;
+
+// Draws a skin whose corners, `insets` wide in pixels of the image, keep their size while
+// the rest stretches to the box. A child goes inside of the insets.
+pub struct NineSlice {
+    image: Arc<Image>,
+    insets: Insets,
+    filter: FilterQuality,
+    child: Option<Box<dyn Widget>>,
+}
+
+impl NineSlice {
+    pub fn new(image: Arc<Image>, insets: Insets) -> Self {
+        Self {
+            image,
+            insets,
+            filter: FilterQuality::Nearest,
+            child: None,
+        }
+    }
+
+    pub fn child(mut self, child: Box<dyn Widget>) -> Self {
+        self.child = Some(child);
+        self
+    }
+
+    pub fn filter(mut self, filter: FilterQuality) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for NineSlice {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(NineSliceRenderObject::new(
+            &self.image,
+            self.insets,
+            self.filter,
+            self.child.as_ref().map(|c| c.create_render_object()),
+        ))
+    }
+}
@@ -0,0 +1,27 @@
+use crate::rendering::render_object::OpacityRenderObject;
+
+use super::Widget;
+
+pub struct Opacity {
+    child: Box<dyn Widget>,
+    opacity: f32,
+}
+
+impl Opacity {
+    pub fn new(child: Box<dyn Widget>, opacity: f32) -> Self {
+        Self { child, opacity }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Opacity {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(OpacityRenderObject::new(
+            self.child.create_render_object(),
+            self.opacity,
+        ))
+    }
+}
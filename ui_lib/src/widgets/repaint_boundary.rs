@@ -0,0 +1,27 @@
+use crate::rendering::render_object::RepaintBoundaryRenderObject;
+
+use super::Widget;
+
+// Keeps its child drawn in its own buffer, so heavy content that rarely changes is only
+// composited when what is around it moves, fades or gets repainted
+pub struct RepaintBoundary {
+    child: Box<dyn Widget>,
+}
+
+impl RepaintBoundary {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self { child }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for RepaintBoundary {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(RepaintBoundaryRenderObject::new(
+            self.child.create_render_object(),
+        ))
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    inputs::{FocusNode, Intent, KeyChord, KeyEvent},
+    rendering::render_object::{
+        ActionCallback, ActionsRenderObject, FocusRenderObject, KeyCallback,
+        KeyboardListenerRenderObject, ShortcutsRenderObject,
+    },
+};
+
+use super::Widget;
+
+// Receives the key events that no descendant handled. With a focus node, only the events
+// sent while that node has the focus.
+pub struct KeyboardListener {
+    child: Box<dyn Widget>,
+    on_key: KeyCallback,
+    focus_node: Option<FocusNode>,
+}
+
+impl KeyboardListener {
+    pub fn new(child: Box<dyn Widget>, on_key: impl Fn(&KeyEvent) -> bool + 'static) -> Self {
+        Self {
+            child,
+            on_key: Rc::new(on_key),
+            focus_node: None,
+        }
+    }
+
+    pub fn focus_node(mut self, node: FocusNode) -> Self {
+        self.focus_node = Some(node);
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for KeyboardListener {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        match self.focus_node {
+            Some(ref node) => Box::new(FocusRenderObject::new(
+                self.child.create_render_object(),
+                node.clone(),
+                Some(self.on_key.clone()),
+                None,
+            )),
+            None => Box::new(KeyboardListenerRenderObject::new(
+                self.child.create_render_object(),
+                self.on_key.clone(),
+            )),
+        }
+    }
+}
+
+// Turns key chords into intents, which are then run by the closest `Actions` ancestor
+pub struct Shortcuts {
+    child: Box<dyn Widget>,
+    bindings: HashMap<KeyChord, Intent>,
+}
+
+impl Shortcuts {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            child,
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn bind(mut self, chord: KeyChord, intent: Intent) -> Self {
+        self.bindings.insert(chord, intent);
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Shortcuts {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(ShortcutsRenderObject::new(
+            self.child.create_render_object(),
+            self.bindings.clone(),
+        ))
+    }
+}
+
+pub struct Actions {
+    child: Box<dyn Widget>,
+    actions: HashMap<Intent, ActionCallback>,
+}
+
+impl Actions {
+    pub fn new(child: Box<dyn Widget>) -> Self {
+        Self {
+            child,
+            actions: HashMap::new(),
+        }
+    }
+
+    pub fn on(mut self, intent: Intent, action: impl Fn() + 'static) -> Self {
+        self.actions.insert(intent, Rc::new(action));
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Actions {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(ActionsRenderObject::new(
+            self.child.create_render_object(),
+            self.actions.clone(),
+        ))
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{
+    rendering::render_object::RenderTable,
+    types::{TableBorder, TableCellVerticalAlignment, TableColumnWidth},
+};
+
+use super::Widget;
+
+// Rows of cells whose columns line up, each row being as tall as its tallest cell
+pub struct Table {
+    rows: Vec<Vec<Box<dyn Widget>>>,
+    column_widths: Vec<TableColumnWidth>,
+    default_column_width: TableColumnWidth,
+    default_vertical_alignment: TableCellVerticalAlignment,
+    border: Option<TableBorder>,
+}
+
+impl Table {
+    pub fn new(rows: Vec<Vec<Box<dyn Widget>>>) -> Self {
+        Self {
+            rows,
+            column_widths: Vec::new(),
+            default_column_width: TableColumnWidth::Flex(1.0),
+            default_vertical_alignment: TableCellVerticalAlignment::Top,
+            border: None,
+        }
+    }
+
+    // Widths of the first columns, the others use the default one
+    pub fn column_widths(mut self, widths: Vec<TableColumnWidth>) -> Self {
+        self.column_widths = widths;
+        self
+    }
+
+    pub fn default_column_width(mut self, width: TableColumnWidth) -> Self {
+        self.default_column_width = width;
+        self
+    }
+
+    pub fn default_vertical_alignment(mut self, a: TableCellVerticalAlignment) -> Self {
+        self.default_vertical_alignment = a;
+        self
+    }
+
+    pub fn border(mut self, border: TableBorder) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Table {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(RenderTable::new(
+            self.rows
+                .iter()
+                .map(|r| r.iter().map(|c| c.create_render_object()).collect())
+                .collect(),
+            self.column_widths.clone(),
+            self.default_column_width,
+            self.default_vertical_alignment,
+            self.border,
+        ))
+    }
+}
+
+// Overrides the vertical alignment of a cell of a `Table`
+pub struct TableCell {
+    child: Box<dyn Widget>,
+    vertical_alignment: TableCellVerticalAlignment,
+}
+
+impl TableCell {
+    pub fn new(child: Box<dyn Widget>, vertical_alignment: TableCellVerticalAlignment) -> Self {
+        Self {
+            child,
+            vertical_alignment,
+        }
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for TableCell {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        let mut ro = self.child.create_render_object();
+        ro.set_prop_i32("table_vertical_alignment", self.vertical_alignment.into());
+        ro
+    }
+}
@@ -0,0 +1,115 @@
+use std::rc::Rc;
+
+use crate::{
+    inputs::{clipboard, clipboard::SharedClipboard, FocusNode},
+    rendering::render_object::{TextCallback, TextFieldRenderObject, TextFieldStyle},
+    types::ValueNotifier,
+};
+
+use super::Widget;
+
+// Editable text. The text lives in the notifier, which the application can read and set.
+pub struct TextField {
+    text: ValueNotifier<String>,
+    node: FocusNode,
+    clipboard: Option<SharedClipboard>,
+    placeholder: String,
+    obscure: bool,
+    multiline: Option<usize>,
+    style: TextFieldStyle,
+    on_changed: Option<TextCallback>,
+    on_submitted: Option<TextCallback>,
+    autofocus: bool,
+}
+
+impl TextField {
+    pub fn new(text: impl Into<ValueNotifier<String>>) -> Self {
+        Self {
+            text: text.into(),
+            node: FocusNode::new(),
+            clipboard: None,
+            placeholder: String::new(),
+            obscure: false,
+            multiline: None,
+            style: TextFieldStyle::default(),
+            on_changed: None,
+            on_submitted: None,
+            autofocus: false,
+        }
+    }
+
+    pub fn focus_node(mut self, node: FocusNode) -> Self {
+        self.node = node;
+        self
+    }
+
+    // Defaults to a clipboard shared by all the fields of the thread
+    pub fn clipboard(mut self, clipboard: SharedClipboard) -> Self {
+        self.clipboard = Some(clipboard);
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    // Shows every character as '*', for passwords
+    pub fn obscure(mut self) -> Self {
+        self.obscure = true;
+        self
+    }
+
+    // Enter inserts new lines, `lines` being the number of visible lines
+    pub fn multiline(mut self, lines: usize) -> Self {
+        self.multiline = Some(lines);
+        self
+    }
+
+    pub fn style(mut self, style: TextFieldStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn on_changed(mut self, on_changed: impl Fn(&str) + 'static) -> Self {
+        self.on_changed = Some(Rc::new(on_changed));
+        self
+    }
+
+    // Called when Enter is pressed in a single line field
+    pub fn on_submitted(mut self, on_submitted: impl Fn(&str) + 'static) -> Self {
+        self.on_submitted = Some(Rc::new(on_submitted));
+        self
+    }
+
+    pub fn autofocus(mut self) -> Self {
+        self.autofocus = true;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for TextField {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        if self.autofocus {
+            self.node.request_focus();
+        }
+        let mut render_object = TextFieldRenderObject::new(
+            self.text.clone(),
+            self.node.clone(),
+            self.clipboard
+                .clone()
+                .unwrap_or_else(clipboard::default_clipboard),
+            self.multiline,
+        );
+        render_object.set_placeholder(&self.placeholder);
+        render_object.set_obscure(self.obscure);
+        render_object.set_style(self.style);
+        render_object.set_on_changed(self.on_changed.clone());
+        render_object.set_on_submitted(self.on_submitted.clone());
+        Box::new(render_object)
+    }
+}
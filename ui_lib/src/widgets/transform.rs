@@ -0,0 +1,81 @@
+use crate::{
+    rendering::{render_object::TransformRenderObject, FilterQuality},
+    types::{Alignment, Matrix, Position},
+};
+
+use super::Widget;
+
+// Draws its child rotated, scaled, skewed or moved without changing the layout. The mouse
+// still reaches the child where it is shown.
+pub struct Transform {
+    child: Box<dyn Widget>,
+    matrix: Matrix,
+    origin: Position,
+    alignment: Option<Alignment>,
+    filter: FilterQuality,
+}
+
+impl Transform {
+    // Around the top left corner of the child
+    pub fn new(child: Box<dyn Widget>, matrix: Matrix) -> Self {
+        Self {
+            child,
+            matrix,
+            origin: Position::default(),
+            alignment: None,
+            filter: FilterQuality::Bilinear,
+        }
+    }
+
+    // Clockwise, around the center
+    pub fn rotate(child: Box<dyn Widget>, radians: f32) -> Self {
+        Self::new(child, Matrix::rotation(radians)).alignment(Alignment::Center)
+    }
+
+    // Around the center
+    pub fn scale(child: Box<dyn Widget>, sx: f32, sy: f32) -> Self {
+        Self::new(child, Matrix::scale(sx, sy)).alignment(Alignment::Center)
+    }
+
+    pub fn translate(child: Box<dyn Widget>, offset: Position) -> Self {
+        Self::new(child, Matrix::translation(offset.x, offset.y))
+    }
+
+    // Around the center, angles in radians
+    pub fn skew(child: Box<dyn Widget>, x: f32, y: f32) -> Self {
+        Self::new(child, Matrix::skew(x, y)).alignment(Alignment::Center)
+    }
+
+    // What stays in place, from the top left corner of the child, or from the alignment
+    // point when there is one
+    pub fn origin(mut self, origin: Position) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    pub fn filter(mut self, filter: FilterQuality) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Transform {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(TransformRenderObject::new(
+            self.child.create_render_object(),
+            self.matrix,
+            self.origin,
+            self.alignment,
+            self.filter,
+        ))
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    rendering::render_object::RenderWrap,
+    types::{Axis, MainAxisAlignment, TextDirection, VerticalDirection, WrapCrossAlignment},
+};
+
+use super::Widget;
+
+// Like a `Flex`, but starts a new run when the next child doesn't fit on the current one
+pub struct Wrap {
+    direction: Axis,
+    alignment: MainAxisAlignment,
+    spacing: f32,
+    run_alignment: MainAxisAlignment,
+    run_spacing: f32,
+    cross_alignment: WrapCrossAlignment,
+    text_direction: TextDirection,
+    vertical_direction: VerticalDirection,
+    children: Vec<Box<dyn Widget>>,
+}
+
+impl Wrap {
+    pub fn new(children: Vec<Box<dyn Widget>>) -> Self {
+        Self {
+            direction: Axis::Horizontal,
+            alignment: MainAxisAlignment::Start,
+            spacing: 0.0,
+            run_alignment: MainAxisAlignment::Start,
+            run_spacing: 0.0,
+            cross_alignment: WrapCrossAlignment::Start,
+            text_direction: TextDirection::Ltr,
+            vertical_direction: VerticalDirection::Down,
+            children,
+        }
+    }
+
+    pub fn direction(mut self, d: Axis) -> Self {
+        self.direction = d;
+        self
+    }
+
+    // Placement of the children within a run
+    pub fn alignment(mut self, a: MainAxisAlignment) -> Self {
+        self.alignment = a;
+        self
+    }
+
+    // Gap between the children of a run
+    pub fn spacing(mut self, s: f32) -> Self {
+        self.spacing = s;
+        self
+    }
+
+    // Placement of the runs along the cross axis
+    pub fn run_alignment(mut self, a: MainAxisAlignment) -> Self {
+        self.run_alignment = a;
+        self
+    }
+
+    // Gap between the runs
+    pub fn run_spacing(mut self, s: f32) -> Self {
+        self.run_spacing = s;
+        self
+    }
+
+    pub fn cross_alignment(mut self, a: WrapCrossAlignment) -> Self {
+        self.cross_alignment = a;
+        self
+    }
+
+    pub fn text_direction(mut self, d: TextDirection) -> Self {
+        self.text_direction = d;
+        self
+    }
+
+    pub fn vertical_direction(mut self, d: VerticalDirection) -> Self {
+        self.vertical_direction = d;
+        self
+    }
+
+    pub fn b(self) -> Box<Self> {
+        Box::new(self)
+    }
+}
+
+impl Widget for Wrap {
+    fn create_render_object(&self) -> Box<dyn crate::rendering::render_object::RenderObject> {
+        Box::new(RenderWrap::new(
+            self.children
+                .iter()
+                .map(|c| c.create_render_object())
+                .collect(),
+            self.direction,
+            self.alignment,
+            self.spacing,
+            self.run_alignment,
+            self.run_spacing,
+            self.cross_alignment,
+            self.text_direction,
+            self.vertical_direction,
+        ))
+    }
+}